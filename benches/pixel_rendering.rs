@@ -24,15 +24,17 @@ fn pixel_rendering() {
     //     }).flatten().collect::<Vec<_>>()
     // }).flatten().collect::<Vec<_>>();
 
+    let viewport = Viewport::default();
+
     let mut rgba = vec![0; H * W * 4];
 
     rgba.par_chunks_mut(W * 4).enumerate().for_each(|(y, chunks_row)| {
-        let mut row = [0; W * 4];
+        let mut row = vec![0; W * 4];
 
         row.par_chunks_mut(4).enumerate().for_each(|(x, chunks_pixel)| {
             let pixel = Point::new((x, y));
 
-            let iter = MandelIter::new(pixel.into());
+            let iter = MandelIter::new(pixel.to_mandel(&viewport, (W, H)));
 
             let iterations = iter.enumerate().take_while(|(idx, _)| *idx <= ESCAPE_POINT).count();
 
@@ -52,7 +54,7 @@ fn pixel_rendering() {
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("pixel rendering", |b| b.iter(|| pixel_rendering()));
+    c.bench_function("pixel rendering", |b| b.iter(pixel_rendering));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,24 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use rayon::prelude::*;
+use mandelbrust::{simd, utils::*};
+
+/// Iterates every pixel of the default view
+/// with the scalar `MandelIter` path.
+fn scalar(viewport: &Viewport) -> Vec<usize> {
+    (0..W * H)
+        .into_par_iter()
+        .map(|idx| MandelIter::escape_time(viewport.orient(Point::new((idx % W, idx / W)), (W, H)).to_mandel(viewport, (W, H)), ESCAPE_POINT))
+        .collect()
+}
+
+/// Compares the scalar path with the SIMD
+/// kernel, `simd::LANES` pixels at a time.
+fn criterion_benchmark(c: &mut Criterion) {
+    let viewport = Viewport::default();
+
+    c.bench_function("scalar kernel", |b| b.iter(|| scalar(&viewport)));
+    c.bench_function("simd kernel", |b| b.iter(|| simd::iterations(&viewport, (W, H), ESCAPE_POINT)));
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use mandelbrust::{fractal::Mandelbrot, render, subdivision, utils::*};
+
+/// Compares iterating every pixel with the Mariani–Silver
+/// subdivision, on the default view and on a view that
+/// is mostly inside the set.
+fn criterion_benchmark(c: &mut Criterion) {
+    for (name, viewport) in [("default", Viewport::default()), ("interior", Viewport::new(MandelPoint::new((-0.1, 0.1)), 4.0, 0.0))] {
+        c.bench_function(&format!("every pixel ({})", name), |b| b.iter(|| render::escape_results(&Mandelbrot, &viewport, (W, H), ESCAPE_POINT)));
+        c.bench_function(&format!("subdivision ({})", name), |b| b.iter(|| subdivision::escape_results(&Mandelbrot, &viewport, (W, H), ESCAPE_POINT)));
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
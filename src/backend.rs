@@ -0,0 +1,146 @@
+use crate::{renderer::{self, RenderSettings}, utils::*};
+use std::io;
+
+/// The fraction of the height of the
+/// view that is panned at each key press.
+const PAN_STEP: f32 = 0.1;
+
+/// The factor by which the view is zoomed at
+/// each key press or step of the wheel.
+const ZOOM_STEP: f32 = 1.5;
+
+/// The keys `explore()` reacts to, which each
+/// backend translates from its own key codes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    /// Either `+` or `=`, which share the key.
+    Plus,
+    Minus,
+    R,
+    Escape,
+}
+
+/// An input of the user, as reported by a `RenderBackend`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+    /// A key was pressed.
+    Key(Key),
+    /// The wheel was scrolled by the given steps (upwards if positive).
+    Scroll(f32),
+    /// The pointer was moved by the given pixels (with the
+    /// y axis pointing down) while dragging the view.
+    Drag((f32, f32)),
+    /// The frames now have the given size (in pixels),
+    /// e.g. because the window was resized.
+    Resize((usize, usize)),
+    /// The window was closed.
+    Close,
+}
+
+/// A window (or anything else) that can show RGBA frames and
+/// report the inputs of the user, so that the renderer doesn't
+/// depend on the library of any of them.
+pub trait RenderBackend {
+    /// Returns the size (in pixels) of the frames.
+    fn size(&self) -> (usize, usize);
+
+    /// Shows a frame, as RGBA pixels, row by
+    /// row, from top to bottom (see `size()`).
+    fn present(&mut self, rgba: &[u8]) -> io::Result<()>;
+
+    /// Returns the inputs received since the last call,
+    /// without waiting for new ones.
+    fn poll_input(&mut self) -> Vec<Input>;
+}
+
+/// Explores the fractal of `settings` on `backend`, starting from
+/// `viewport`, until the user quits: the arrow keys pan, dragging
+/// moves the view, `+`, `-` and the wheel zoom, `R` resets the view
+/// and `Escape` (or closing the window) quits; the frames have the
+/// size of the backend (the last one it reported, if it was resized),
+/// whatever the size of `settings`, and are rendered into the same
+/// buffer until the size changes; the views that can't be rendered
+/// (see `Viewport::check()`) are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{backend::{explore, Input, Key, RenderBackend}, renderer::RenderSettings, utils::Viewport};
+/// // a backend that presses `+`, is resized, and then closes the window
+/// struct Scripted {
+///     inputs: Vec<Vec<Input>>,
+///     frames: Vec<Vec<u8>>,
+/// }
+///
+/// impl RenderBackend for Scripted {
+///     fn size(&self) -> (usize, usize) {
+///         (30, 20)
+///     }
+///
+///     fn present(&mut self, rgba: &[u8]) -> std::io::Result<()> {
+///         self.frames.push(rgba.to_vec());
+///
+///         Ok(())
+///     }
+///
+///     fn poll_input(&mut self) -> Vec<Input> {
+///         self.inputs.pop().unwrap_or_default()
+///     }
+/// }
+///
+/// let mut backend = Scripted { inputs: vec![vec![Input::Close], vec![Input::Resize((15, 10))], vec![Input::Key(Key::Plus)]], frames: Vec::new() };
+///
+/// explore(&mut backend, Viewport::default(), RenderSettings::default()).unwrap();
+///
+/// assert_eq!(backend.frames.len(), 3);
+/// assert_eq!(backend.frames[0].len(), 30 * 20 * 4);
+/// assert_ne!(backend.frames[0], backend.frames[1]);
+/// assert_eq!(backend.frames[2].len(), 15 * 10 * 4);
+/// ```
+pub fn explore(backend: &mut dyn RenderBackend, viewport: Viewport, mut settings: RenderSettings) -> io::Result<()> {
+    settings.size = backend.size();
+
+    let mut frame = vec![0; settings.size.0 * settings.size.1 * 4];
+    let (mut viewport, mut rendered) = (viewport, None);
+
+    loop {
+        if rendered != Some(viewport) {
+            match renderer::render_into(&mut frame, &viewport, &settings) {
+                Ok(()) => rendered = Some(viewport),
+                Err(err) => {
+                    log::warn!("could not render the view: {}", err);
+
+                    // back to the last view that could be rendered
+                    viewport = rendered.unwrap_or_default();
+                }
+            }
+        }
+
+        backend.present(&frame)?;
+
+        for input in backend.poll_input() {
+            match input {
+                Input::Key(Key::Left) => viewport.pan((-PAN_STEP, 0.0)),
+                Input::Key(Key::Right) => viewport.pan((PAN_STEP, 0.0)),
+                Input::Key(Key::Up) => viewport.pan((0.0, PAN_STEP)),
+                Input::Key(Key::Down) => viewport.pan((0.0, -PAN_STEP)),
+                Input::Key(Key::Plus) => viewport.zoom_by(ZOOM_STEP),
+                Input::Key(Key::Minus) => viewport.zoom_by(1.0 / ZOOM_STEP),
+                Input::Key(Key::R) => viewport = Viewport::default(),
+                Input::Scroll(steps) => viewport.zoom_by(ZOOM_STEP.powf(steps)),
+                Input::Drag(delta) => viewport.drag(delta, settings.size),
+                Input::Resize(size) if size != settings.size => {
+                    settings.size = size;
+                    frame = vec![0; size.0 * size.1 * 4];
+                    rendered = None;
+                }
+                Input::Resize(_) => {}
+                Input::Key(Key::Escape) | Input::Close => return Ok(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! The basic explorer of `backend::explore()` on a window of
+//! `pixels`, with the size and the view of `mandelbrust.toml`
+//! (see `config`), which doesn't need `ggez` at all, so that
+//! it builds with just the `pixels-backend` feature, like
+//!
+//! ```text
+//! cargo run --release --no-default-features --features pixels-backend --bin mandelbrust-pixels
+//! ```
+
+use mandelbrust::{backend, config::Config, locale::Locale, pixels_backend::PixelsBackend, renderer::RenderSettings};
+use std::{path::Path, process};
+
+/// The configuration file read at startup.
+const CONFIG_PATH: &str = "mandelbrust.toml";
+
+/// The environment variable that sets the filter of the logger.
+const LOG_ENV: &str = "MANDELBRUST_LOG";
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::new().filter_or(LOG_ENV, "warn")).init();
+
+    let locale = Locale::from_env();
+
+    let config = Config::load(Path::new(CONFIG_PATH)).unwrap_or_else(|err| {
+        eprintln!("could not load the configuration `{}`: {}", CONFIG_PATH, err);
+
+        process::exit(1);
+    });
+
+    let result = PixelsBackend::new(locale.get("window.title"), config.size()).and_then(|mut window| backend::explore(&mut window, config.viewport, RenderSettings::default()));
+
+    if let Err(err) = result {
+        eprintln!("window error: {}", err);
+
+        process::exit(1);
+    }
+}
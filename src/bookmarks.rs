@@ -0,0 +1,151 @@
+//! The locations saved from the window, with the settings
+//! they were seen with, stored in a TOML file as an array
+//! of `[[bookmark]]` tables, like
+//!
+//! ```toml
+//! [[bookmark]]
+//! name = "bookmark-1"
+//! center = [-0.7453, 0.1127]
+//! zoom = 150.0
+//! rotation = 0.0
+//! iterations = 512
+//! fractal = "mandelbrot"
+//! power = 2
+//! coloring = "smooth-palette"
+//! ```
+//!
+//! where `rotation`, `fractal`, `power` and `coloring`
+//! can be left out.
+
+use crate::utils::*;
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+/// A location saved from the window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// The name of the bookmark, like `bookmark-1`.
+    pub name: String,
+    /// The center of the view (see `Viewport::center()`).
+    pub center: (f64, f64),
+    /// The zoom of the view.
+    pub zoom: f32,
+    /// The rotation of the view.
+    #[serde(default)]
+    pub rotation: f32,
+    /// The maximum number of iterations.
+    pub iterations: usize,
+    /// The name of the fractal (see `fractal::Fractal::name()`).
+    #[serde(default = "default_fractal")]
+    pub fractal: String,
+    /// The exponent of the fractal (see `fractal::Multibrot`).
+    #[serde(default = "default_power")]
+    pub power: u32,
+    /// The name of the coloring (see `coloring::ColorMapper::name()`).
+    #[serde(default = "default_coloring")]
+    pub coloring: String,
+}
+
+fn default_fractal() -> String {
+    "mandelbrot".to_owned()
+}
+
+fn default_power() -> u32 {
+    2
+}
+
+fn default_coloring() -> String {
+    "palette".to_owned()
+}
+
+impl Bookmark {
+    /// Returns the view of the bookmark.
+    pub fn viewport(&self) -> Viewport {
+        Viewport::new(MandelPoint::new(self.center), self.zoom, self.rotation)
+    }
+}
+
+/// The bookmarks, in the order they were saved.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{bookmarks::{Bookmark, Bookmarks}, utils::{Plottable, MandelPoint}};
+/// let mut bookmarks = Bookmarks::parse("[[bookmark]]\nname = \"valley\"\ncenter = [-0.75, 0.125]\nzoom = 20.0\niterations = 512").unwrap();
+///
+/// assert_eq!(bookmarks.len(), 1);
+/// assert_eq!(bookmarks[0].viewport().center(), MandelPoint::new((-0.75, 0.125)));
+/// assert_eq!(bookmarks[0].coloring, "palette");
+///
+/// let name = bookmarks.next_name();
+///
+/// bookmarks.push(Bookmark { name, center: (0.25, 0.0), zoom: 4.0, rotation: 0.0, iterations: 256, fractal: "julia".to_owned(), power: 2, coloring: "grayscale".to_owned() });
+///
+/// assert_eq!(Bookmarks::parse(&bookmarks.to_toml()).unwrap(), bookmarks);
+/// assert_eq!(bookmarks[1].name, "bookmark-2");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bookmarks {
+    #[serde(default, rename = "bookmark")]
+    bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    /// Parses the bookmarks of a TOML file (see the module).
+    pub fn parse(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+
+    /// Returns the bookmarks as the source of a TOML file.
+    pub fn to_toml(&self) -> String {
+        // the bookmarks are plain tables, which are always serializable
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    /// Reads the bookmarks of the file at `path`,
+    /// which are none if the file doesn't exist.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::parse(&source).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the bookmarks to the file at `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    /// Adds `bookmark` after the others.
+    pub fn push(&mut self, bookmark: Bookmark) {
+        self.bookmarks.push(bookmark);
+    }
+
+    /// Returns the number of bookmarks.
+    pub fn len(&self) -> usize {
+        self.bookmarks.len()
+    }
+
+    /// Returns whether there are no bookmarks.
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    /// Returns a name for a new bookmark, like `bookmark-3`,
+    /// which no other bookmark has.
+    pub fn next_name(&self) -> String {
+        (self.len() + 1..)
+            .map(|idx| format!("bookmark-{}", idx))
+            .find(|name| self.bookmarks.iter().all(|bookmark| bookmark.name != *name))
+            .unwrap_or_default()
+    }
+}
+
+impl std::ops::Index<usize> for Bookmarks {
+    type Output = Bookmark;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.bookmarks[idx]
+    }
+}
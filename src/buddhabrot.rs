@@ -0,0 +1,160 @@
+use crate::{tonemap::{self, ToneMap}, utils::*};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use std::fmt;
+
+/// The number of points sampled with the
+/// same generator, in parallel with the others.
+const CHUNK: usize = 4096;
+
+/// The half side of the square the points `c` are sampled
+/// from, which contains the whole Mandelbrot set.
+const SAMPLING_RADIUS: f64 = 2.0;
+
+/// Which orbits are accumulated into the histogram.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Selection {
+    /// The orbits of the points that escape, which
+    /// give the Buddhabrot.
+    Escaping,
+    /// The orbits of the points that never escape,
+    /// which give the Anti-Buddhabrot, tracing the
+    /// attracting cycles of the inside of the set.
+    Bounded,
+}
+
+impl Selection {
+    /// Returns whether an orbit that did
+    /// (or didn't) escape is accumulated.
+    pub fn selects(&self, escaped: bool) -> bool {
+        match self {
+            Selection::Escaping => escaped,
+            Selection::Bounded => !escaped,
+        }
+    }
+}
+
+impl fmt::Display for Selection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Selection::Escaping => write!(f, "buddhabrot"),
+            Selection::Bounded => write!(f, "anti-buddhabrot"),
+        }
+    }
+}
+
+/// Returns the index of the pixel of a screen with the given
+/// `size`, showing `viewport`, that contains `mandelpoint`,
+/// if it's on the screen (see `MandelPoint::to_screen()`).
+fn pixel_index(mandelpoint: MandelPoint, viewport: &Viewport, size: (usize, usize)) -> Option<usize> {
+    let pixel_size = viewport.pixel_size(size);
+
+    let (x, y) = (mandelpoint - viewport.center()).complex_mul(MandelPoint::from_polar(1.0, -viewport.rotation() as f64)).coordinates();
+
+    let (x, y) = (x / pixel_size + size.0 as f64 / 2.0, y / pixel_size + size.1 as f64 / 2.0);
+
+    if x < 0.0 || y < 0.0 || x >= size.0 as f64 || y >= size.1 as f64 {
+        return None;
+    }
+
+    // orient the y coordinate back, like `render::escape_results()`
+    let (x, y) = viewport.orient(Point::new((x as usize, y as usize)), size).coordinates();
+
+    Some(y.min(size.1 - 1) * size.0 + x)
+}
+
+/// Samples `samples` random points `c` (uniformly, with generators
+/// seeded by `seed`), and counts how many times the orbits chosen
+/// by `selection` (iterated up to `max_iterations` times) pass
+/// through each pixel of a screen of the given `size` showing
+/// `viewport`, row by row, from top to bottom.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{buddhabrot::{histogram, Selection}, utils::Viewport};
+/// let buddhabrot = histogram(&Viewport::default(), (30, 20), 64, 10_000, Selection::Escaping, 42);
+/// let anti = histogram(&Viewport::default(), (30, 20), 64, 10_000, Selection::Bounded, 42);
+///
+/// assert_eq!(buddhabrot.len(), 30 * 20);
+/// assert!(buddhabrot.iter().any(|&count| count > 0));
+/// assert!(anti.iter().any(|&count| count > 0));
+/// assert_ne!(buddhabrot, anti);
+///
+/// // the same seed gives the same histogram
+/// assert_eq!(buddhabrot, histogram(&Viewport::default(), (30, 20), 64, 10_000, Selection::Escaping, 42));
+/// ```
+pub fn histogram(viewport: &Viewport, size: (usize, usize), max_iterations: usize, samples: usize, selection: Selection, seed: u64) -> Vec<u32> {
+    let (w, h) = size;
+
+    (0..samples.div_ceil(CHUNK))
+        .into_par_iter()
+        .fold(
+            || vec![0; w * h],
+            |mut counts, chunk| {
+                let mut rng = StdRng::seed_from_u64(seed ^ chunk as u64);
+
+                let mut orbit = Vec::with_capacity(max_iterations);
+
+                for _ in 0..CHUNK.min(samples - chunk * CHUNK) {
+                    let c = MandelPoint::new((rng.gen_range(-SAMPLING_RADIUS..SAMPLING_RADIUS), rng.gen_range(-SAMPLING_RADIUS..SAMPLING_RADIUS)));
+
+                    orbit.clear();
+                    orbit.extend(MandelIter::builder(c).max_iterations(max_iterations).build());
+
+                    // the iterator stops right after the value that escapes
+                    let escaped = orbit.last().is_some_and(|z| z.norm_sqr() > 4.0);
+
+                    if !selection.selects(escaped) {
+                        continue;
+                    }
+
+                    for &z in &orbit {
+                        if let Some(idx) = pixel_index(z, viewport, size) {
+                            counts[idx] += 1;
+                        }
+                    }
+                }
+
+                counts
+            },
+        )
+        .reduce(|| vec![0; w * h], |mut counts, other| {
+            counts.iter_mut().zip(other).for_each(|(count, other)| *count += other);
+
+            counts
+        })
+}
+
+/// Colors a `histogram` (see `histogram()`) by tone mapping its
+/// counts with `operator`, after exposing them so that their
+/// logarithmic average becomes `tonemap::KEY`, like
+/// `tonemap::tone_mapped()`; the pixels no orbit passed
+/// through are black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{buddhabrot::{histogram, to_rgba, Selection}, tonemap::ToneMap, utils::Viewport};
+/// let counts = histogram(&Viewport::default(), (30, 20), 64, 10_000, Selection::Bounded, 42);
+///
+/// let rgba = to_rgba(&counts, ToneMap::Reinhard);
+///
+/// assert_eq!(rgba.len(), 30 * 20 * 4);
+/// assert_eq!(to_rgba(&[0], ToneMap::Reinhard), [0, 0, 0, 255]);
+/// ```
+pub fn to_rgba(histogram: &[u32], operator: ToneMap) -> Vec<u8> {
+    let (sum, count) = histogram.iter().filter(|&&count| count > 0).fold((0.0, 0), |(sum, count), &value| (sum + (value as f32).ln(), count + 1));
+
+    let average = if count == 0 { 1.0 } else { (sum / count as f32).exp() };
+
+    let exposure = tonemap::KEY / average;
+
+    histogram
+        .par_iter()
+        .flat_map_iter(|&count| match count {
+            0 => [0, 0, 0, 255],
+            count => tonemap::gradient(operator.map(count as f32 * exposure)),
+        })
+        .collect()
+}
@@ -0,0 +1,829 @@
+use crate::{kernel::EscapeResult, palette::{Gradient, Palette}, utils::*};
+use std::{fmt, ops::Index};
+
+/// The number of decades of `|dz/dc|` spanned
+/// by the gradient of `DerivativeHeatmap`.
+pub const DERIVATIVE_DECADES: f64 = 12.0;
+
+/// The number of decades of distances from the
+/// boundary spanned by the shades of `InteriorDistance`.
+pub const DISTANCE_DECADES: f64 = 6.0;
+
+/// The number of iterations after which the
+/// hues of `HueCycle` repeat.
+pub const HUE_PERIOD: usize = 32;
+
+/// The distance from the trap (see `OrbitTrap`)
+/// beyond which the pixels are black.
+pub const TRAP_RADIUS: f64 = 1.0;
+
+/// A trait implemented by any algorithm that
+/// maps the result of the iteration of a pixel
+/// to its color.
+pub trait ColorMapper: Send + Sync + fmt::Debug {
+    /// Returns the name of the coloring algorithm.
+    fn name(&self) -> &str;
+
+    /// Returns the RGBA color of a pixel, given the
+    /// `result` of its iteration and the maximum number
+    /// of iterations used to compute it.
+    fn color(&self, result: &EscapeResult, max_iterations: usize) -> [u8; 4];
+
+    /// Returns whether the algorithm colors the inside of the
+    /// set by itself, rather than painting it black and
+    /// leaving it to `InteriorColoring`.
+    fn colors_interior(&self) -> bool {
+        false
+    }
+}
+
+/// Returns the built-in colorings, in the
+/// order they are cycled through in the window.
+pub fn builtin() -> Vec<Box<dyn ColorMapper>> {
+    vec![
+        Box::new(WikipediaPalette::default()),
+        Box::new(DerivativeHeatmap),
+        Box::new(AtomDomain),
+        Box::new(BinaryDecomposition { levels: 1 }),
+        Box::new(BinaryDecomposition { levels: 3 }),
+        Box::new(OrbitStatistic::Mean),
+        Box::new(OrbitStatistic::Variance),
+        Box::new(OrbitStatistic::Minimum),
+        Box::new(OrbitTrap::new(TrapShape::Point)),
+        Box::new(OrbitTrap::new(TrapShape::Line)),
+        Box::new(OrbitTrap::new(TrapShape::Cross)),
+        Box::new(InteriorDistance),
+        Box::new(SmoothPalette::default()),
+        Box::new(Grayscale),
+        Box::new(HueCycle),
+    ]
+}
+
+/// The colorings available at runtime (the built-in ones,
+/// and the ones loaded from plugins and scripts), looked up
+/// by name, in the order they were registered, which is the
+/// order they are cycled through in the window; a coloring
+/// with the name of one already registered replaces it.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::coloring::{ColorMapper, DerivativeHeatmap, Registry};
+/// let mut registry = Registry::builtin();
+///
+/// let count = registry.len();
+///
+/// registry.register(Box::new(DerivativeHeatmap));
+///
+/// assert_eq!(registry.len(), count);
+/// assert_eq!(registry.position("derivative"), Some(1));
+/// assert_eq!(registry[0].name(), "palette");
+/// assert!(registry.find("rainbow").is_none());
+/// ```
+#[derive(Debug, Default)]
+pub struct Registry {
+    mappers: Vec<Box<dyn ColorMapper>>,
+}
+
+impl Registry {
+    /// Returns a registry with the built-in colorings.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+
+        registry.extend(builtin());
+
+        registry
+    }
+
+    /// Registers `mapper`, replacing the coloring
+    /// with the same name, if there is one.
+    pub fn register(&mut self, mapper: Box<dyn ColorMapper>) {
+        match self.position(mapper.name()) {
+            Some(idx) => {
+                log::warn!("the coloring `{}` replaces the one with the same name", mapper.name());
+
+                self.mappers[idx] = mapper;
+            }
+            None => self.mappers.push(mapper),
+        }
+    }
+
+    /// Returns the number of colorings.
+    pub fn len(&self) -> usize {
+        self.mappers.len()
+    }
+
+    /// Returns whether there are no colorings.
+    pub fn is_empty(&self) -> bool {
+        self.mappers.is_empty()
+    }
+
+    /// Returns the index of the coloring called `name`, if any.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.mappers.iter().position(|mapper| mapper.name() == name)
+    }
+
+    /// Returns the coloring called `name`, if any.
+    pub fn find(&self, name: &str) -> Option<&dyn ColorMapper> {
+        self.position(name).map(|idx| &self[idx])
+    }
+
+    /// Returns the names of the colorings.
+    pub fn names(&self) -> Vec<&str> {
+        self.mappers.iter().map(|mapper| mapper.name()).collect()
+    }
+}
+
+impl Extend<Box<dyn ColorMapper>> for Registry {
+    fn extend<T: IntoIterator<Item = Box<dyn ColorMapper>>>(&mut self, mappers: T) {
+        mappers.into_iter().for_each(|mapper| self.register(mapper));
+    }
+}
+
+impl Index<usize> for Registry {
+    type Output = dyn ColorMapper;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        self.mappers[idx].as_ref()
+    }
+}
+
+/// Returns the built-in coloring with the given `name`
+/// (see `ColorMapper::name()`), if there is one.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::coloring::from_name;
+/// assert_eq!(from_name("atom-domain").map(|mapper| mapper.name().to_owned()), Some("atom-domain".to_owned()));
+/// assert!(from_name("rainbow").is_none());
+/// ```
+pub fn from_name(name: &str) -> Option<Box<dyn ColorMapper>> {
+    builtin().into_iter().find(|mapper| mapper.name() == name)
+}
+
+/// The colors of a `Palette`, repeated along the iterations,
+/// which by default are the color gradient used in the [Wikipedia
+/// page of the Mandelbrot set](https://en.wikipedia.org/wiki/Mandelbrot_set)
+/// (see `COLOR_MAP`), which seems to match the color gradient
+/// used in Ultra Fractal; the inside of the set is black.
+///
+/// (*Check [this](https://stackoverflow.com/questions/16500656/which-color-gradient-is-used-to-color-mandelbrot-in-wikipedia)
+/// Stack Overflow question for reference*).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, WikipediaPalette}, kernel::escape, palette::Palette, utils::{Plottable, MandelPoint, COLOR_MAP}};
+/// let result = escape(MandelPoint::new((1.0, 1.0)), 128);
+///
+/// assert_eq!(WikipediaPalette::default().color(&result, 128), COLOR_MAP[result.iterations]);
+///
+/// let palette = Palette::new(vec![[255, 0, 0, 255], [0, 0, 255, 255]]).unwrap();
+///
+/// assert_eq!(WikipediaPalette { palette }.color(&result, 128), [255, 0, 0, 255]);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct WikipediaPalette {
+    /// The colors of the gradient.
+    pub palette: Palette,
+}
+
+impl ColorMapper for WikipediaPalette {
+    fn name(&self) -> &str {
+        "palette"
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        if !result.escaped {
+            return [0, 0, 0, 255];
+        }
+
+        self.palette.color(result.iterations)
+    }
+}
+
+/// A heatmap of the magnitude of the derivative `dz/dc`
+/// at escape (or at the last iteration, for the points
+/// that don't escape), on a logarithmic scale, which
+/// highlights where the dynamics are most sensitive.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, DerivativeHeatmap}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let calm = DerivativeHeatmap.color(&escape(MandelPoint::new((1.0, 1.0)), 128), 128);
+/// let sensitive = DerivativeHeatmap.color(&escape(MandelPoint::new((0.26, 0.0)), 128), 128);
+///
+/// // hotter colors are brighter
+/// assert!(calm.iter().map(|&ch| ch as u32).sum::<u32>() < sensitive.iter().map(|&ch| ch as u32).sum::<u32>());
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct DerivativeHeatmap;
+
+impl ColorMapper for DerivativeHeatmap {
+    fn name(&self) -> &str {
+        "derivative"
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        let magnitude = result.derivative.norm();
+
+        heat((magnitude.max(1.0).log10() / DERIVATIVE_DECADES).min(1.0) as f32)
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// Colors each pixel by its atom domain (the iteration at
+/// which `|z|` attains its minimum, see `EscapeResult::atom_domain`),
+/// which reveals the pattern of the hyperbolic components
+/// ("atoms") of the set; consecutive domains get hues
+/// spaced by the golden angle, so that they stand out.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, AtomDomain}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let main_cardioid = AtomDomain.color(&escape(MandelPoint::new((0.0, 0.0)), 128), 128);
+/// let period_2_bulb = AtomDomain.color(&escape(MandelPoint::new((-1.0, 0.0)), 128), 128);
+///
+/// assert_ne!(main_cardioid, period_2_bulb);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct AtomDomain;
+
+impl ColorMapper for AtomDomain {
+    fn name(&self) -> &str {
+        "atom-domain"
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        // the golden ratio conjugate, which spreads
+        // the hues of consecutive domains evenly
+        const GOLDEN: f32 = 0.618_034;
+
+        let [r, g, b, a] = hue((result.atom_domain as f32 * GOLDEN).fract());
+
+        if result.escaped {
+            // dim the outside of the set
+            [r / 2, g / 2, b / 2, a]
+        } else {
+            [r, g, b, a]
+        }
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// Binary decomposition of the outside of the set: the
+/// argument of `z` at escape is split into `2^levels`
+/// sectors, each one with its own shade of gray, which
+/// produces radial spokes that visualize the external
+/// angles; with a single level, the pixels are white or
+/// black depending on the sign of the imaginary part of `z`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, BinaryDecomposition}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let binary = BinaryDecomposition { levels: 1 };
+///
+/// // `z` escapes at `1 + 3i`
+/// assert_eq!(binary.color(&escape(MandelPoint::new((1.0, 1.0)), 128), 128), [255, 255, 255, 255]);
+/// // `z` escapes at `1 - 3i`
+/// assert_eq!(binary.color(&escape(MandelPoint::new((1.0, -1.0)), 128), 128), [32, 32, 32, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BinaryDecomposition {
+    /// The number of times the argument of `z` is halved.
+    pub levels: u32,
+}
+
+impl Default for BinaryDecomposition {
+    fn default() -> Self {
+        Self { levels: 1 }
+    }
+}
+
+impl ColorMapper for BinaryDecomposition {
+    fn name(&self) -> &str {
+        if self.levels > 1 {
+            "multi-level-binary-decomposition"
+        } else {
+            "binary-decomposition"
+        }
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        if !result.escaped {
+            return [0, 0, 0, 255];
+        }
+
+        let sectors = 1 << self.levels.clamp(1, 8);
+
+        let turn = (result.z.arg() / std::f64::consts::TAU).rem_euclid(1.0);
+
+        let sector = ((turn * sectors as f64) as usize).min(sectors - 1);
+
+        // the first sector (starting from the positive real axis,
+        // counter-clockwise) is the brightest one
+        let shade = (255 - (255 - 32) * sector / (sectors - 1)) as u8;
+
+        [shade, shade, shade, 255]
+    }
+}
+
+/// Colors each pixel by a statistic of the values of `|z|`
+/// along its orbit (see `EscapeResult::orbit`), on a heat
+/// gradient, which gives smooth images that reveal the
+/// structure of both the inside and the outside of the set,
+/// without the bands of the escape time.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, OrbitStatistic}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// // `z` stays at the origin
+/// assert_eq!(OrbitStatistic::Mean.color(&escape(MandelPoint::new((0.0, 0.0)), 128), 128), [0, 0, 0, 255]);
+/// // `z` alternates between `-1` and `0`
+/// assert_ne!(OrbitStatistic::Variance.color(&escape(MandelPoint::new((-1.0, 0.0)), 128), 128), [0, 0, 0, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OrbitStatistic {
+    /// The average distance of `z` from the origin.
+    Mean,
+    /// The variance of `|z|`.
+    Variance,
+    /// The smallest value of `|z|`.
+    Minimum,
+}
+
+impl ColorMapper for OrbitStatistic {
+    fn name(&self) -> &str {
+        match self {
+            OrbitStatistic::Mean => "orbit-mean",
+            OrbitStatistic::Variance => "orbit-variance",
+            OrbitStatistic::Minimum => "orbit-minimum",
+        }
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        // every value of `|z|` is at most 2, so the
+        // variance is at most 1 (and the deviation too)
+        let t = match self {
+            OrbitStatistic::Mean => result.orbit.mean() / 2.0,
+            OrbitStatistic::Variance => result.orbit.variance().sqrt(),
+            OrbitStatistic::Minimum => (result.orbit.min / 2.0).sqrt(),
+        };
+
+        heat(t.clamp(0.0, 1.0))
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// The shapes of the traps of `OrbitTrap`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TrapShape {
+    /// The center of the trap.
+    Point,
+    /// The horizontal line through the center.
+    Line,
+    /// The horizontal and the vertical lines through the center.
+    Cross,
+}
+
+/// Colors each pixel by the smallest distance between the
+/// values of `z` along its orbit (up to the one that escapes,
+/// excluded) and a trap, a shape placed at `center`, on a
+/// heat gradient, from white (on the trap) to black (at
+/// `TRAP_RADIUS` or farther); since the orbits are not
+/// stored, they're iterated again from `z = 0`, with the
+/// `c` and the exponent of the results.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, OrbitTrap, TrapShape}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// // `z` alternates between `-1` and `0`, which is on the trap
+/// let result = escape(MandelPoint::new((-1.0, 0.0)), 128);
+///
+/// assert_eq!(OrbitTrap::new(TrapShape::Point).color(&result, 128), [255, 255, 255, 255]);
+///
+/// let moved = OrbitTrap { center: MandelPoint::new((0.0, 2.0)), ..OrbitTrap::new(TrapShape::Point) };
+///
+/// assert_eq!(moved.color(&result, 128), [0, 0, 0, 255]);
+/// // the vertical line still goes through `0`
+/// assert_eq!(OrbitTrap { shape: TrapShape::Cross, ..moved }.color(&result, 128), [255, 255, 255, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitTrap {
+    /// The shape of the trap.
+    pub shape: TrapShape,
+    /// The position of the trap.
+    pub center: MandelPoint,
+}
+
+impl OrbitTrap {
+    /// Returns a trap of the given `shape` at the origin.
+    pub fn new(shape: TrapShape) -> Self {
+        Self { shape, center: MANDELPOINT_ZERO }
+    }
+
+    /// Returns the distance between `z` and the trap.
+    pub fn distance(&self, z: MandelPoint) -> f64 {
+        let (re, im) = (z - self.center).coordinates();
+
+        match self.shape {
+            TrapShape::Point => (z - self.center).norm(),
+            TrapShape::Line => im.abs(),
+            TrapShape::Cross => re.abs().min(im.abs()),
+        }
+    }
+}
+
+impl ColorMapper for OrbitTrap {
+    fn name(&self) -> &str {
+        match self.shape {
+            TrapShape::Point => "trap-point",
+            TrapShape::Line => "trap-line",
+            TrapShape::Cross => "trap-cross",
+        }
+    }
+
+    fn color(&self, result: &EscapeResult, max_iterations: usize) -> [u8; 4] {
+        let distance = MandelIter::builder(result.c)
+            .power(result.power)
+            .build()
+            .take(result.iterations.min(max_iterations + 1))
+            .take_while(|z| z.norm_sqr() <= 4.0)
+            .map(|z| self.distance(z))
+            .fold(f64::INFINITY, f64::min);
+
+        heat((1.0 - distance / TRAP_RADIUS).clamp(0.0, 1.0) as f32)
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// Shades the inside of the set by its distance from the
+/// boundary (see `EscapeResult::interior_distance_estimate()`)
+/// on a heat gradient, and the outside in grays by the
+/// exterior distance (see `EscapeResult::distance_estimate()`),
+/// both on a logarithmic scale, so that the boundary is dark;
+/// the interior points whose cycle can't be found are black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, InteriorDistance}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let center = InteriorDistance.color(&escape(MandelPoint::new((0.0, 0.0)), 128), 128);
+/// let near_boundary = InteriorDistance.color(&escape(MandelPoint::new((0.24, 0.0)), 128), 128);
+///
+/// // deeper points are brighter
+/// assert!(near_boundary.iter().map(|&ch| ch as u32).sum::<u32>() < center.iter().map(|&ch| ch as u32).sum::<u32>());
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct InteriorDistance;
+
+impl ColorMapper for InteriorDistance {
+    fn name(&self) -> &str {
+        "interior-distance"
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        // the distances are at most about 1, so they
+        // are shaded over the decades below it
+        let shade = |distance: f64| (1.0 + distance.log10() / DISTANCE_DECADES).clamp(0.0, 1.0) as f32;
+
+        if result.escaped {
+            let gray = (result.distance_estimate().map_or(0.0, shade) * 255.0) as u8;
+
+            return [gray, gray, gray, 255];
+        }
+
+        match result.interior_distance_estimate() {
+            Some(distance) => heat(shade(distance)),
+            None => [0, 0, 0, 255],
+        }
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// A `Gradient`, repeated every `period` iterations and sampled
+/// at the normalized iteration count (see
+/// `EscapeResult::smooth_iterations()`), which removes the bands of
+/// the palette colorings; by default, it's the gradient through
+/// the colors of `WikipediaPalette`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, SmoothPalette, WikipediaPalette}, kernel::escape, utils::{Plottable, MandelPoint, COLOR_MAP}};
+/// let result = escape(MandelPoint::new((0.35, 0.5)), 128);
+///
+/// // the color is between the ones of the bands around the point
+/// let band = result.smooth_iterations() as usize;
+/// let (low, high) = (COLOR_MAP[band % 16], COLOR_MAP[(band + 1) % 16]);
+///
+/// let color = SmoothPalette::default().color(&result, 128);
+///
+/// assert!((0..3).all(|ch| (low[ch].min(high[ch])..=low[ch].max(high[ch])).contains(&color[ch])));
+///
+/// // the inside of the set is the same
+/// let inside = escape(MandelPoint::new((0.0, 0.0)), 128);
+///
+/// assert_eq!(SmoothPalette::default().color(&inside, 128), WikipediaPalette::default().color(&inside, 128));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmoothPalette {
+    /// The gradient of the colors.
+    pub gradient: Gradient,
+    /// The number of iterations after which the gradient repeats.
+    pub period: f32,
+}
+
+impl Default for SmoothPalette {
+    fn default() -> Self {
+        Self::from(&Palette::default())
+    }
+}
+
+impl From<&Palette> for SmoothPalette {
+    /// Returns the coloring interpolating between the colors of
+    /// `palette`, like `WikipediaPalette` does without bands.
+    fn from(palette: &Palette) -> Self {
+        Self { gradient: Gradient::from(palette), period: palette.colors().len() as f32 }
+    }
+}
+
+impl ColorMapper for SmoothPalette {
+    fn name(&self) -> &str {
+        "smooth-palette"
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        if !result.escaped {
+            return [0, 0, 0, 255];
+        }
+
+        self.gradient.sample(result.smooth_iterations().max(0.0) / self.period)
+    }
+}
+
+/// The escape time as a shade of gray, from black (the points
+/// that escape at once) to white (the ones that escape at the
+/// iteration cap); the inside of the set is black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, Grayscale}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// assert_eq!(Grayscale.color(&escape(MandelPoint::new((0.0, 0.0)), 128), 128), [0, 0, 0, 255]);
+/// assert_eq!(Grayscale.color(&escape(MandelPoint::new((1.0, 1.0)), 128), 128), [3, 3, 3, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Grayscale;
+
+impl ColorMapper for Grayscale {
+    fn name(&self) -> &str {
+        "grayscale"
+    }
+
+    fn color(&self, result: &EscapeResult, max_iterations: usize) -> [u8; 4] {
+        if !result.escaped {
+            return [0, 0, 0, 255];
+        }
+
+        let gray = (result.iterations as f32 / max_iterations.max(1) as f32 * 255.0).min(255.0) as u8;
+
+        [gray, gray, gray, 255]
+    }
+}
+
+/// The escape time as a fully saturated hue, going around
+/// the color wheel every `HUE_PERIOD` iterations; the
+/// inside of the set is black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{hue, ColorMapper, HueCycle, HUE_PERIOD}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let result = escape(MandelPoint::new((1.0, 1.0)), 128);
+///
+/// assert_eq!(HueCycle.color(&result, 128), hue(result.iterations as f32 / HUE_PERIOD as f32));
+/// assert_eq!(HueCycle.color(&escape(MandelPoint::new((0.0, 0.0)), 128), 128), [0, 0, 0, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct HueCycle;
+
+impl ColorMapper for HueCycle {
+    fn name(&self) -> &str {
+        "hue-cycle"
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        if !result.escaped {
+            return [0, 0, 0, 255];
+        }
+
+        hue((result.iterations % HUE_PERIOD) as f32 / HUE_PERIOD as f32)
+    }
+}
+
+/// The colorings of the inside of the set, applied
+/// by `WithInterior` to the algorithms that leave it
+/// black (see `ColorMapper::colors_interior()`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::InteriorColoring, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let (main_cardioid, period_2_bulb) = (escape(MandelPoint::new((0.0, 0.0)), 128), escape(MandelPoint::new((-1.0, 0.0)), 128));
+///
+/// assert_eq!(InteriorColoring::Black.color(&main_cardioid), [0, 0, 0, 255]);
+/// assert_ne!(InteriorColoring::Period.color(&main_cardioid), InteriorColoring::Period.color(&period_2_bulb));
+/// // `z` stays at the origin
+/// assert_eq!(InteriorColoring::Modulus.color(&main_cardioid), [0, 0, 0, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum InteriorColoring {
+    /// Plain black.
+    #[default]
+    Black,
+    /// A hue for each period of the cycle attracting the orbit
+    /// (see `EscapeResult::period()`), black if it isn't found.
+    Period,
+    /// The final value of `|z|` on a heat gradient.
+    Modulus,
+}
+
+impl InteriorColoring {
+    /// Returns the color of a point that didn't escape.
+    pub fn color(&self, result: &EscapeResult) -> [u8; 4] {
+        // the golden ratio conjugate, like `AtomDomain`
+        const GOLDEN: f32 = 0.618_034;
+
+        match self {
+            InteriorColoring::Black => [0, 0, 0, 255],
+            InteriorColoring::Period => result.period().map_or([0, 0, 0, 255], |period| hue(((period - 1) as f32 * GOLDEN).fract())),
+            InteriorColoring::Modulus => heat((result.z.norm() / 2.0).clamp(0.0, 1.0) as f32),
+        }
+    }
+
+    /// Returns the coloring that follows this one,
+    /// in the order they are cycled through in the window.
+    pub fn next(&self) -> Self {
+        match self {
+            InteriorColoring::Black => InteriorColoring::Period,
+            InteriorColoring::Period => InteriorColoring::Modulus,
+            InteriorColoring::Modulus => InteriorColoring::Black,
+        }
+    }
+}
+
+impl fmt::Display for InteriorColoring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            InteriorColoring::Black => "black",
+            InteriorColoring::Period => "period",
+            InteriorColoring::Modulus => "modulus",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A coloring algorithm with the inside of the set colored
+/// by `interior`, unless the algorithm colors it by itself.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{AtomDomain, ColorMapper, InteriorColoring, WikipediaPalette, WithInterior}, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let (inside, outside) = (escape(MandelPoint::new((-1.0, 0.0)), 128), escape(MandelPoint::new((1.0, 1.0)), 128));
+///
+/// let palette = WithInterior { mapper: &WikipediaPalette::default(), interior: InteriorColoring::Period };
+///
+/// assert_eq!(palette.color(&inside, 128), InteriorColoring::Period.color(&inside));
+/// assert_eq!(palette.color(&outside, 128), WikipediaPalette::default().color(&outside, 128));
+///
+/// let atoms = WithInterior { mapper: &AtomDomain, interior: InteriorColoring::Period };
+///
+/// assert_eq!(atoms.color(&inside, 128), AtomDomain.color(&inside, 128));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WithInterior<'a> {
+    /// The coloring algorithm.
+    pub mapper: &'a dyn ColorMapper,
+    /// The coloring of the inside of the set.
+    pub interior: InteriorColoring,
+}
+
+impl ColorMapper for WithInterior<'_> {
+    fn name(&self) -> &str {
+        self.mapper.name()
+    }
+
+    fn color(&self, result: &EscapeResult, max_iterations: usize) -> [u8; 4] {
+        if result.escaped || self.mapper.colors_interior() {
+            self.mapper.color(result, max_iterations)
+        } else {
+            self.interior.color(result)
+        }
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// A coloring algorithm with the colors of the points that
+/// escape shifted by `offset` iterations, which, advanced at
+/// every frame, makes them flow across the bands ("color cycling")
+/// without iterating the points again.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{ColorMapper, Cycled, WikipediaPalette}, kernel::escape, utils::{Plottable, MandelPoint, COLOR_MAP}};
+/// let (inside, outside) = (escape(MandelPoint::new((0.0, 0.0)), 128), escape(MandelPoint::new((1.0, 1.0)), 128));
+///
+/// let cycled = Cycled { mapper: WikipediaPalette::default(), offset: 3 };
+///
+/// assert_eq!(cycled.color(&outside, 128), COLOR_MAP[outside.iterations + 3]);
+/// assert_eq!(cycled.color(&inside, 128), [0, 0, 0, 255]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Cycled<M> {
+    /// The coloring algorithm.
+    pub mapper: M,
+    /// The number of iterations the colors are shifted by.
+    pub offset: usize,
+}
+
+impl<M: ColorMapper> ColorMapper for Cycled<M> {
+    fn name(&self) -> &str {
+        self.mapper.name()
+    }
+
+    fn color(&self, result: &EscapeResult, max_iterations: usize) -> [u8; 4] {
+        if !result.escaped {
+            return self.mapper.color(result, max_iterations);
+        }
+
+        self.mapper.color(&EscapeResult { iterations: result.iterations.wrapping_add(self.offset), ..*result }, max_iterations)
+    }
+
+    fn colors_interior(&self) -> bool {
+        self.mapper.colors_interior()
+    }
+}
+
+/// Returns the fully saturated color of the
+/// hue `t` (between `0.0` and `1.0`, which are red).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::coloring::hue;
+/// assert_eq!(hue(0.0), [255, 0, 0, 255]);
+/// assert_eq!(hue(1.0 / 3.0), [0, 255, 0, 255]);
+/// ```
+pub fn hue(t: f32) -> [u8; 4] {
+    let channel = |offset: f32| {
+        let distance = ((t - offset).rem_euclid(1.0) * 6.0 - 3.0).abs();
+
+        ((distance - 1.0).clamp(0.0, 1.0) * 255.0).round() as u8
+    };
+
+    [channel(0.0), channel(1.0 / 3.0), channel(2.0 / 3.0), 255]
+}
+
+/// Returns the color of `t` (between `0.0` and `1.0`) on a
+/// heat gradient, going from black to red, yellow and white.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::coloring::heat;
+/// assert_eq!(heat(0.0), [0, 0, 0, 255]);
+/// assert_eq!(heat(1.0), [255, 255, 255, 255]);
+/// ```
+pub fn heat(t: f32) -> [u8; 4] {
+    let channel = |offset: f32| ((t * 3.0 - offset).clamp(0.0, 1.0) * 255.0) as u8;
+
+    [channel(0.0), channel(1.0), channel(2.0), 255]
+}
@@ -0,0 +1,110 @@
+//! The configuration of the window, read at startup from a
+//! TOML file, like
+//!
+//! ```toml
+//! width = 1280
+//! height = 720
+//! viewport = "center=-0.75+0.1i, zoom=20"
+//! max_iterations = 512
+//! palette = "palettes/fire.map"
+//! threads = 4
+//!
+//! [keys]
+//! Z = "Q"
+//! X = "E"
+//! ```
+//!
+//! where every key can be left out, keeping its default (see
+//! `Config::default()`); the view is given in the format of the
+//! `[VIEWPORT]` argument (see `parse`), and each of the `keys`
+//! is a key that acts as the control key it's bound to.
+
+use crate::utils::*;
+use serde::{de, Deserialize, Deserializer};
+use std::{collections::BTreeMap, fs, io, path::{Path, PathBuf}};
+
+/// The configuration of the window.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{config::Config, utils::{Plottable, MandelPoint, Viewport, ESCAPE_POINT}};
+/// let config = Config::parse("width = 640\nviewport = \"re=-0.75 im=0.125 zoom=20\"\n\n[keys]\nZ = \"Q\"").unwrap();
+///
+/// assert_eq!((config.width, config.height), (640, Config::default().height));
+/// assert_eq!(config.viewport, Viewport::new(MandelPoint::new((-0.75, 0.125)), 20.0, 0.0));
+/// assert_eq!(config.max_iterations, ESCAPE_POINT);
+/// assert_eq!(config.keys["Z"], "Q");
+/// assert_eq!(config.threads, None);
+///
+/// assert!(Config::parse("viewport = \"zoom=-1\"").is_err());
+/// assert!(Config::parse("colour = \"red\"").is_err());
+/// assert_eq!(Config::parse("").unwrap(), Config::default());
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The width (in pixels) of the window.
+    pub width: usize,
+    /// The height (in pixels) of the window.
+    pub height: usize,
+    /// The view the window starts from.
+    #[serde(deserialize_with = "viewport")]
+    pub viewport: Viewport,
+    /// The maximum number of iterations the window starts with.
+    pub max_iterations: usize,
+    /// The palette file of the palette colorings
+    /// (like `--palette`, which replaces it), if any.
+    pub palette: Option<PathBuf>,
+    /// The number of threads the pixels are split among,
+    /// if not one for each core.
+    pub threads: Option<usize>,
+    /// The keys bound to the controls, each one acting as the
+    /// key of the control it's bound to (e.g. `Z = "Q"`), by the
+    /// names of the keys (see the controls).
+    pub keys: BTreeMap<String, String>,
+}
+
+/// Parses a `Viewport` from a string of TOML.
+fn viewport<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Viewport, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+}
+
+impl Default for Config {
+    /// Returns the configuration of a `W`x`H` window, starting from
+    /// the default `Viewport` with `ESCAPE_POINT` iterations, with
+    /// no palette file, a thread for each core and no key bindings.
+    fn default() -> Self {
+        Self {
+            width: W,
+            height: H,
+            viewport: Viewport::default(),
+            max_iterations: ESCAPE_POINT,
+            palette: None,
+            threads: None,
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Parses the configuration of a TOML file (see the module).
+    pub fn parse(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+
+    /// Reads the configuration of the file at `path`,
+    /// which is the default one if the file doesn't exist.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::parse(&source).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the size (in pixels) of the window.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+}
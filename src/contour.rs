@@ -0,0 +1,114 @@
+use rayon::prelude::*;
+
+/// A segment of a contour line, given by
+/// its two endpoints in screen coordinates.
+pub type Segment = ((f32, f32), (f32, f32));
+
+/// The pairs of edges of a cell crossed by
+/// the contour line, indexed by the marching
+/// squares case of the cell; the edges are
+/// `0` (top), `1` (right), `2` (bottom) and `3` (left).
+const CASES: [&[(usize, usize)]; 16] = [
+    &[],
+    &[(3, 2)],
+    &[(2, 1)],
+    &[(3, 1)],
+    &[(0, 1)],
+    &[(3, 0), (2, 1)],
+    &[(0, 2)],
+    &[(3, 0)],
+    &[(3, 0)],
+    &[(0, 2)],
+    &[(0, 1), (3, 2)],
+    &[(0, 1)],
+    &[(3, 1)],
+    &[(2, 1)],
+    &[(3, 2)],
+    &[],
+];
+
+/// Returns the segments of the contour line that
+/// separates the pixels with at least `level`
+/// iterations from the others, computed with the
+/// marching squares algorithm over the `iterations`
+/// buffer of a screen with the given `size`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::contour::contour_lines;
+/// let iterations = [
+///     1, 1, 1,
+///     1, 5, 1,
+///     1, 1, 1,
+/// ];
+///
+/// // a closed line around the center pixel
+/// assert_eq!(contour_lines(&iterations, (3, 3), 5).len(), 4);
+/// assert!(contour_lines(&iterations, (3, 3), 6).is_empty());
+/// ```
+pub fn contour_lines(iterations: &[usize], size: (usize, usize), level: usize) -> Vec<Segment> {
+    let (w, h) = size;
+
+    // crossing the threshold halfway between two
+    // integer values keeps the lines between pixels
+    let threshold = level as f32 - 0.5;
+
+    (0..h.saturating_sub(1))
+        .into_par_iter()
+        .flat_map_iter(|y| {
+            let mut segments = Vec::new();
+
+            for x in 0..w.saturating_sub(1) {
+                let corners = [
+                    iterations[y * w + x] as f32,
+                    iterations[y * w + x + 1] as f32,
+                    iterations[(y + 1) * w + x + 1] as f32,
+                    iterations[(y + 1) * w + x] as f32,
+                ];
+
+                let case = corners
+                    .iter()
+                    .fold(0, |case, &corner| (case << 1) | (corner >= threshold) as usize);
+
+                for &(from, to) in CASES[case] {
+                    segments.push((
+                        edge_point(x, y, &corners, from, threshold),
+                        edge_point(x, y, &corners, to, threshold),
+                    ));
+                }
+            }
+
+            segments
+        })
+        .collect()
+}
+
+/// Returns the segments of the contour lines
+/// of every level in `levels` (see `contour_lines()`).
+pub fn contours(iterations: &[usize], size: (usize, usize), levels: &[usize]) -> Vec<Segment> {
+    levels
+        .iter()
+        .flat_map(|&level| contour_lines(iterations, size, level))
+        .collect()
+}
+
+/// Returns the point where the contour line crosses
+/// the given `edge` of the cell whose top-left pixel
+/// is `(x, y)`, interpolating the values of the
+/// `corners` (in clockwise order, starting from the
+/// top-left one); pixels are centered on half-integers.
+fn edge_point(x: usize, y: usize, corners: &[f32; 4], edge: usize, threshold: f32) -> (f32, f32) {
+    let (a, b) = (corners[edge], corners[(edge + 1) % 4]);
+
+    let t = if a == b { 0.5 } else { (threshold - a) / (b - a) };
+
+    let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+
+    match edge {
+        0 => (x + t, y),
+        1 => (x + 1.0, y + t),
+        2 => (x + 1.0 - t, y + 1.0),
+        _ => (x, y + 1.0 - t),
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{fixed::Fixed, precision::Real};
+use std::{fmt, ops::{Add, Mul, Neg, Sub}};
+
+/// A double-double number, the unevaluated sum of two
+/// `f64` that don't overlap, which has about 106 bits
+/// of mantissa (32 decimal digits) while only needing
+/// a few floating-point operations for each operation.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::double_double::DoubleDouble;
+/// let x = DoubleDouble::from(1.5);
+///
+/// assert_eq!((x * x).to_f64(), 2.25);
+/// assert_eq!((x - DoubleDouble::from(2.0)).to_f64(), -0.5);
+///
+/// // the sum keeps the digits that `f64` would lose
+/// let tiny = DoubleDouble::from(1e-25);
+///
+/// assert_eq!((DoubleDouble::from(1.0) + tiny - DoubleDouble::from(1.0)).to_f64(), 1e-25);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct DoubleDouble {
+    high: f64,
+    low: f64,
+}
+
+impl DoubleDouble {
+    /// Returns the `f64` closest to the number.
+    pub fn to_f64(self) -> f64 {
+        self.high + self.low
+    }
+
+    /// Returns the sum of `high` and `low`, renormalized
+    /// so that `low` is below the precision of `high`.
+    fn normalized(high: f64, low: f64) -> Self {
+        let sum = high + low;
+
+        Self { high: sum, low: low - (sum - high) }
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(value: f64) -> Self {
+        Self { high: value, low: 0.0 }
+    }
+}
+
+impl fmt::Display for DoubleDouble {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f64(), f)
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        // the exact sum of the high parts (Knuth's two-sum)
+        let sum = self.high + other.high;
+        let virtual_other = sum - self.high;
+        let error = (self.high - (sum - virtual_other)) + (other.high - virtual_other);
+
+        Self::normalized(sum, error + self.low + other.low)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { high: -self.high, low: -self.low }
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        // the exact product of the high parts, through the fused multiply-add
+        let product = self.high * other.high;
+        let error = self.high.mul_add(other.high, -product);
+
+        Self::normalized(product, error + self.high * other.low + self.low * other.high)
+    }
+}
+
+impl Real for DoubleDouble {
+    fn from_f32(value: f32) -> Self {
+        Self::from(value as f64)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Self::from(value)
+    }
+
+    fn from_fixed(value: Fixed) -> Self {
+        // the bits of `value` that its `f64` rounding drops
+        let high = value.to_f64();
+
+        Self::from(high) + Self::from((value - Fixed::from_f64(high)).to_f64())
+    }
+
+    fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        DoubleDouble::to_f64(self)
+    }
+}
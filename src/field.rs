@@ -0,0 +1,75 @@
+use crate::{contour::Segment, kernel::EscapeResult, utils::*};
+
+/// The angle between the shaft of an arrow and each side of its head.
+const HEAD_ANGLE: f32 = std::f32::consts::PI / 6.0;
+
+/// The length of the head of an arrow,
+/// relative to the length of the arrow.
+const HEAD_LENGTH: f32 = 0.35;
+
+/// Returns the segments of the arrows that show the argument
+/// of `z` at escape, one every `spacing` pixels, centered on
+/// their pixel, given the `results` of the iteration of a
+/// screen with the given `size` (whose rows go from top to
+/// bottom) showing `viewport`; no arrow is drawn over the
+/// points that didn't escape.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{field::escape_arrows, kernel::escape, utils::{Plottable, MandelPoint, Viewport}};
+/// let results = [
+///     escape(MandelPoint::new((3.0, 0.0)), 128),
+///     escape(MandelPoint::new((0.0, 0.0)), 128),
+/// ];
+///
+/// let arrows = escape_arrows(&results, (2, 1), 1, 10.0, &Viewport::default());
+///
+/// // the shaft and the head of the first arrow, which points right
+/// assert_eq!(arrows.len(), 3);
+/// assert_eq!(arrows[0], ((-4.5, 0.5), (5.5, 0.5)));
+/// ```
+pub fn escape_arrows(results: &[EscapeResult], size: (usize, usize), spacing: usize, length: f32, viewport: &Viewport) -> Vec<Segment> {
+    let (w, h) = size;
+    let spacing = spacing.max(1);
+
+    // the y axis of the screen points downwards, so the
+    // arrows are mirrored in the math orientation
+    let mirror = match viewport.orientation() {
+        Orientation::Math => -1.0,
+        Orientation::Screen => 1.0,
+    };
+
+    let mut segments = Vec::new();
+
+    for y in (spacing / 2..h).step_by(spacing) {
+        for x in (spacing / 2..w).step_by(spacing) {
+            let result = &results[y * w + x];
+
+            if !result.escaped {
+                continue;
+            }
+
+            // the screen is rotated in the opposite direction of the view
+            let angle = result.z.arg() as f32 - viewport.rotation();
+
+            let center = (x as f32 + 0.5, y as f32 + 0.5);
+            let direction = (angle.cos() * length / 2.0, mirror * angle.sin() * length / 2.0);
+
+            let tail = (center.0 - direction.0, center.1 - direction.1);
+            let tip = (center.0 + direction.0, center.1 + direction.1);
+
+            segments.push((tail, tip));
+
+            for side in [-HEAD_ANGLE, HEAD_ANGLE] {
+                let back = angle + std::f32::consts::PI + side;
+
+                let end = (tip.0 + back.cos() * length * HEAD_LENGTH, tip.1 + mirror * back.sin() * length * HEAD_LENGTH);
+
+                segments.push((tip, end));
+            }
+        }
+    }
+
+    segments
+}
@@ -0,0 +1,329 @@
+use crate::precision::Real;
+use std::{convert::TryFrom, error, fmt, ops::{Add, Mul, Neg, Sub}, str::FromStr};
+
+/// The number of fractional bits of a `Fixed`, which
+/// leaves 7 bits to the integer part (and one to the
+/// sign), enough for the values of `z` before they escape.
+pub const FRACTION_BITS: u32 = 120;
+
+/// The number of decimal digits that tell apart
+/// any two values of `Fixed` (see its `Display`
+/// implementation).
+pub const DIGITS: usize = 37;
+
+/// A signed fixed-point number of 128 bits (see `FRACTION_BITS`),
+/// whose resolution of about `7.5e-37` is far finer than the one
+/// of `f32`, and whose arithmetic only needs integer operations.
+///
+/// The numbers are in the range `(-128, 128)`: `from_f64()` saturates
+/// the values outside of it, and the operators wrap around when their
+/// result is outside of it (like the ones of the integers in release
+/// builds), unlike `saturating_add()`, `saturating_sub()` and
+/// `saturating_mul()`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::fixed::Fixed;
+/// let x = Fixed::from_f32(1.5);
+///
+/// assert_eq!((x * x).to_f32(), 2.25);
+/// assert_eq!((x - Fixed::from_f32(2.0)).to_f32(), -0.5);
+///
+/// // the sum keeps the digits that `f32` would lose
+/// let tiny = Fixed::from_f32(1e-20);
+///
+/// assert_eq!((Fixed::from_f32(1.0) + tiny - Fixed::from_f32(1.0)).to_f32(), 1e-20);
+///
+/// // the range ends at `128`
+/// let big = Fixed::from_f32(16.0);
+///
+/// assert_eq!(Fixed::from_f32(1000.0), Fixed::from_f32(128.0));
+/// assert_eq!((big * big).to_f32(), 0.0);
+/// assert!(big.saturating_mul(big).to_f32() > 127.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Fixed(i128);
+
+impl Fixed {
+    /// Returns the fixed-point number closest to `value`
+    /// (saturating at the bounds of the integer part).
+    pub fn from_f32(value: f32) -> Self {
+        // every `f32` is exact in an `f64`
+        Self::from_f64(value as f64)
+    }
+
+    /// Returns the fixed-point number closest to `value`
+    /// (saturating at the bounds of the integer part).
+    pub const fn from_f64(value: f64) -> Self {
+        // the product by a power of two is exact
+        Self((value * (1u128 << FRACTION_BITS) as f64) as i128)
+    }
+
+    /// Returns the `f32` closest to the number.
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    /// Returns the `f64` closest to the number.
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (FRACTION_BITS as f64).exp2()
+    }
+
+    /// Returns the sum of the two numbers, saturating
+    /// at the bounds of the integer part.
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    /// Returns the difference of the two numbers,
+    /// saturating at the bounds of the integer part.
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    /// Returns the product of the two numbers, saturating
+    /// at the bounds of the integer part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::fixed::Fixed;
+    /// let x = Fixed::from_f32(-12.5);
+    ///
+    /// assert_eq!(x.saturating_mul(Fixed::from_f32(2.0)), x * Fixed::from_f32(2.0));
+    /// assert_eq!(x.saturating_mul(x), Fixed::from_f32(128.0));
+    /// assert_eq!(x.saturating_mul(-x), -Fixed::from_f32(128.0));
+    /// ```
+    pub fn saturating_mul(self, other: Self) -> Self {
+        let (negative, high, low) = self.wide_mul(other);
+
+        // the magnitude must fit in the 127 bits below the sign
+        if high >> (127 - (128 - FRACTION_BITS)) != 0 {
+            return Self(if negative { i128::MIN + 1 } else { i128::MAX });
+        }
+
+        Self::from_wide(negative, high, low)
+    }
+
+    /// Returns the sign and the two halves of the (256-bit)
+    /// product of the magnitudes of the two numbers.
+    fn wide_mul(self, other: Self) -> (bool, u128, u128) {
+        const LOW: u128 = u64::MAX as u128;
+
+        let negative = (self.0 < 0) != (other.0 < 0);
+        let (a, b) = (self.0.unsigned_abs(), other.0.unsigned_abs());
+
+        // the product of the magnitudes, on 256 bits,
+        // from the products of their halves
+        let (a_high, a_low, b_high, b_low) = (a >> 64, a & LOW, b >> 64, b & LOW);
+
+        let (middle, middle_carry) = (a_low * b_high).overflowing_add(a_high * b_low);
+        let (low, low_carry) = (a_low * b_low).overflowing_add(middle << 64);
+
+        let high = (a_high * b_high).wrapping_add(middle >> 64).wrapping_add((middle_carry as u128) << 64).wrapping_add(low_carry as u128);
+
+        (negative, high, low)
+    }
+
+    /// Returns the number whose magnitude is the given
+    /// (256-bit) product, wrapping around its range.
+    fn from_wide(negative: bool, high: u128, low: u128) -> Self {
+        // drop the extra fractional bits
+        let magnitude = ((high << (128 - FRACTION_BITS)) | (low >> FRACTION_BITS)) as i128;
+
+        Self(if negative { magnitude.wrapping_neg() } else { magnitude })
+    }
+
+    /// Returns the number in decimal, rounded
+    /// to the given number of `digits`.
+    fn to_decimal(self, digits: usize) -> String {
+        let mask = (1u128 << FRACTION_BITS) - 1;
+        let magnitude = self.0.unsigned_abs();
+
+        let (mut integer, mut fraction) = (magnitude >> FRACTION_BITS, magnitude & mask);
+        let mut decimals = Vec::with_capacity(digits);
+
+        for _ in 0..digits {
+            fraction *= 10;
+            decimals.push((fraction >> FRACTION_BITS) as u8);
+            fraction &= mask;
+        }
+
+        // round half up, carrying into the integer part
+        if fraction >= 1 << (FRACTION_BITS - 1) {
+            let carry = decimals.iter_mut().rev().all(|digit| {
+                *digit = (*digit + 1) % 10;
+                *digit == 0
+            });
+
+            integer += carry as u128;
+        }
+
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let decimals: String = decimals.iter().map(|&digit| (b'0' + digit) as char).collect();
+
+        match digits {
+            0 => format!("{}{}", sign, integer),
+            _ => format!("{}{}.{}", sign, integer, decimals),
+        }
+    }
+}
+
+impl From<i8> for Fixed {
+    fn from(value: i8) -> Self {
+        Self((value as i128) << FRACTION_BITS)
+    }
+}
+
+/// The `Display` implementation of `Fixed` shows the number
+/// in decimal, rounded to the precision, if one is specified,
+/// or with the fewest digits (at most `DIGITS`) that are parsed
+/// back into the same number.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::fixed::Fixed;
+/// let x: Fixed = "-0.743643887037158704752191506114774".parse().unwrap();
+///
+/// assert_eq!(x.to_string(), "-0.743643887037158704752191506114774");
+/// assert_eq!(format!("{:.3}", x), "-0.744");
+/// assert_eq!(Fixed::from_f32(0.5).to_string(), "0.5");
+/// assert_eq!(format!("{:.2}", Fixed::from_f32(9.999)), "10.00");
+/// ```
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimal = match f.precision() {
+            Some(precision) => self.to_decimal(precision),
+            None => (0..DIGITS).map(|digits| self.to_decimal(digits)).find(|decimal| decimal.parse() == Ok(*self)).unwrap_or_else(|| self.to_decimal(DIGITS)),
+        };
+
+        f.write_str(&decimal)
+    }
+}
+
+/// The error returned when a string is not a number
+/// within the range of `Fixed` (see `FRACTION_BITS`).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ParseFixedError;
+
+impl fmt::Display for ParseFixedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not a number between -128 and 128")
+    }
+}
+
+impl error::Error for ParseFixedError {}
+
+/// The `FromStr` implementation of `Fixed` reads the decimal
+/// numbers (like `-0.75`) with all the digits it can hold, and
+/// any other number (like `1e-3`) with the precision of `f64`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::fixed::{Fixed, ParseFixedError};
+/// let x: Fixed = "1.00000000000000000000000000001".parse().unwrap();
+///
+/// // far below the precision of `f64`
+/// assert!(x > Fixed::from_f64(1.0));
+/// assert_eq!("-.5".parse(), Ok(Fixed::from_f32(-0.5)));
+/// assert_eq!("1e-3".parse(), Ok(Fixed::from_f64(1e-3)));
+/// assert_eq!("128".parse::<Fixed>(), Err(ParseFixedError));
+/// assert_eq!("0.5i".parse::<Fixed>(), Err(ParseFixedError));
+/// ```
+impl FromStr for Fixed {
+    type Err = ParseFixedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let decimal = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+
+        if !(decimal(integer) && decimal(fraction)) || integer.is_empty() && fraction.is_empty() {
+            let value: f64 = s.parse().map_err(|_| ParseFixedError)?;
+
+            return if value.abs() < 128.0 { Ok(Self::from_f64(value)) } else { Err(ParseFixedError) };
+        }
+
+        let integer: u128 = if integer.is_empty() { 0 } else { integer.parse().map_err(|_| ParseFixedError)? };
+
+        if integer >= 128 {
+            return Err(ParseFixedError);
+        }
+
+        // the digits are shifted in from the last one,
+        // dividing by 10 (rounded to nearest) each time
+        let fraction = fraction.bytes().rev().fold(0, |fraction, digit| (fraction + (((digit - b'0') as u128) << FRACTION_BITS) + 5) / 10);
+
+        let magnitude = i128::try_from((integer << FRACTION_BITS) + fraction).map_err(|_| ParseFixedError)?;
+
+        Ok(Self(if negative { -magnitude } else { magnitude }))
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0.wrapping_add(other.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0.wrapping_sub(other.0))
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let (negative, high, low) = self.wide_mul(other);
+
+        Self::from_wide(negative, high, low)
+    }
+}
+
+impl Real for Fixed {
+    fn from_f32(value: f32) -> Self {
+        Fixed::from_f32(value)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Fixed::from_f64(value)
+    }
+
+    fn from_fixed(value: Fixed) -> Self {
+        value
+    }
+
+    fn norm_sqr(re: Self, im: Self) -> Self {
+        re.saturating_mul(re).saturating_add(im.saturating_mul(im))
+    }
+
+    fn to_f32(self) -> f32 {
+        Fixed::to_f32(self)
+    }
+
+    fn to_f64(self) -> f64 {
+        Fixed::to_f64(self)
+    }
+}
@@ -0,0 +1,417 @@
+//! Formulas of the iteration of new escape-time fractals,
+//! like `z*z*z + c*z + c`, which are parsed once and compiled
+//! into closures, so that they are much faster than the
+//! scripts (see `script`), although much less flexible.
+//!
+//! A formula is an expression of `z` and `c`, made of:
+//! - real numbers (like `0.5` or `1e-3`) and the imaginary unit `i`
+//! - `+`, `-`, `*`, `/` and parentheses
+//! - `^` followed by an integer exponent, up to `MAX_EXPONENT` (like `z^3`)
+//! - `conj(x)`, the conjugate of `x`, and `abs(x)`, which takes
+//!   the absolute values of both parts of `x` (as in the Burning Ship)
+//!
+//! # Examples
+//!
+//! ```
+//! # pub use mandelbrust::{formula::Formula, fractal::{Fractal, Mandelbrot}, utils::{Plottable, MandelPoint}};
+//! let formula: Formula = "z^2 + c".parse().unwrap();
+//!
+//! let c = MandelPoint::new((-0.75, 0.1));
+//!
+//! assert_eq!(formula.escape(c, 128).iterations, Mandelbrot.escape(c, 128).iterations);
+//!
+//! let burning_ship: Formula = "abs(z)^2 + c".parse().unwrap();
+//!
+//! assert_eq!(burning_ship.iterate(MandelPoint::new((-1.0, -1.0)), MandelPoint::new((0.0, 0.0))), MandelPoint::new((0.0, 2.0)));
+//! ```
+
+use crate::{fractal::Fractal, kernel::EscapeResult, utils::*};
+use std::{error, fmt, str::FromStr};
+
+/// The largest exponent of `^`.
+pub const MAX_EXPONENT: u32 = 64;
+
+/// The error returned when a formula can't be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormulaError {
+    /// The formula ended where a value was expected.
+    UnexpectedEnd,
+    /// A character was not expected at the given position (in bytes).
+    UnexpectedChar(char, usize),
+    /// A name is neither `z`, `c`, `i` nor a function.
+    UnknownName(String),
+    /// A number is not valid.
+    InvalidNumber(String),
+    /// An exponent is not an integer up to `MAX_EXPONENT`.
+    InvalidExponent(String),
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormulaError::UnexpectedEnd => write!(f, "the formula ended unexpectedly"),
+            FormulaError::UnexpectedChar(c, position) => write!(f, "unexpected `{}` at position {}", c, position),
+            FormulaError::UnknownName(name) => write!(f, "unknown name `{}` (expected `z`, `c`, `i`, `conj` or `abs`)", name),
+            FormulaError::InvalidNumber(number) => write!(f, "`{}` is not a valid number", number),
+            FormulaError::InvalidExponent(exponent) => write!(f, "`{}` is not a valid exponent (expected an integer up to {})", exponent, MAX_EXPONENT),
+        }
+    }
+}
+
+impl error::Error for FormulaError {}
+
+/// A parsed formula, before it's compiled.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Z,
+    C,
+    Constant(MandelPoint),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, u32),
+    Conj(Box<Expr>),
+    Abs(Box<Expr>),
+}
+
+/// A compiled formula, which returns
+/// the next value of `z`, given `z` and `c`.
+type Compiled = Box<dyn Fn(MandelPoint, MandelPoint) -> MandelPoint + Send + Sync>;
+
+impl Expr {
+    /// Returns the closure that evaluates the expression.
+    fn compile(&self) -> Compiled {
+        fn binary(a: &Expr, b: &Expr, op: fn(MandelPoint, MandelPoint) -> MandelPoint) -> Compiled {
+            let (a, b) = (a.compile(), b.compile());
+
+            Box::new(move |z, c| op(a(z, c), b(z, c)))
+        }
+
+        fn unary(a: &Expr, op: impl Fn(MandelPoint) -> MandelPoint + Send + Sync + 'static) -> Compiled {
+            let a = a.compile();
+
+            Box::new(move |z, c| op(a(z, c)))
+        }
+
+        match self {
+            Expr::Z => Box::new(|z, _| z),
+            Expr::C => Box::new(|_, c| c),
+            &Expr::Constant(k) => Box::new(move |_, _| k),
+            Expr::Neg(a) => unary(a, |x| x * -1.0),
+            Expr::Add(a, b) => binary(a, b, |x, y| x + y),
+            Expr::Sub(a, b) => binary(a, b, |x, y| x - y),
+            Expr::Mul(a, b) => binary(a, b, |x, y| x.complex_mul(y)),
+            Expr::Div(a, b) => binary(a, b, |x, y| x.complex_div(y)),
+            &Expr::Pow(ref a, exponent) => unary(a, move |x| (0..exponent).fold(MandelPoint::new((1.0, 0.0)), |power, _| power.complex_mul(x))),
+            Expr::Conj(a) => unary(a, |x| x.conj()),
+            Expr::Abs(a) => unary(a, |x| {
+                let (re, im) = x.coordinates();
+
+                MandelPoint::new((re.abs(), im.abs()))
+            }),
+        }
+    }
+
+    /// Returns the degree of the expression in `z`, if it's a
+    /// polynomial in `z` (possibly divided by a constant).
+    fn degree(&self) -> Option<u32> {
+        match self {
+            Expr::Z => Some(1),
+            Expr::C | Expr::Constant(_) => Some(0),
+            Expr::Neg(a) | Expr::Conj(a) | Expr::Abs(a) => a.degree(),
+            Expr::Add(a, b) | Expr::Sub(a, b) => Some(a.degree()?.max(b.degree()?)),
+            Expr::Mul(a, b) => Some(a.degree()? + b.degree()?),
+            Expr::Div(a, b) => a.degree().filter(|_| b.degree() == Some(0)),
+            Expr::Pow(a, exponent) => Some(a.degree()? * exponent),
+        }
+    }
+
+    /// Returns whether the conjugates of `z` and `c` give the
+    /// conjugate value, as for every expression with real
+    /// constants and without `abs()`.
+    fn is_symmetric(&self) -> bool {
+        match self {
+            Expr::Z | Expr::C => true,
+            Expr::Constant(k) => k.coordinates().1 == 0.0,
+            Expr::Neg(a) | Expr::Conj(a) | Expr::Pow(a, _) => a.is_symmetric(),
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) | Expr::Div(a, b) => a.is_symmetric() && b.is_symmetric(),
+            Expr::Abs(_) => false,
+        }
+    }
+}
+
+/// A recursive descent parser of the formulas, whose grammar is
+///
+/// ```text
+/// sum     = product (("+" | "-") product)*
+/// product = unary (("*" | "/") unary)*
+/// unary   = "-" unary | power
+/// power   = atom ("^" integer)?
+/// atom    = number | name | name "(" sum ")" | "(" sum ")"
+/// ```
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the next character, skipping the whitespace.
+    fn peek(&mut self) -> Option<char> {
+        let skipped = self.source[self.position..].len() - self.source[self.position..].trim_start().len();
+
+        self.position += skipped;
+
+        self.source[self.position..].chars().next()
+    }
+
+    /// Consumes the next character if it's `expected`.
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+
+        if found {
+            self.position += expected.len_utf8();
+        }
+
+        found
+    }
+
+    /// Consumes the next character, which must be `expected`.
+    fn expect(&mut self, expected: char) -> Result<(), FormulaError> {
+        match self.peek() {
+            _ if self.eat(expected) => Ok(()),
+            Some(c) => Err(FormulaError::UnexpectedChar(c, self.position)),
+            None => Err(FormulaError::UnexpectedEnd),
+        }
+    }
+
+    /// Consumes the longest sequence of characters satisfying `accept`.
+    fn take_while(&mut self, mut accept: impl FnMut(char) -> bool) -> &str {
+        let start = self.position;
+
+        let length = self.source[start..].find(|c: char| !accept(c)).unwrap_or(self.source.len() - start);
+
+        self.position += length;
+
+        &self.source[start..self.position]
+    }
+
+    /// Parses a `sum` (see `Parser`).
+    fn sum(&mut self) -> Result<Expr, FormulaError> {
+        let mut expr = self.product()?;
+
+        loop {
+            if self.eat('+') {
+                expr = Expr::Add(Box::new(expr), Box::new(self.product()?));
+            } else if self.eat('-') {
+                expr = Expr::Sub(Box::new(expr), Box::new(self.product()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// Parses a `product`.
+    fn product(&mut self) -> Result<Expr, FormulaError> {
+        let mut expr = self.unary()?;
+
+        loop {
+            if self.eat('*') {
+                expr = Expr::Mul(Box::new(expr), Box::new(self.unary()?));
+            } else if self.eat('/') {
+                expr = Expr::Div(Box::new(expr), Box::new(self.unary()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// Parses a `unary`, with its `power`.
+    fn unary(&mut self) -> Result<Expr, FormulaError> {
+        if self.eat('-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+
+        let atom = self.atom()?;
+
+        if !self.eat('^') {
+            return Ok(atom);
+        }
+
+        self.peek();
+
+        let exponent = self.take_while(|c| c.is_ascii_alphanumeric() || c == '.');
+
+        match exponent.parse() {
+            Ok(exponent) if exponent <= MAX_EXPONENT => Ok(Expr::Pow(Box::new(atom), exponent)),
+            _ if exponent.is_empty() => Err(self.peek().map_or(FormulaError::UnexpectedEnd, |c| FormulaError::UnexpectedChar(c, self.position))),
+            _ => Err(FormulaError::InvalidExponent(exponent.to_owned())),
+        }
+    }
+
+    /// Parses an `atom`.
+    fn atom(&mut self) -> Result<Expr, FormulaError> {
+        match self.peek() {
+            None => Err(FormulaError::UnexpectedEnd),
+            Some('(') => {
+                self.eat('(');
+
+                let expr = self.sum()?;
+
+                self.expect(')')?;
+
+                Ok(expr)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                // the sign of an exponent belongs to the number
+                let mut previous = ' ';
+
+                let number = self.take_while(|c| {
+                    let accepted = c.is_ascii_digit() || c == '.' || matches!(c, 'e' | 'E') || (matches!(c, '+' | '-') && matches!(previous, 'e' | 'E'));
+
+                    previous = c;
+
+                    accepted
+                });
+
+                number.parse().map(|re| Expr::Constant(MandelPoint::new((re, 0.0)))).map_err(|_| FormulaError::InvalidNumber(number.to_owned()))
+            }
+            Some(c) if c.is_alphabetic() => {
+                let name = self.take_while(char::is_alphanumeric).to_owned();
+
+                match name.as_str() {
+                    "z" => Ok(Expr::Z),
+                    "c" => Ok(Expr::C),
+                    "i" => Ok(Expr::Constant(MandelPoint::new((0.0, 1.0)))),
+                    "conj" | "abs" => {
+                        self.expect('(')?;
+
+                        let argument = Box::new(self.sum()?);
+
+                        self.expect(')')?;
+
+                        Ok(if name == "conj" { Expr::Conj(argument) } else { Expr::Abs(argument) })
+                    }
+                    _ => Err(FormulaError::UnknownName(name)),
+                }
+            }
+            Some(c) => Err(FormulaError::UnexpectedChar(c, self.position)),
+        }
+    }
+}
+
+/// A fractal given by a formula (see the module), iterated
+/// from `z = 0` like the Mandelbrot set, except for the
+/// derivative, which is unknown (and left to `0`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{formula::{Formula, FormulaError}, fractal::Fractal, utils::{Plottable, MandelPoint}};
+/// let formula = Formula::parse("z*z*z + c*z + c").unwrap();
+///
+/// assert_eq!(formula.name(), "z*z*z + c*z + c");
+/// assert_eq!(formula.iterate(MandelPoint::new((1.0, 0.0)), MandelPoint::new((0.0, 1.0))), MandelPoint::new((1.0, 2.0)));
+///
+/// assert_eq!(Formula::parse("z^2 + d").unwrap_err(), FormulaError::UnknownName("d".to_owned()));
+/// assert_eq!(Formula::parse("(z + c").unwrap_err(), FormulaError::UnexpectedEnd);
+/// assert_eq!(Formula::parse("z^-1").unwrap_err(), FormulaError::UnexpectedChar('-', 2));
+/// ```
+pub struct Formula {
+    source: String,
+    power: u32,
+    symmetric: bool,
+    iterate: Compiled,
+}
+
+impl Formula {
+    /// Parses and compiles `source`.
+    pub fn parse(source: &str) -> Result<Self, FormulaError> {
+        let mut parser = Parser { source, position: 0 };
+
+        let expr = parser.sum()?;
+
+        if let Some(c) = parser.peek() {
+            return Err(FormulaError::UnexpectedChar(c, parser.position));
+        }
+
+        Ok(Self {
+            source: source.trim().to_owned(),
+            // the smooth coloring needs the degree in `z`
+            power: expr.degree().unwrap_or(2).max(2),
+            symmetric: expr.is_symmetric(),
+            iterate: expr.compile(),
+        })
+    }
+
+    /// Returns the next value of `z`, given `z` and `c`.
+    pub fn iterate(&self, z: MandelPoint, c: MandelPoint) -> MandelPoint {
+        (self.iterate)(z, c)
+    }
+}
+
+impl FromStr for Formula {
+    type Err = FormulaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Formula::parse(s)
+    }
+}
+
+impl fmt::Debug for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Formula").field("source", &self.source).finish()
+    }
+}
+
+impl Fractal for Formula {
+    fn name(&self) -> &str {
+        &self.source
+    }
+
+    fn escape(&self, c: MandelPoint, max_iterations: usize) -> EscapeResult {
+        let mut result = EscapeResult { c, power: self.power, ..EscapeResult::default() };
+        let mut min_norm_sqr = f64::INFINITY;
+
+        while result.iterations <= max_iterations {
+            result.iterations += 1;
+            result.z = self.iterate(result.z, c);
+
+            let norm_sqr = result.z.norm_sqr();
+
+            // a formula may also diverge to `NaN`
+            if norm_sqr > 4.0 || norm_sqr.is_nan() {
+                result.escaped = true;
+
+                return result;
+            }
+
+            result.orbit.push(norm_sqr.sqrt() as f32);
+
+            if norm_sqr < min_norm_sqr {
+                min_norm_sqr = norm_sqr;
+                result.atom_domain = result.iterations;
+            }
+        }
+
+        result
+    }
+
+    fn orbit(&self, c: MandelPoint, max_points: usize) -> Vec<MandelPoint> {
+        let mut orbit = Vec::new();
+        let mut z = MANDELPOINT_ZERO;
+
+        while orbit.len() < max_points && z.norm_sqr() <= 4.0 {
+            z = self.iterate(z, c);
+
+            orbit.push(z);
+        }
+
+        orbit
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.symmetric
+    }
+}
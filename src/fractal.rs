@@ -0,0 +1,221 @@
+use crate::{kernel::{self, EscapeResult}, utils::*};
+use std::{fmt, io::{self, Write}};
+
+/// The factor by which the views of `Multibrot::default_viewport()`
+/// are larger than the disk containing the set.
+const MULTIBROT_MARGIN: f32 = 1.1;
+
+/// The first line of the CSV files written by `write_orbits_csv()`.
+pub const ORBIT_CSV_HEADER: &str = "c_re,c_im,iteration,re,im,abs";
+
+/// A trait implemented by any escape-time fractal
+/// that can be explored, given by the iteration
+/// of a function of `z` (depending on `c`).
+pub trait Fractal: Send + Sync + fmt::Debug {
+    /// Returns the name of the fractal.
+    fn name(&self) -> &str;
+
+    /// Iterates the point `c` until it escapes, or until
+    /// `max_iterations + 1` iterations are done (see `kernel::escape()`).
+    fn escape(&self, c: MandelPoint, max_iterations: usize) -> EscapeResult;
+
+    /// Returns the first `max_points` values of `z`
+    /// for the point `c`, if the fractal can provide them;
+    /// the orbit is not drawn otherwise.
+    fn orbit(&self, _c: MandelPoint, _max_points: usize) -> Vec<MandelPoint> {
+        Vec::new()
+    }
+
+    /// Returns whether the fractal is symmetric about the real
+    /// axis (the conjugate of a point has the conjugate result),
+    /// so that only half of the symmetric views is iterated (see
+    /// `render::distinct_rows()`); it's not assumed to be otherwise.
+    fn is_symmetric(&self) -> bool {
+        false
+    }
+
+    /// Returns the view that shows the whole fractal,
+    /// which is `Viewport::default()` (fitting the
+    /// Mandelbrot set) unless the fractal has other extents.
+    fn default_viewport(&self) -> Viewport {
+        Viewport::default()
+    }
+}
+
+/// Writes the orbits (see `Fractal::orbit()`) of the `points`
+/// of `fractal`, up to `max_points` values of `z` each, to
+/// `writer` as CSV (see `ORBIT_CSV_HEADER`), with a row for
+/// each iteration, counted from `1`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::{write_orbits_csv, Mandelbrot}, utils::{Plottable, MandelPoint}};
+/// let mut csv = Vec::new();
+///
+/// write_orbits_csv(&mut csv, &Mandelbrot, &[MandelPoint::new((-1.0, 0.0))], 2).unwrap();
+///
+/// assert_eq!(String::from_utf8(csv).unwrap(), "c_re,c_im,iteration,re,im,abs\n-1,0,1,-1,0,1\n-1,0,2,0,0,0\n");
+/// ```
+pub fn write_orbits_csv<T: Write>(mut writer: T, fractal: &dyn Fractal, points: &[MandelPoint], max_points: usize) -> io::Result<()> {
+    writeln!(writer, "{}", ORBIT_CSV_HEADER)?;
+
+    for &point in points {
+        let (c_re, c_im) = point.coordinates();
+
+        for (idx, z) in fractal.orbit(point, max_points).into_iter().enumerate() {
+            let (re, im) = z.coordinates();
+
+            writeln!(writer, "{},{},{},{},{},{}", c_re, c_im, idx + 1, re, im, z.norm())?;
+        }
+    }
+
+    writer.flush()
+}
+
+/// The Mandelbrot set, given by `z = z^2 + c`, starting with `z = 0`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::{Fractal, Mandelbrot}, utils::{Plottable, MandelPoint}};
+/// let c = MandelPoint::new((-1.0, 0.0));
+///
+/// assert!(!Mandelbrot.escape(c, 128).escaped);
+/// assert_eq!(Mandelbrot.orbit(c, 4), vec![MandelPoint::new((-1.0, 0.0)), MandelPoint::new((0.0, 0.0)), MandelPoint::new((-1.0, 0.0)), MandelPoint::new((0.0, 0.0))]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Mandelbrot;
+
+impl Fractal for Mandelbrot {
+    fn name(&self) -> &str {
+        "mandelbrot"
+    }
+
+    fn escape(&self, c: MandelPoint, max_iterations: usize) -> EscapeResult {
+        kernel::escape(c, max_iterations)
+    }
+
+    fn orbit(&self, c: MandelPoint, max_points: usize) -> Vec<MandelPoint> {
+        MandelIter::new(c).take(max_points).collect()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/// The whole family of iterations of `z = z^2 + c`,
+/// where the point of each pixel is either `c` or the
+/// starting `z`, depending on `seed` (e.g. a fixed `c`
+/// gives the Julia sets, while `Seed::default()` gives
+/// the Mandelbrot set).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::{Fractal, Quadratic}, utils::{Plottable, MandelPoint, Seed}};
+/// let julia = Quadratic { seed: Seed::Start { c: MandelPoint::new((-1.0, 0.0)) } };
+///
+/// // the point is the starting `z`
+/// assert!(!julia.escape(MandelPoint::new((0.0, 0.0)), 128).escaped);
+/// assert!(julia.escape(MandelPoint::new((2.0, 0.0)), 128).escaped);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Quadratic {
+    /// How the point of each pixel enters the iteration.
+    pub seed: Seed,
+}
+
+impl Fractal for Quadratic {
+    fn name(&self) -> &str {
+        match self.seed {
+            Seed::Parameter { .. } => "mandelbrot",
+            Seed::Start { .. } => "julia",
+        }
+    }
+
+    fn escape(&self, point: MandelPoint, max_iterations: usize) -> EscapeResult {
+        let (z0, c) = self.seed.split(point);
+
+        kernel::escape_from(z0, c, max_iterations)
+    }
+
+    fn orbit(&self, point: MandelPoint, max_points: usize) -> Vec<MandelPoint> {
+        MandelIter::seeded(self.seed, point).take(max_points).collect()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        // the fixed value must be real too
+        match self.seed {
+            Seed::Parameter { z0: fixed } | Seed::Start { c: fixed } => fixed.coordinates().1 == 0.0,
+        }
+    }
+}
+
+/// The Multibrot sets, given by `z = z^power + c`, starting
+/// with `z = 0`, for the integer exponents `power >= 2` (`2`
+/// being the Mandelbrot set), which have `power - 1` bulbs
+/// around the center and get closer to the unit disk as
+/// the exponent grows.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::{Fractal, Multibrot}, utils::{Plottable, MandelPoint}};
+/// let cubic = Multibrot::new(3);
+///
+/// assert!(!cubic.escape(MandelPoint::new((0.0, 0.5)), 128).escaped);
+/// assert!(cubic.escape(MandelPoint::new((-1.0, 0.0)), 128).escaped);
+///
+/// // the set is centered on the origin
+/// assert_eq!(cubic.default_viewport().center(), MandelPoint::new((0.0, 0.0)));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Multibrot {
+    power: u32,
+}
+
+impl Multibrot {
+    /// Returns the Multibrot set of the given
+    /// exponent; exponents smaller than `2` become `2`.
+    pub fn new(power: u32) -> Self {
+        Self { power: power.max(2) }
+    }
+
+    /// Returns the exponent of the iteration.
+    pub fn power(&self) -> u32 {
+        self.power
+    }
+}
+
+impl Fractal for Multibrot {
+    fn name(&self) -> &str {
+        "multibrot"
+    }
+
+    fn escape(&self, c: MandelPoint, max_iterations: usize) -> EscapeResult {
+        kernel::escape_power(c, self.power, max_iterations)
+    }
+
+    fn orbit(&self, c: MandelPoint, max_points: usize) -> Vec<MandelPoint> {
+        MandelIter::builder(c).power(self.power).build().take(max_points).collect()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+
+    fn default_viewport(&self) -> Viewport {
+        // every point farther than `2^(1 / (power - 1))`
+        // escapes, and `Viewport::default()` is 2 units tall
+        let radius = 2f32.powf(1.0 / (self.power - 1) as f32);
+
+        Viewport::new(MANDELPOINT_ZERO, 1.0 / (radius * MULTIBROT_MARGIN), 0.0)
+    }
+}
+
+/// Returns the built-in fractals.
+pub fn builtin() -> Vec<Box<dyn Fractal>> {
+    vec![Box::new(Mandelbrot)]
+}
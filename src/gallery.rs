@@ -0,0 +1,151 @@
+use crate::{fractal::Fractal, render, utils::*};
+use rand::Rng;
+
+/// The size (in pixels) of the coarse scan
+/// that looks for the boundary in `random_jump()`.
+const SCAN_SIZE: (usize, usize) = (96, 64);
+
+/// The smallest and largest zoom factors of `random_jump()`.
+pub const JUMP_FACTORS: (f32, f32) = (4.0, 64.0);
+
+/// A famous location of the Mandelbrot set, with
+/// the settings suggested to explore it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Preset {
+    /// The name of the preset, like `seahorse-valley`.
+    pub name: &'static str,
+    /// The center of the view.
+    pub center: (f64, f64),
+    /// The zoom of the view.
+    pub zoom: f32,
+    /// The suggested maximum number of iterations.
+    pub max_iterations: usize,
+    /// The name of the suggested coloring
+    /// (see `coloring::from_name()`).
+    pub coloring: &'static str,
+}
+
+impl Preset {
+    /// Returns the view of the preset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{gallery::find, utils::{Plottable, MandelPoint}};
+    /// let viewport = find("seahorse-valley").unwrap().viewport();
+    ///
+    /// assert_eq!(viewport.center(), MandelPoint::new((-0.7453, 0.1127)));
+    /// assert_eq!(viewport.zoom(), 150.0);
+    /// ```
+    pub fn viewport(&self) -> Viewport {
+        Viewport::new(MandelPoint::new(self.center), self.zoom, 0.0)
+    }
+}
+
+/// The built-in presets, in the order they
+/// are cycled through in the window; the zoom
+/// stays within the precision of `f64`.
+pub const PRESETS: [Preset; 5] = [
+    Preset { name: "seahorse-valley", center: (-0.7453, 0.1127), zoom: 150.0, max_iterations: 512, coloring: "palette" },
+    Preset { name: "elephant-valley", center: (0.2925, 0.0149), zoom: 60.0, max_iterations: 512, coloring: "palette" },
+    // the Misiurewicz point `c = i`, whose critical orbit is preperiodic
+    Preset { name: "misiurewicz-i", center: (0.0, 1.0), zoom: 12.0, max_iterations: 1024, coloring: "binary-decomposition" },
+    // the Misiurewicz point at the tip of the antenna, `c = -2`
+    Preset { name: "misiurewicz-tip", center: (-2.0, 0.0), zoom: 40.0, max_iterations: 1024, coloring: "derivative" },
+    // the period-4 minibrot on the real axis
+    Preset { name: "minibrot", center: (-1.940_799_8, 0.0), zoom: 600.0, max_iterations: 2048, coloring: "atom-domain" },
+];
+
+/// Returns the iteration cap suggested to show
+/// the details of a view with the given `zoom`,
+/// which grows with the square of its magnitude.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::gallery::suggested_iterations;
+/// assert_eq!(suggested_iterations(1.0), 128);
+/// assert_eq!(suggested_iterations(100.0), 1152);
+/// ```
+pub fn suggested_iterations(zoom: f32) -> usize {
+    let magnitude = 1.0 + zoom.log10().max(0.0);
+
+    (ESCAPE_POINT as f32 * magnitude * magnitude).round() as usize
+}
+
+/// Returns a view centered on a random point near the
+/// boundary of `fractal`, chosen with a coarse scan of the
+/// distance estimate of the area seen through `viewport`
+/// (see `EscapeResult::distance_estimate()`), and zoomed by
+/// a random factor between the `JUMP_FACTORS` (but not
+/// beyond the precision of its center), together with its
+/// suggested iteration cap (see `suggested_iterations()`);
+/// returns `None` if the boundary isn't in view.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, gallery::random_jump, utils::Viewport};
+/// # use rand::{rngs::StdRng, SeedableRng};
+/// let mut rng = StdRng::seed_from_u64(42);
+///
+/// let (viewport, max_iterations) = random_jump(&Mandelbrot, &Viewport::default(), 128, &mut rng).unwrap();
+///
+/// assert!(viewport.zoom() >= 4.0 && viewport.zoom() <= 64.0);
+/// assert!(max_iterations > 128);
+/// ```
+pub fn random_jump<R: Rng>(fractal: &dyn Fractal, viewport: &Viewport, max_iterations: usize, rng: &mut R) -> Option<(Viewport, usize)> {
+    let results = render::escape_results(fractal, viewport, SCAN_SIZE, max_iterations);
+
+    let pixel_size = viewport.pixel_size(SCAN_SIZE);
+
+    // the pixels that escaped within a pixel from the boundary
+    let candidates: Vec<usize> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| result.distance_estimate().is_some_and(|distance| distance < pixel_size))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let idx = candidates[rng.gen_range(0..candidates.len())];
+
+    let pixel = viewport.orient(Point::new((idx % SCAN_SIZE.0, idx / SCAN_SIZE.0)), SCAN_SIZE);
+
+    // zoom by a factor with a uniform order of magnitude
+    let factor = rng.gen_range(JUMP_FACTORS.0.ln()..JUMP_FACTORS.1.ln()).exp();
+
+    let mut jump = Viewport::new(pixel.to_mandel(viewport, SCAN_SIZE), viewport.zoom() * factor, viewport.rotation());
+
+    jump.set_orientation(viewport.orientation());
+
+    // don't zoom so much that the pixels are
+    // smaller than the precision of the center
+    let (re, im) = jump.center().coordinates();
+
+    let resolution = 4.0 * re.abs().max(im.abs()) * f64::EPSILON;
+
+    let pixel_size = jump.pixel_size((W, H));
+
+    if pixel_size < resolution {
+        jump.zoom_by((pixel_size / resolution) as f32);
+    }
+
+    Some((jump, suggested_iterations(jump.zoom())))
+}
+
+/// Returns the preset with the given `name`, if there is one.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::gallery::find;
+/// assert_eq!(find("minibrot").map(|preset| preset.max_iterations), Some(2048));
+/// assert!(find("valley").is_none());
+/// ```
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name == name)
+}
@@ -0,0 +1,129 @@
+use crate::{coloring::Registry, fractal::Mandelbrot, gallery, render, utils::*};
+use std::{collections::BTreeMap, io::{self, BufRead, Write}};
+
+/// The size (in pixels) of the renders of the suite,
+/// small enough to run it in a few seconds.
+pub const GOLDEN_SIZE: (usize, usize) = (150, 100);
+
+/// A render of the suite of `suite()`, which must always
+/// give the same pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    /// The name of the case, which identifies its reference.
+    pub name: String,
+    /// The view that is rendered.
+    pub viewport: Viewport,
+    /// The maximum number of iterations of each pixel.
+    pub max_iterations: usize,
+    /// The name of the coloring of the render.
+    pub coloring: String,
+}
+
+impl Case {
+    /// Renders the case (at `GOLDEN_SIZE`), with
+    /// its coloring taken from `colorings`.
+    pub fn render(&self, colorings: &Registry) -> Result<Vec<u8>, String> {
+        let mapper = colorings.find(&self.coloring).ok_or_else(|| format!("unknown coloring `{}`", self.coloring))?;
+
+        render::render_rgba(&Mandelbrot, &self.viewport, GOLDEN_SIZE, self.max_iterations, mapper, None).map_err(|err| err.to_string())
+    }
+}
+
+/// Returns the suite of renders checked by the golden-image
+/// regression test: the default view with every built-in
+/// coloring, and the presets of the gallery.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::Registry, golden::{hash, suite}};
+/// let colorings = Registry::builtin();
+///
+/// for case in suite(&colorings) {
+///     let rgba = case.render(&colorings).unwrap();
+///
+///     // the renders are deterministic
+///     assert_eq!(hash(&rgba), hash(&case.render(&colorings).unwrap()));
+/// }
+/// ```
+pub fn suite(colorings: &Registry) -> Vec<Case> {
+    let defaults = colorings.names().into_iter().map(|coloring| Case {
+        name: format!("default-{}", coloring),
+        viewport: Viewport::default(),
+        max_iterations: ESCAPE_POINT,
+        coloring: coloring.to_owned(),
+    });
+
+    let presets = gallery::PRESETS.iter().map(|preset| Case {
+        name: preset.name.to_owned(),
+        viewport: preset.viewport(),
+        max_iterations: preset.max_iterations,
+        coloring: preset.coloring.to_owned(),
+    });
+
+    defaults.chain(presets).collect()
+}
+
+/// Returns the 64-bit FNV-1a hash of `rgba`, which, unlike the
+/// hashers of the standard library, is the same on every version
+/// and platform.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::golden::hash;
+/// assert_eq!(hash(&[]), 0xcbf2_9ce4_8422_2325);
+/// assert_ne!(hash(&[0, 0, 0, 255]), hash(&[0, 0, 1, 255]));
+/// ```
+pub fn hash(rgba: &[u8]) -> u64 {
+    rgba.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Reads the references of a suite, a line `NAME HASH` (in
+/// hexadecimal) for each case, skipping the empty lines and
+/// the comments (starting with `#`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::golden::{read_references, write_references};
+/// let mut references = std::collections::BTreeMap::new();
+/// references.insert("minibrot".to_owned(), 0xdead_beef);
+///
+/// let mut file = Vec::new();
+/// write_references(&mut file, &references).unwrap();
+///
+/// assert_eq!(read_references(file.as_slice()).unwrap(), references);
+/// ```
+pub fn read_references<R: BufRead>(reader: R) -> io::Result<BTreeMap<String, u64>> {
+    let mut references = BTreeMap::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("invalid reference `{}` (expected `NAME HASH`)", line));
+
+        let (name, hash) = line.split_once(' ').ok_or_else(invalid)?;
+        let hash = u64::from_str_radix(hash.trim(), 16).map_err(|_| invalid())?;
+
+        references.insert(name.to_owned(), hash);
+    }
+
+    Ok(references)
+}
+
+/// Writes the `references` of a suite (see `read_references()`).
+pub fn write_references<W: Write>(mut writer: W, references: &BTreeMap<String, u64>) -> io::Result<()> {
+    writeln!(writer, "# the hashes of the golden images, see `mandelbrust --golden`")?;
+
+    for (name, hash) in references {
+        writeln!(writer, "{} {:016x}", name, hash)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! The navigation history of the window, which keeps the
+//! previous views, to go back to them, and the ones that
+//! were left by going back, to go forward again.
+
+use std::collections::VecDeque;
+
+/// The number of previous states kept by a `History`,
+/// if not specified otherwise.
+pub const HISTORY_CAPACITY: usize = 256;
+
+/// A history of states with undo and redo: the states left by
+/// undoing can be redone, until a new state is recorded; only
+/// the latest `capacity` previous states are kept.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::history::History;
+/// let mut history = History::new(8);
+///
+/// // going from 1 to 2, and then to 3
+/// history.record(1);
+/// history.record(2);
+///
+/// assert_eq!(history.undo(3), Some(2));
+/// assert_eq!(history.undo(2), Some(1));
+/// assert_eq!(history.undo(1), None);
+/// assert_eq!(history.redo(1), Some(2));
+///
+/// // a new state clears the ones to redo
+/// history.record(2);
+///
+/// assert_eq!(history.redo(4), None);
+/// assert_eq!(history.undo(4), Some(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    past: VecDeque<T>,
+    future: Vec<T>,
+    capacity: usize,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new(HISTORY_CAPACITY)
+    }
+}
+
+impl<T> History<T> {
+    /// Returns an empty history, keeping
+    /// (at most) `capacity` previous states.
+    pub fn new(capacity: usize) -> Self {
+        Self { past: VecDeque::new(), future: Vec::new(), capacity }
+    }
+
+    /// Records `previous` as the state that was left for a new one,
+    /// forgetting the states to redo, and the oldest previous
+    /// state if there are more than the capacity.
+    pub fn record(&mut self, previous: T) {
+        self.future.clear();
+        self.past.push_back(previous);
+
+        if self.past.len() > self.capacity {
+            self.past.pop_front();
+        }
+    }
+
+    /// Returns the state before `current`, which can then be
+    /// redone, or `None` if there are no previous states.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.past.pop_back()?;
+
+        self.future.push(current);
+
+        Some(previous)
+    }
+
+    /// Returns the state that was undone last, leaving
+    /// `current` as the previous one, or `None` if
+    /// there are no states to redo.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.future.pop()?;
+
+        self.past.push_back(current);
+
+        Some(next)
+    }
+}
@@ -0,0 +1,510 @@
+use crate::utils::*;
+
+/// The largest period of the attracting cycles
+/// looked for by `EscapeResult::interior_distance_estimate()`.
+pub const MAX_PERIOD: usize = 1024;
+
+/// How close `z` must come back to itself
+/// to be considered part of an attracting cycle.
+const CYCLE_EPSILON: f64 = 1e-4;
+
+/// How close (squared) `z` must come back to the value
+/// saved by a `CycleCheck` to be considered periodic.
+const PERIODICITY_EPSILON_SQR: f64 = 1e-24;
+
+/// The number of steps of Newton's method
+/// that refine a point of an attracting cycle.
+const NEWTON_STEPS: usize = 8;
+
+/// The result of iterating a point of the
+/// Mandelbrot plane until it escapes, or until
+/// the maximum number of iterations is reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EscapeResult {
+    /// The number of iterations needed to escape,
+    /// which is `max_iterations + 1` for the points
+    /// that never escape (like `MandelIter::escape_time()`).
+    pub iterations: usize,
+    /// The last value of `z`.
+    pub z: MandelPoint,
+    /// The last value of the derivative `dz/dc`.
+    pub derivative: MandelPoint,
+    /// Whether the point escaped.
+    pub escaped: bool,
+    /// The (first) iteration at which `|z|` attained its
+    /// minimum, which identifies the atom domain of the point.
+    pub atom_domain: usize,
+    /// The statistics of `|z|` along the orbit.
+    pub orbit: OrbitStats,
+    /// The parameter `c` of the iteration.
+    pub c: MandelPoint,
+    /// The exponent `d` of the iteration `z = z^d + c`.
+    pub power: u32,
+}
+
+impl Default for EscapeResult {
+    /// Returns the result of a point of the Mandelbrot set
+    /// (i.e. with `power = 2`) before the first iteration.
+    fn default() -> Self {
+        Self {
+            iterations: 0,
+            z: MANDELPOINT_ZERO,
+            derivative: MANDELPOINT_ZERO,
+            escaped: false,
+            atom_domain: 0,
+            orbit: OrbitStats::default(),
+            c: MANDELPOINT_ZERO,
+            power: 2,
+        }
+    }
+}
+
+/// Statistics of the values of `|z|` along an orbit,
+/// accumulated while iterating it, except for the
+/// value that escapes.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::kernel::OrbitStats;
+/// let mut stats = OrbitStats::default();
+///
+/// stats.push(1.0);
+/// stats.push(0.5);
+/// stats.push(1.5);
+///
+/// assert_eq!(stats.min, 0.5);
+/// assert_eq!(stats.mean(), 1.0);
+/// assert!((stats.variance() - 1.0 / 6.0).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OrbitStats {
+    /// The number of values of `|z|`.
+    pub count: usize,
+    /// The sum of the values of `|z|`.
+    pub sum: f32,
+    /// The sum of the squares of the values of `|z|`.
+    pub sum_sqr: f32,
+    /// The smallest value of `|z|` (`0` if there are none).
+    pub min: f32,
+}
+
+impl OrbitStats {
+    /// Adds the value `norm` of `|z|`.
+    pub fn push(&mut self, norm: f32) {
+        if self.count == 0 || norm < self.min {
+            self.min = norm;
+        }
+
+        self.count += 1;
+        self.sum += norm;
+        self.sum_sqr += norm * norm;
+    }
+
+    /// Returns the average distance of
+    /// `z` from the origin (`0` if unknown).
+    pub fn mean(&self) -> f32 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        self.sum / self.count as f32
+    }
+
+    /// Returns the variance of `|z|` (`0` if unknown).
+    pub fn variance(&self) -> f32 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        let mean = self.mean();
+
+        // rounding may make it slightly negative
+        (self.sum_sqr / self.count as f32 - mean * mean).max(0.0)
+    }
+}
+
+impl EscapeResult {
+    /// Returns an estimate of the distance between
+    /// the point and the boundary of the Mandelbrot
+    /// set (correct within a factor of 2), or `None`
+    /// if the point didn't escape.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{kernel::escape, utils::{Plottable, MandelPoint}};
+    /// let near = escape(MandelPoint::new((0.3, 0.0)), 128).distance_estimate().unwrap();
+    /// let far = escape(MandelPoint::new((1.0, 0.0)), 128).distance_estimate().unwrap();
+    ///
+    /// assert!(near < far);
+    /// assert_eq!(escape(MandelPoint::new((0.0, 0.0)), 128).distance_estimate(), None);
+    /// ```
+    pub fn distance_estimate(&self) -> Option<f64> {
+        if !self.escaped {
+            return None;
+        }
+
+        let norm = self.z.norm();
+
+        Some(norm * norm.ln() / self.derivative.norm())
+    }
+
+    /// Returns the period of the cycle attracting the orbit of
+    /// the point (up to `MAX_PERIOD`), as the first time `z`
+    /// comes back close to its last value, or `None` if the
+    /// point escaped or the period wasn't found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{kernel::{escape, escape_power}, utils::{Plottable, MandelPoint}};
+    /// assert_eq!(escape(MandelPoint::new((0.0, 0.0)), 128).period(), Some(1));
+    /// assert_eq!(escape(MandelPoint::new((-1.0, 0.0)), 128).period(), Some(2));
+    /// assert_eq!(escape(MandelPoint::new((-0.12, 0.75)), 512).period(), Some(3));
+    /// assert_eq!(escape_power(MandelPoint::new((0.0, 0.0)), 3, 128).period(), Some(1));
+    /// assert_eq!(escape(MandelPoint::new((1.0, 0.0)), 128).period(), None);
+    /// ```
+    pub fn period(&self) -> Option<usize> {
+        if self.escaped {
+            return None;
+        }
+
+        let mut w = self.z;
+
+        (1..=MAX_PERIOD).find(|_| {
+            w = (1..self.power).fold(w, |power, _| power.complex_mul(w)) + self.c;
+
+            (w - self.z).norm_sqr() < CYCLE_EPSILON * CYCLE_EPSILON
+        })
+    }
+
+    /// Returns an estimate of the distance between the point
+    /// and the boundary of the Mandelbrot set (correct within
+    /// a factor of 4), for the points that didn't escape and
+    /// whose orbit is attracted by a cycle (with a period up
+    /// to `MAX_PERIOD`), based on the multiplier of the cycle,
+    /// or `None` otherwise; the cycle is found starting from
+    /// the last value of `z`, which is already close to it;
+    /// only the Mandelbrot set (`power = 2`) is supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{kernel::escape, utils::{Plottable, MandelPoint}};
+    /// let deep = escape(MandelPoint::new((0.0, 0.0)), 128).interior_distance_estimate().unwrap();
+    /// let shallow = escape(MandelPoint::new((0.2, 0.0)), 128).interior_distance_estimate().unwrap();
+    ///
+    /// assert!(shallow < deep);
+    /// // the center of the period 2 bulb, whose radius is `1/4`
+    /// assert!((0.25..=1.0).contains(&escape(MandelPoint::new((-1.0, 0.0)), 128).interior_distance_estimate().unwrap()));
+    /// assert_eq!(escape(MandelPoint::new((1.0, 0.0)), 128).interior_distance_estimate(), None);
+    /// ```
+    pub fn interior_distance_estimate(&self) -> Option<f64> {
+        if self.escaped || self.power != 2 {
+            return None;
+        }
+
+        let one = MandelPoint::new((1.0, 0.0));
+        let c = self.c;
+
+        let period = self.period()?;
+
+        // refine a point of the cycle with Newton's
+        // method on `F^period(z) - z = 0`
+        let mut z0 = self.z;
+
+        for _ in 0..NEWTON_STEPS {
+            let (mut w, mut dw) = (z0, one);
+
+            for _ in 0..period {
+                dw = (w * 2.0).complex_mul(dw);
+                w = w.square() + c;
+            }
+
+            z0 = z0 - (w - z0).complex_div(dw - one);
+        }
+
+        // the derivatives of `F^period` at the point of the
+        // cycle, where `dz` is the multiplier of the cycle
+        let (mut z, mut dz, mut dzdz, mut dc, mut dcdz) = (z0, one, MANDELPOINT_ZERO, MANDELPOINT_ZERO, MANDELPOINT_ZERO);
+
+        for _ in 0..period {
+            dcdz = (z.complex_mul(dcdz) + dc.complex_mul(dz)) * 2.0;
+            dc = (z * 2.0).complex_mul(dc) + one;
+            dzdz = (dz.square() + z.complex_mul(dzdz)) * 2.0;
+            dz = (z * 2.0).complex_mul(dz);
+            z = z.square() + c;
+        }
+
+        // the cycle must be attracting
+        if dz.norm_sqr() >= 1.0 {
+            return None;
+        }
+
+        let distance = (1.0 - dz.norm_sqr()) / (dcdz + dzdz.complex_mul(dc).complex_div(one - dz)).norm();
+
+        Some(distance).filter(|distance| distance.is_finite())
+    }
+
+    /// Returns the normalized (continuous) iteration count,
+    /// which removes the bands of the escape time, or
+    /// `iterations` for the points that didn't escape.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{kernel::escape, utils::{Plottable, MandelPoint}};
+    /// let result = escape(MandelPoint::new((0.3, 0.0)), 128);
+    ///
+    /// assert!((result.smooth_iterations() - result.iterations as f32).abs() < 2.0);
+    /// assert_eq!(escape(MandelPoint::new((0.0, 0.0)), 128).smooth_iterations(), 129.0);
+    /// ```
+    pub fn smooth_iterations(&self) -> f32 {
+        if !self.escaped {
+            return self.iterations as f32;
+        }
+
+        // `log_d`, which is exactly `log2` for `d = 2`
+        self.iterations as f32 + 1.0 - (self.z.norm().ln().log2() / (self.power as f64).log2()) as f32
+    }
+}
+
+/// Iterates `z = z^2 + c`, starting with `z = 0` (see
+/// `escape_from()` for any other starting value), until `z`
+/// leaves the area of radius 2 or `max_iterations + 1`
+/// iterations are done, tracking the derivative `dz/dc`
+/// (which follows `dz = 2 * z * dz + 1`, starting with `dz = 0`),
+/// needed by the distance estimation and the derivative heatmap,
+/// the iteration at which `|z|` is the smallest and the
+/// statistics of `|z|` along the orbit (see `OrbitStats`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{kernel::escape, utils::{Plottable, MandelIter, MandelPoint}};
+/// let mandelpoint = MandelPoint::new((1.0, 1.0));
+///
+/// let result = escape(mandelpoint, 128);
+///
+/// assert_eq!(result.iterations, MandelIter::escape_time(mandelpoint, 128));
+/// assert_eq!(result.z, MandelPoint::new((1.0, 3.0)));
+/// assert!(result.escaped);
+/// assert_eq!(result.atom_domain, 1);
+///
+/// // the center of the period 2 bulb
+/// assert_eq!(escape(MandelPoint::new((-1.0, 0.0)), 128).atom_domain, 2);
+/// ```
+pub fn escape(c: MandelPoint, max_iterations: usize) -> EscapeResult {
+    escape_from(MANDELPOINT_ZERO, c, max_iterations)
+}
+
+/// Iterates `z = z^2 + c` like `escape()`, but starting with `z = z0`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{kernel::{escape, escape_from}, utils::{Plottable, MandelPoint, MANDELPOINT_ZERO}};
+/// let c = MandelPoint::new((0.3, 0.5));
+///
+/// assert_eq!(escape_from(MANDELPOINT_ZERO, c, 128), escape(c, 128));
+/// // `z0 = 2` escapes right after the first iteration
+/// assert_eq!(escape_from(MandelPoint::new((2.0, 0.0)), MANDELPOINT_ZERO, 128).iterations, 1);
+/// ```
+pub fn escape_from(z0: MandelPoint, c: MandelPoint, max_iterations: usize) -> EscapeResult {
+    let mut state = EscapeState::new(z0, c);
+
+    state.advance(max_iterations);
+
+    state.result()
+}
+
+/// Iterates `z = z^power + c` like `escape()` (the
+/// derivative following `dz = power * z^(power - 1) * dz + 1`),
+/// for the Multibrot sets; exponents smaller than `2` become `2`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{kernel::{escape, escape_power}, utils::{Plottable, MandelIter, MandelPoint}};
+/// let c = MandelPoint::new((0.3, 0.5));
+///
+/// assert_eq!(escape_power(c, 2, 128), escape(c, 128));
+///
+/// let cubic = MandelIter::builder(c).power(3).build().enumerate().take_while(|(idx, _)| *idx <= 128).count();
+///
+/// assert_eq!(escape_power(c, 3, 128).iterations, cubic);
+/// ```
+pub fn escape_power(c: MandelPoint, power: u32, max_iterations: usize) -> EscapeResult {
+    let mut state = EscapeState::with_power(MANDELPOINT_ZERO, c, power);
+
+    state.advance(max_iterations);
+
+    state.result()
+}
+
+/// The periodicity checking of an orbit, with Brent's algorithm:
+/// `z` is compared with a saved value, which is replaced by the
+/// current one after twice as many steps as the previous time,
+/// until `z` comes back close enough to it.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{kernel::CycleCheck, utils::{Plottable, MandelPoint, MANDELPOINT_ZERO}};
+/// let mut cycle = CycleCheck::new(MANDELPOINT_ZERO);
+///
+/// // `0, -1, 0, -1, ...`, which gets back to `-1` (up to a rounding error)
+/// assert_eq!(cycle.check(MandelPoint::new((-1.0, 0.0))), None);
+/// assert_eq!(cycle.check(MandelPoint::new((0.0, 0.0))), None);
+/// assert_eq!(cycle.check(MandelPoint::new((-1.0, 1e-13))), Some(2));
+/// assert_eq!(cycle.period(), Some(2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CycleCheck {
+    saved: MandelPoint,
+    steps: usize,
+    limit: usize,
+    period: Option<usize>,
+}
+
+impl CycleCheck {
+    /// Returns the checking of an orbit starting with `z = z0`.
+    pub fn new(z0: MandelPoint) -> Self {
+        Self { saved: z0, steps: 0, limit: 1, period: None }
+    }
+
+    /// Checks the next value `z` of the orbit, returning the
+    /// period of the cycle it entered, if it's found; the period
+    /// may be a multiple of the smallest one.
+    pub fn check(&mut self, z: MandelPoint) -> Option<usize> {
+        if self.period.is_none() {
+            self.steps += 1;
+
+            if (z - self.saved).norm_sqr() < PERIODICITY_EPSILON_SQR {
+                self.period = Some(self.steps);
+            } else if self.steps == self.limit {
+                self.saved = z;
+                self.steps = 0;
+                self.limit *= 2;
+            }
+        }
+
+        self.period
+    }
+
+    /// Returns the period of the cycle found, if any.
+    pub fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+/// The state of the iteration of a point, which can be
+/// resumed with a larger iteration cap without starting
+/// over (e.g. to raise the cap a little at a time); the
+/// points whose orbit enters a cycle are detected as soon
+/// as it repeats (see `CycleCheck`), and count as never
+/// escaping, whatever the iteration cap.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{kernel::{escape, EscapeState}, utils::{Plottable, MandelPoint, MANDELPOINT_ZERO}};
+/// let c = MandelPoint::new((-0.75, 0.1));
+///
+/// let mut state = EscapeState::new(MANDELPOINT_ZERO, c);
+///
+/// state.advance(16);
+/// assert_eq!(state.result(), escape(c, 16));
+///
+/// state.advance(128);
+/// assert_eq!(state.result(), escape(c, 128));
+///
+/// // the orbit of `-1` is `0, -1, 0, -1, ...`
+/// let mut state = EscapeState::new(MANDELPOINT_ZERO, MandelPoint::new((-1.0, 0.0)));
+///
+/// state.advance(1000);
+/// assert_eq!(state.period(), Some(2));
+/// assert_eq!(state.result().orbit.count, 3);
+/// assert_eq!(state.result().iterations, 1001);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EscapeState {
+    c: MandelPoint,
+    result: EscapeResult,
+    min_norm_sqr: f64,
+    cycle: CycleCheck,
+}
+
+impl EscapeState {
+    /// Returns the state of the iteration of `z = z^2 + c`
+    /// before the first iteration, starting with `z = z0`.
+    pub fn new(z0: MandelPoint, c: MandelPoint) -> Self {
+        Self::with_power(z0, c, 2)
+    }
+
+    /// Returns the state of the iteration of `z = z^power + c`
+    /// (see `new()`); exponents smaller than `2` become `2`.
+    pub fn with_power(z0: MandelPoint, c: MandelPoint, power: u32) -> Self {
+        Self {
+            c,
+            result: EscapeResult { z: z0, c, power: power.max(2), ..EscapeResult::default() },
+            min_norm_sqr: f64::INFINITY,
+            cycle: CycleCheck::new(z0),
+        }
+    }
+
+    /// Iterates the point until it escapes, until its orbit
+    /// enters a cycle, or until `max_iterations + 1`
+    /// iterations are done in total.
+    pub fn advance(&mut self, max_iterations: usize) {
+        let EscapeResult { mut z, mut derivative, mut atom_domain, power, .. } = self.result;
+
+        while !self.result.escaped && self.cycle.period().is_none() && self.result.iterations <= max_iterations {
+            let iterations = self.result.iterations + 1;
+
+            // `z^(power - 1)`, which is `z` itself for the Mandelbrot set
+            let partial = (2..power).fold(z, |partial, _| partial.complex_mul(z));
+
+            derivative = (partial * power as f64).complex_mul(derivative) + MandelPoint::new((1.0, 0.0));
+            z = partial.complex_mul(z) + self.c;
+
+            let norm_sqr = z.norm_sqr();
+
+            self.result.iterations = iterations;
+
+            if norm_sqr > 4.0 {
+                self.result.escaped = true;
+            } else {
+                self.result.orbit.push(norm_sqr.sqrt() as f32);
+
+                if norm_sqr < self.min_norm_sqr {
+                    self.min_norm_sqr = norm_sqr;
+                    atom_domain = iterations;
+                }
+
+                self.cycle.check(z);
+            }
+        }
+
+        // a cycle never escapes
+        if self.cycle.period().is_some() {
+            self.result.iterations = self.result.iterations.max(max_iterations + 1);
+        }
+
+        self.result.z = z;
+        self.result.derivative = derivative;
+        self.result.atom_domain = atom_domain;
+    }
+
+    /// Returns the result of the iterations done so far.
+    pub fn result(&self) -> EscapeResult {
+        self.result
+    }
+
+    /// Returns the period of the cycle the orbit entered, if
+    /// it was found (see `CycleCheck::check()`).
+    pub fn period(&self) -> Option<usize> {
+        self.cycle.period()
+    }
+}
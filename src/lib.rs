@@ -1 +1,41 @@
-pub mod utils;
\ No newline at end of file
+pub mod backend;
+pub mod bookmarks;
+pub mod buddhabrot;
+pub mod coloring;
+pub mod config;
+pub mod contour;
+pub mod double_double;
+pub mod field;
+pub mod fixed;
+pub mod formula;
+pub mod fractal;
+pub mod gallery;
+pub mod golden;
+pub mod history;
+pub mod kernel;
+pub mod locale;
+pub mod mesh;
+pub mod palette;
+pub mod panel;
+pub mod parse;
+pub mod perturbation;
+#[cfg(feature = "pixels-backend")]
+pub mod pixels_backend;
+pub mod plugin;
+pub mod precision;
+pub mod render;
+pub mod renderer;
+pub mod rpc;
+pub mod scheduler;
+pub mod script;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod server;
+pub mod stats;
+pub mod subdivision;
+pub mod terminal;
+pub mod tonemap;
+pub mod tui;
+pub mod utils;
+pub mod wallpaper;
+pub mod worker;
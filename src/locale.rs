@@ -0,0 +1,167 @@
+use std::{collections::HashMap, env, fmt, fs, io, path::Path};
+
+/// The English language file, which is
+/// embedded in the binary and used as the
+/// fallback for every missing translation.
+pub const EN: &str = include_str!("../lang/en.lang");
+
+/// The Italian language file.
+pub const IT: &str = include_str!("../lang/it.lang");
+
+/// The environment variable used to
+/// choose the language of the UI,
+/// which takes precedence over `LANG`.
+pub const LANG_VAR: &str = "MANDELBRUST_LANG";
+
+/// A table of UI strings, indexed by key.
+/// Every on-screen text of the viewer
+/// should be retrieved through a `Locale`,
+/// so that it can be translated.
+///
+/// Language files contain one `key = value`
+/// pair per line; empty lines and lines
+/// starting with `#` are ignored, and
+/// `\n` is replaced by a newline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Locale {
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    /// Returns a new `Locale` containing the
+    /// strings of the given language file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::locale::Locale;
+    /// let locale = Locale::parse("# comment\nhello = Hello, {}!");
+    ///
+    /// assert_eq!(locale.get("hello"), "Hello, {}!");
+    /// ```
+    pub fn parse(source: &str) -> Self {
+        let strings = source
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (key, value) = line.split_once('=')?;
+
+                Some((key.trim().to_owned(), value.trim().replace("\\n", "\n")))
+            })
+            .collect();
+
+        Self { strings }
+    }
+
+    /// Reads a language file from `path`, using
+    /// the English strings for the missing keys.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let source = fs::read_to_string(path)?;
+
+        Ok(Self::english().merge(Self::parse(&source)))
+    }
+
+    /// Returns the embedded English `Locale`.
+    pub fn english() -> Self {
+        Self::parse(EN)
+    }
+
+    /// Returns the embedded `Locale` of the given
+    /// language code (e.g. `"it"` or `"it_IT.UTF-8"`),
+    /// if a translation for it is shipped with the viewer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::locale::Locale;
+    /// assert!(Locale::builtin("it_IT.UTF-8").is_some());
+    /// assert!(Locale::builtin("xx").is_none());
+    /// ```
+    pub fn builtin(language: &str) -> Option<Self> {
+        let code = language.split(['_', '.', '-']).next()?;
+
+        let source = match code.to_lowercase().as_str() {
+            "en" => EN,
+            "it" => IT,
+            _ => return None,
+        };
+
+        Some(Self::english().merge(Self::parse(source)))
+    }
+
+    /// Returns the `Locale` chosen by the user through
+    /// `MANDELBRUST_LANG` (either a language code or the
+    /// path of a language file) or `LANG`, falling back
+    /// to English.
+    pub fn from_env() -> Self {
+        if let Ok(value) = env::var(LANG_VAR) {
+            if let Some(locale) = Self::builtin(&value) {
+                return locale;
+            }
+
+            if let Ok(locale) = Self::load(&value) {
+                return locale;
+            }
+        }
+
+        env::var("LANG")
+            .ok()
+            .and_then(|lang| Self::builtin(&lang))
+            .unwrap_or_else(Self::english)
+    }
+
+    /// Returns a new `Locale` with the strings
+    /// of `self`, overridden by the ones of `other`.
+    pub fn merge(mut self, other: Self) -> Self {
+        self.strings.extend(other.strings);
+
+        self
+    }
+
+    /// Returns the string associated to `key`,
+    /// or `key` itself if it has no translation,
+    /// so that missing strings are easy to spot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::locale::Locale;
+    /// let locale = Locale::english();
+    ///
+    /// assert_eq!(locale.get("window.title"), "MandelbRust");
+    /// assert_eq!(locale.get("missing.key"), "missing.key");
+    /// ```
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    /// Returns the string associated to `key`,
+    /// replacing its `{}` placeholders in order
+    /// with the given `args`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::locale::Locale;
+    /// let locale = Locale::parse("iterations = Iterations: {} / {}");
+    ///
+    /// assert_eq!(locale.format("iterations", &[&12, &128]), "Iterations: 12 / 128");
+    /// ```
+    pub fn format(&self, key: &str, args: &[&dyn fmt::Display]) -> String {
+        let mut pieces = self.get(key).split("{}");
+        let mut args = args.iter();
+
+        let mut formatted = pieces.next().unwrap_or_default().to_owned();
+
+        for piece in pieces {
+            if let Some(arg) = args.next() {
+                formatted.push_str(&arg.to_string());
+            }
+
+            formatted.push_str(piece);
+        }
+
+        formatted
+    }
+}
@@ -1,10 +1,9 @@
-#![allow(incomplete_features)]
-#![feature(const_generics, const_evaluatable_checked)]
-
-use std::convert::TryInto;
-use ggez::{Context, ContextBuilder, GameResult, conf, event, graphics::{self, Color, DrawParam}, input::mouse, nalgebra::Point2};
-use rayon::{iter::{IndexedParallelIterator, ParallelIterator}, slice::ParallelSliceMut};
-use mandelbrust::utils::*;
+use std::{collections::BTreeMap, convert::TryInto, env, fs::{self, File}, io::{self, BufReader, BufWriter}, path::Path, process, sync::{mpsc::Receiver, Arc}, time::{Duration, Instant, SystemTime}};
+use copypasta::{ClipboardContext, ClipboardProvider};
+use ggez::{Context, ContextBuilder, GameError, GameResult, conf, event::{self, winit_event::{ElementState, Event, KeyboardInput, MouseScrollDelta, WindowEvent}, EventHandler}, graphics::{self, Color, DrawMode, DrawParam, Rect}, input::{keyboard::{self, KeyCode, KeyMods}, mouse::{self, MouseButton}}, nalgebra::Point2};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use mandelbrust::{backend::{self, Input, RenderBackend}, bookmarks::{Bookmark, Bookmarks}, config::Config, buddhabrot::{self, Selection}, coloring::{self, ColorMapper, Cycled, InteriorColoring, OrbitTrap, Registry, TrapShape, WithInterior}, contour, fixed::Fixed, formula::Formula, field, fractal::{self, Fractal, Multibrot}, gallery::{self, Preset}, golden, history::History, kernel::EscapeResult, locale::Locale, mesh::{Heightfield, MeshFormat}, palette::{self, Gradient, ImagePalette, Palette, PaletteFile}, panel::{self, Item, Panel, PanelInput}, parse::{self, Location}, plugin::Plugins, precision::Precision, render::{self, Accumulator, IterationBuffer, Sweep}, renderer::RenderSettings, rpc::{self, Call, Command, Presenter}, scheduler::Scheduler, script, server, stats::{self, RenderStats}, terminal::{self, ColorDepth}, tonemap::{self, ToneMap}, tui, utils::*, wallpaper, worker::{Job, Pass, RenderWorker}};
 
 /// The color red `#FF0000FF`.
 const RED: Color = Color {
@@ -14,180 +13,3464 @@ const RED: Color = Color {
     a: 1.0,
 };
 
+/// The default width (in pixels) of the orbit line.
+const ORBIT_WIDTH: f32 = 1.0;
+
+/// The default width (in pixels) of the band around
+/// the boundary shown by the boundary-only mode.
+const BOUNDARY_WIDTH: f32 = 2.0;
+
+/// The default number of jittered samples of
+/// each pixel, when the antialiasing is on.
+const SAMPLES: usize = 8;
+
+/// The color white `#FFFFFFFF`.
+const WHITE: Color = Color {
+    r: 1.0,
+    g: 1.0,
+    b: 1.0,
+    a: 1.0,
+};
+
+/// The background of the settings panel, a translucent black.
+const PANEL_BACKGROUND: Color = Color {
+    r: 0.0,
+    g: 0.0,
+    b: 0.0,
+    a: 0.7,
+};
+
+/// The color of the grid of the plane, a translucent white.
+const GRID_COLOR: Color = Color {
+    r: 1.0,
+    g: 1.0,
+    b: 1.0,
+    a: 0.3,
+};
+
+/// The default distance (in iterations)
+/// between two consecutive contour lines.
+const CONTOUR_STEP: usize = 4;
+
+/// The distance (in pixels) between two
+/// arrows of the escape-direction overlay.
+const ARROW_SPACING: usize = 35;
+
+/// The length (in pixels) of the arrows
+/// of the escape-direction overlay.
+const ARROW_LENGTH: f32 = 20.0;
+
+/// The side (in pixels) of the tiles used when the
+/// fractal can't be uploaded as a single texture.
+const TEXTURE_TILE: usize = 512;
+
+/// The factors by which the coarse passes of a new view
+/// are smaller than the window, which the worker iterates
+/// before the view at full size.
+const REFINEMENT_FACTORS: [usize; 3] = [8, 4, 2];
+
+/// The factor by which the preview of the Julia set
+/// of the point under the cursor is smaller than the window.
+const JULIA_PREVIEW_FACTOR: usize = 4;
+
+/// The smallest size (in pixels) the window can be resized to.
+const MIN_WINDOW_SIZE: (f32, f32) = (160.0, 120.0);
+
+/// The height (in pixels) of a line of text of the HUD.
+const HUD_LINE_HEIGHT: f32 = 20.0;
+
+/// How long a toast stays on the screen.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// The factor by which each step of
+/// the mouse wheel scales the zoom.
+const ZOOM_FACTOR: f32 = 1.2;
+
+/// The factor by which each step of the mouse
+/// wheel (holding `Ctrl`) scales the iteration cap.
+const ITERATIONS_FACTOR: f32 = 1.25;
+
+/// The smallest and the largest iteration cap.
+const ITERATIONS_RANGE: (usize, usize) = (16, 100_000);
+
+/// The smallest and the largest exponent of the Multibrot sets.
+const POWER_RANGE: (u32, u32) = (2, 16);
+
+/// How long the iteration cap has to stay unchanged
+/// before the fractal is rendered with it.
+const ITERATIONS_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How often the palette file given with `--palette`
+/// is checked for changes, which are applied.
+const PALETTE_POLL: Duration = Duration::from_secs(1);
+
+/// The number of iterations the colors are shifted
+/// by at each frame, while they are cycled.
+const CYCLE_STEP: usize = 1;
+
+/// How long the iteration sweep takes to
+/// raise the iteration cap from 1 to its value.
+const SWEEP_DURATION: Duration = Duration::from_secs(10);
+
+/// How long a view has to stay still to be
+/// recorded in the navigation history.
+const HISTORY_SETTLE: Duration = Duration::from_millis(500);
+
+/// The size (in pixels) of the smallest rectangle that zooms
+/// the view when it's selected with the left button; the
+/// smaller ones are clicks.
+const SELECTION_MIN: f32 = 4.0;
+
+/// The distance (in pixels) from the divider of the
+/// comparison view within which it can be dragged.
+const DIVIDER_GRAB: f32 = 6.0;
+
+/// The environment variable that sets the filter of the logger.
+const LOG_ENV: &str = "MANDELBRUST_LOG";
+
+/// The file where `O` exports the orbits.
+const ORBITS_PATH: &str = "orbits.csv";
+
+/// The file the bookmarks are loaded from, and saved to.
+const BOOKMARKS_PATH: &str = "bookmarks.toml";
+
+/// The configuration file read at startup (see `config`),
+/// unless another one is given with `--config <FILE>`.
+const CONFIG_PATH: &str = "mandelbrust.toml";
+
+/// The file of the references of `--golden`, if none is given.
+const GOLDEN_PATH: &str = "golden.txt";
+
+/// How many times `--bench` renders each scene, if not
+/// specified otherwise, keeping the fastest timings.
+const BENCH_REPEATS: usize = 3;
+
+/// The height (in pixels) of the gradient
+/// shown under the preview of `--extract-palette`.
+const PALETTE_STRIP: usize = 32;
+
+/// The extension of the script files that, dropped
+/// on the window, add their colorings.
+const SCRIPT_EXTENSION: &str = "rhai";
+
+/// The extension of the screenshots, which, dropped
+/// on the window, restore the view they show.
+const PNG_EXTENSION: &str = "png";
+
+/// The extension of the exports streamed into a memory-mapped
+/// PAM file (see `render::render_pam()`).
+const PAM_EXTENSION: &str = "pam";
+
+/// The address the render server listens
+/// on, if none is given.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// The address a session is hosted on, if none is given,
+/// which accepts the instances of the whole network.
+const DEFAULT_SESSION_ADDRESS: &str = "0.0.0.0:7878";
+
+/// The number of points sampled by `--buddhabrot`, if
+/// none is given.
+const BUDDHABROT_SAMPLES: usize = 1_000_000;
+
+/// The iteration cap of `--buddhabrot`, if none is given.
+const BUDDHABROT_ITERATIONS: usize = 1024;
+
+/// The number of decimal digits of the
+/// center shown in the title of the window.
+const TITLE_PRECISION: usize = 4;
+
+/// The angle (in radians) by which the
+/// view is rotated at each key press.
+const ROTATION_STEP: f32 = std::f32::consts::PI / 36.0;
+
+/// The ways of showing the iso-iteration contour lines,
+/// which are drawn at every multiple of the contour step,
+/// like a topographic map of the escape-time field.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum ContourMode {
+    /// Only the filled fractal is drawn.
+    Hidden,
+    /// The contour lines are drawn over the filled fractal.
+    Overlay,
+    /// Only the contour lines are drawn.
+    Only,
+}
+
+impl ContourMode {
+    /// Returns the mode that follows `self`.
+    fn next(self) -> Self {
+        match self {
+            ContourMode::Hidden => ContourMode::Overlay,
+            ContourMode::Overlay => ContourMode::Only,
+            ContourMode::Only => ContourMode::Hidden,
+        }
+    }
+}
+
+/// The point that stays still when zooming with the mouse wheel.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ZoomAnchor {
+    /// The point under the cursor.
+    Cursor,
+    /// The center of the view.
+    Center,
+}
+
+impl ZoomAnchor {
+    /// Returns the other anchor.
+    fn toggled(self) -> Self {
+        match self {
+            ZoomAnchor::Cursor => ZoomAnchor::Center,
+            ZoomAnchor::Center => ZoomAnchor::Cursor,
+        }
+    }
+}
+
+/// How the orbit of the point under the cursor is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OrbitStyle {
+    /// The color of the line.
+    color: Color,
+    /// The width of the line, in pixels.
+    width: f32,
+    /// The maximum number of segments of the line.
+    max_points: usize,
+    /// Whether the window is multisampled,
+    /// which smooths the edges of the line.
+    antialiasing: bool,
+}
+
+impl Default for OrbitStyle {
+    fn default() -> Self {
+        Self {
+            color: RED,
+            width: ORBIT_WIDTH,
+            max_points: ESCAPE_POINT,
+            antialiasing: true,
+        }
+    }
+}
+
+impl OrbitStyle {
+    /// Parses the options of the style in `args`, which can
+    /// contain `--orbit-color <RRGGBB[AA]>`, `--orbit-width <PIXELS>`,
+    /// `--orbit-points <COUNT>` and `--no-antialiasing`,
+    /// exiting with an error message if they are invalid.
+    fn parse(args: &[String]) -> Self {
+        fn fail(message: String) -> ! {
+            eprintln!("{}", message);
+
+            process::exit(2);
+        }
+
+        let mut style = OrbitStyle::default();
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--orbit-color" => {
+                    let value = args.next().map(String::as_str).unwrap_or_default();
+
+                    let [r, g, b, a] = parse::parse_color(value).unwrap_or_else(|err| fail(format!("invalid orbit color: {}", err)));
+
+                    style.color = Color::from_rgba(r, g, b, a);
+                }
+                "--orbit-width" => {
+                    let value = args.next().map(String::as_str).unwrap_or_default();
+
+                    style.width = value.parse().ok().filter(|&width: &f32| width.is_finite() && width > 0.0).unwrap_or_else(|| fail(format!("invalid orbit width `{}` (expected a positive number)", value)));
+                }
+                "--orbit-points" => {
+                    let value = args.next().map(String::as_str).unwrap_or_default();
+
+                    style.max_points = value.parse().unwrap_or_else(|_| fail(format!("invalid number of orbit points `{}`", value)));
+                }
+                "--no-antialiasing" => style.antialiasing = false,
+                _ => {}
+            }
+        }
+
+        style
+    }
+}
+
+/// Returns the iteration cap `iterations` scaled by
+/// `ITERATIONS_FACTOR` for each of the given `steps`
+/// of the mouse wheel, within `ITERATIONS_RANGE`.
+fn scaled_iterations(iterations: usize, steps: f32) -> usize {
+    let scaled = (iterations as f32 * ITERATIONS_FACTOR.powf(steps)).round() as usize;
+
+    scaled.clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1)
+}
+
+/// Returns the color of the `idx`-th pinned orbit; consecutive
+/// orbits get hues spaced by the golden angle, starting far
+/// from red, the default color of the orbit of the cursor.
+fn pin_color(idx: usize) -> Color {
+    // the golden ratio conjugate
+    const GOLDEN: f32 = 0.618_034;
+
+    let [r, g, b, a] = coloring::hue(((idx + 1) as f32 * GOLDEN).fract());
+
+    Color::from_rgba(r, g, b, a)
+}
+
+/// The settings of the right side of the comparison view,
+/// which shows the same view as the left side.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Comparison {
+    /// The index of the coloring.
+    coloring: usize,
+    /// The iteration cap.
+    max_iterations: usize,
+    /// The arithmetic the points are iterated with.
+    precision: Precision,
+    /// The x coordinate of the divider between the two sides.
+    divider: usize,
+    /// Whether the divider is being dragged.
+    dragging: bool,
+    /// Whether the difference between the two sides
+    /// is shown instead of the sides themselves.
+    difference: bool,
+}
+
+/// How the image of the fractal was uploaded to the GPU.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Upload {
+    /// As a single texture.
+    Whole,
+    /// Split into tiles of `TEXTURE_TILE` pixels.
+    Tiled,
+    /// Shrunk by the given factor, and stretched back.
+    Downscaled(usize),
+}
+
+/// Everything the iterations of a frame depend on, so
+/// that they're only done again when one of them changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IterationKey {
+    viewport: Viewport,
+    size: (usize, usize),
+    fractal: usize,
+    power: u32,
+    max_iterations: usize,
+    precision: Precision,
+}
+
+/// Everything the colors of a frame depend on, besides its
+/// iterations, so that it's only colored again (without
+/// iterating it again) when one of them changes.
+#[derive(Debug, Clone, PartialEq)]
+struct ColorKey {
+    coloring: usize,
+    colorings: usize,
+    contour_only: bool,
+    tone_map: Option<ToneMap>,
+    boundary: Option<f32>,
+    jittered: bool,
+    accumulate: bool,
+    comparison: Option<Comparison>,
+    trap: MandelPoint,
+    interior: InteriorColoring,
+    palette: usize,
+    cycle: Option<usize>,
+}
+
+/// The fractal of the last frame, with what's drawn
+/// over it, which the next frames reuse as long as
+/// its iterations (and its colors) don't change.
+#[derive(Debug)]
+struct FrameCache {
+    /// `None` for the frames of the iteration
+    /// sweep, which are never reused.
+    key: Option<IterationKey>,
+    iterations: IterationBuffer,
+    /// The colors of the fractal, with their key.
+    colors: Option<ColorKey>,
+    rgba: Option<Vec<u8>>,
+    /// `None` if the GPU can't hold the fractal in a single
+    /// texture, in which case `rgba` is uploaded at each frame.
+    image: Option<graphics::Image>,
+    /// The contour lines, with the distance between them.
+    contours: Option<(usize, Vec<contour::Segment>)>,
+    arrows: Option<Vec<contour::Segment>>,
+}
+
+/// Creates an image out of an `rgba` buffer of the given
+/// `size`, failing (instead of panicking) if it's too large.
+fn image(ctx: &mut Context, rgba: &[u8], size: (usize, usize)) -> GameResult<graphics::Image> {
+    match (size.0.try_into(), size.1.try_into()) {
+        (Ok(width), Ok(height)) => graphics::Image::from_rgba8(ctx, width, height, rgba),
+        _ => Err(GameError::RenderError(format!("an image of {}x{} pixels is too large", size.0, size.1))),
+    }
+}
+
+/// Returns the key code of `ggez` with the given name (like
+/// `A`, `F1`, `Tab` or `Left`, ignoring the case), for the key
+/// bindings of the configuration; the names are the ones of the
+/// keys of the controls, with `Plus`, `Minus`, `Backspace`,
+/// `Enter` and the digits as well.
+fn key_code(name: &str) -> Option<KeyCode> {
+    let letters = [
+        KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H, KeyCode::I,
+        KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+        KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    ];
+    let digits = [KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9];
+    let functions = [KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12];
+
+    let name = name.to_lowercase();
+    let mut chars = name.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(letter @ 'a'..='z'), None) => return Some(letters[letter as usize - 'a' as usize]),
+        (Some(digit @ '0'..='9'), None) => return Some(digits[digit as usize - '0' as usize]),
+        _ => {}
+    }
+
+    if let Some(idx) = name.strip_prefix('f').and_then(|number| number.parse::<usize>().ok()).filter(|number| (1..=functions.len()).contains(number)) {
+        return Some(functions[idx - 1]);
+    }
+
+    match name.as_str() {
+        "left" => Some(KeyCode::Left),
+        "right" => Some(KeyCode::Right),
+        "up" => Some(KeyCode::Up),
+        "down" => Some(KeyCode::Down),
+        "escape" => Some(KeyCode::Escape),
+        "tab" => Some(KeyCode::Tab),
+        "back" | "backspace" => Some(KeyCode::Back),
+        "return" | "enter" => Some(KeyCode::Return),
+        "space" => Some(KeyCode::Space),
+        "home" => Some(KeyCode::Home),
+        "end" => Some(KeyCode::End),
+        "insert" => Some(KeyCode::Insert),
+        "delete" => Some(KeyCode::Delete),
+        "pageup" => Some(KeyCode::PageUp),
+        "pagedown" => Some(KeyCode::PageDown),
+        "add" | "plus" => Some(KeyCode::Add),
+        "subtract" | "minus" => Some(KeyCode::Minus),
+        "equals" => Some(KeyCode::Equals),
+        "comma" => Some(KeyCode::Comma),
+        "period" => Some(KeyCode::Period),
+        "lbracket" => Some(KeyCode::LBracket),
+        "rbracket" => Some(KeyCode::RBracket),
+        _ => None,
+    }
+}
+
+/// Draws an `rgba` buffer of the given `size` as a single
+/// image, falling back to smaller tiles, and then to
+/// downscaled buffers, if the texture can't be created
+/// (e.g. because of the limits of the GPU on huge windows).
+fn draw_rgba(ctx: &mut Context, rgba: &[u8], size: (usize, usize)) -> GameResult<Upload> {
+    if let Ok(screen) = image(ctx, rgba, size) {
+        graphics::draw(ctx, &screen, DrawParam::default())?;
+
+        return Ok(Upload::Whole);
+    }
+
+    let tiles: GameResult<Vec<_>> = (0..size.1)
+        .step_by(TEXTURE_TILE)
+        .flat_map(|y| (0..size.0).step_by(TEXTURE_TILE).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let (tile, tile_size) = render::crop(rgba, size, (x, y), (TEXTURE_TILE, TEXTURE_TILE));
+
+            image(ctx, &tile, tile_size).map(|tile| (tile, x, y))
+        })
+        .collect();
+
+    if let Ok(tiles) = tiles {
+        for (tile, x, y) in tiles {
+            graphics::draw(ctx, &tile, DrawParam::default().dest([x as f32, y as f32]))?;
+        }
+
+        return Ok(Upload::Tiled);
+    }
+
+    let mut factor = 2;
+
+    loop {
+        let (small, small_size) = render::downscale(rgba, size, factor);
+
+        match image(ctx, &small, small_size) {
+            Ok(screen) => {
+                graphics::draw(ctx, &screen, DrawParam::default().scale([factor as f32, factor as f32]))?;
+
+                return Ok(Upload::Downscaled(factor));
+            }
+            // even a single pixel can't be uploaded
+            Err(err) if small_size == (1, 1) => return Err(err),
+            Err(_) => factor *= 2,
+        }
+    }
+}
+
+/// Draws the given `segments` (in screen
+/// coordinates) as lines of the given `color`.
+fn draw_segments(ctx: &mut Context, segments: &[contour::Segment], color: Color) -> GameResult {
+    if segments.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = graphics::MeshBuilder::new();
+
+    for &(from, to) in segments {
+        builder.line(&[Point2::new(from.0, from.1), Point2::new(to.0, to.1)], 1.0, color)?;
+    }
+
+    let lines = builder.build(ctx)?;
+
+    graphics::draw(ctx, &lines, DrawParam::default())
+}
+
 /// The main struct of the application.
 /// It handles the whole rendering of the fractal
 /// by using the tools provided by the `ggez` crate.
-/// `W` and `H` are respectively the width and the
-/// height of the window.
 /// 
 /// **Note**: this program uses `ggez 0.5.1`, but the current latest version
 /// is `0.6.0`, and this is due to a heavy drop in performance.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
-pub struct MandelPlane<const W: usize, const H: usize> {
-    cursor: Cursor,
+#[derive(Debug)]
+pub struct MandelPlane {
+    /// The size (in pixels) of the window,
+    /// which changes when it's resized.
+    size: (usize, usize),
+    cursor: Option<Cursor>,
+    viewport: Viewport,
+    contour_mode: ContourMode,
+    contour_step: usize,
+    fractals: Vec<Arc<dyn Fractal>>,
+    fractal: usize,
+    /// The Multibrot set shown instead of the selected
+    /// fractal, once the exponent is changed.
+    multibrot: Option<Multibrot>,
+    colorings: Registry,
+    coloring: usize,
+    /// The position of the orbit traps (see `coloring::OrbitTrap`).
+    trap: MandelPoint,
+    /// The coloring of the inside of the set.
+    interior: InteriorColoring,
+    /// The palette file of the palette colorings, if any,
+    /// with the last time it was checked for changes.
+    palette_file: Option<(PaletteFile, Instant)>,
+    /// The number of times the palette was replaced.
+    palette_version: usize,
+    /// The offset of the colors (see `coloring::Cycled`),
+    /// if they are cycled.
+    cycle: Option<usize>,
+    show_field: bool,
+    /// Whether the Julia set of the point under the
+    /// cursor is shown in the top-right corner.
+    julia_preview: bool,
+    /// Whether the HUD is shown in the top-left corner.
+    hud: bool,
+    /// Whether the orbit of the point under the cursor is drawn.
+    show_orbit: bool,
+    /// Whether the grid of the plane is drawn over the fractal.
+    show_grid: bool,
+    /// The settings panel, if it's shown.
+    settings: Option<Panel>,
+    /// Whether the left button was pressed on the
+    /// settings panel since the last frame.
+    settings_pressed: bool,
+    /// The preset of the gradient (see `palette::PRESETS`),
+    /// if one was chosen from the settings panel.
+    gradient: Option<usize>,
+    /// How long the last frame took to be rendered.
+    frame_time: Option<Duration>,
+    zoom_anchor: ZoomAnchor,
+    /// The last position of the cursor while the
+    /// view is dragged with the right button.
+    drag: Option<(f32, f32)>,
+    /// The corners of the rectangle selected with the left
+    /// button, the first one being where it started.
+    selection: Option<((f32, f32), (f32, f32))>,
+    bookmarks: Bookmarks,
+    /// The keys bound to the keys of the controls they act as.
+    bindings: BTreeMap<KeyCode, KeyCode>,
+    /// The index of the last bookmark visited, if any.
+    bookmark: Option<usize>,
+    /// The views left behind, and the ones to go forward to.
+    history: History<Viewport>,
+    /// The last view recorded in the history.
+    visited: Viewport,
+    /// The view of the last frame, with the time it was reached.
+    last_view: (Viewport, Instant),
+    orbit_style: OrbitStyle,
+    pins: Vec<MandelPoint>,
+    comparison: Option<Comparison>,
+    sweep: Option<(Sweep, Instant)>,
+    boundary_width: f32,
+    boundary_only: bool,
+    samples: usize,
+    jittered: bool,
+    accumulate: bool,
+    /// The samples of the job of the current view, with
+    /// the number of the job and the key of their colors.
+    accumulator: Option<(u64, ColorKey, Accumulator)>,
+    frame: Option<FrameCache>,
+    tone_map: Option<ToneMap>,
+    precision: Option<Precision>,
+    active_precision: Precision,
+    worker: RenderWorker,
+    /// The view submitted to the worker, with the number of its
+    /// job, and the number of samples of each pixel it iterates.
+    job: Option<(IterationKey, u64, usize)>,
+    /// The latest coarse pass of the job, while it's iterated.
+    preview: Option<graphics::Image>,
+    /// The worker iterating the right side of the comparison view.
+    comparison_worker: RenderWorker,
+    /// The right side of the comparison view submitted to its
+    /// worker, with the number of its job, and its results
+    /// once they're ready.
+    comparison_job: Option<(IterationKey, u64, Option<Vec<EscapeResult>>)>,
+    max_iterations: usize,
+    pending_iterations: Option<(usize, Instant)>,
+    preset: Option<usize>,
+    toast: Option<(String, Instant)>,
+    title: String,
+    locale: Locale,
+    control: Option<Receiver<Call>>,
+    presenter: Option<Presenter>,
 }
 
-impl<const W: usize, const H: usize> MandelPlane<W, H> {
-    /// Returns an instance of the main struct, with
-    /// the cursor position set on `(0, 0)` by default.
+impl MandelPlane {
+    /// Returns an instance of the main struct, for a window
+    /// of `W`x`H` pixels (until it's resized), with no
+    /// cursor position (until the pointer enters the
+    /// window) and the default `Viewport`, which shows its text
+    /// using the given `locale`, runs the commands
+    /// received from `control`, if any, mirrors
+    /// its view through `presenter`, if any, offers
+    /// the fractals and colorings of the `plugins`
+    /// after the built-in ones, draws the orbit
+    /// of the cursor with `orbit_style`, and shows
+    /// a band of `boundary_width` pixels around the
+    /// boundary in the boundary-only mode, and takes
+    /// `samples` jittered samples of each pixel when
+    /// the antialiasing is on.
     /// 
     /// # Examples
     /// 
     /// ```
     /// # pub use mandelbrust::MandelPlane;
-    /// const W: usize = 300;
-    /// const H: usize = 200;
-    /// 
-    /// let state = &mut MandelPlane::<W, H>::new().expect("Error while trying to build the state"); // `ggez 0.5.1`
+    /// let state = &mut MandelPlane::new(Locale::english(), None, None, Plugins::default(), OrbitStyle::default(), 2.0, 8).expect("Error while trying to build the state"); // `ggez 0.5.1`
     /// ```
-    fn new() -> GameResult<MandelPlane<W, H>> {
+    fn new(locale: Locale, control: Option<Receiver<Call>>, presenter: Option<Presenter>, plugins: Plugins, orbit_style: OrbitStyle, boundary_width: f32, samples: usize) -> GameResult<MandelPlane> {
+        let mut fractals = fractal::builtin();
+        let mut colorings = Registry::builtin();
+
+        fractals.extend(plugins.fractals);
+
+        let fractals = fractals.into_iter().map(Arc::from).collect();
+        colorings.extend(plugins.colorings);
+
         Ok(Self {
-            cursor: Cursor::new((0, 0)),
+            size: (W, H),
+            cursor: None,
+            viewport: Viewport::default(),
+            contour_mode: ContourMode::Hidden,
+            contour_step: CONTOUR_STEP,
+            fractals,
+            fractal: 0,
+            multibrot: None,
+            colorings,
+            coloring: 0,
+            trap: MANDELPOINT_ZERO,
+            interior: InteriorColoring::default(),
+            palette_file: None,
+            palette_version: 0,
+            cycle: None,
+            show_field: false,
+            julia_preview: false,
+            hud: false,
+            show_orbit: true,
+            show_grid: false,
+            settings: None,
+            settings_pressed: false,
+            gradient: None,
+            frame_time: None,
+            zoom_anchor: ZoomAnchor::Cursor,
+            drag: None,
+            selection: None,
+            bookmarks: Bookmarks::default(),
+            bindings: BTreeMap::new(),
+            bookmark: None,
+            history: History::default(),
+            visited: Viewport::default(),
+            last_view: (Viewport::default(), Instant::now()),
+            orbit_style,
+            pins: Vec::new(),
+            comparison: None,
+            sweep: None,
+            boundary_width,
+            boundary_only: false,
+            samples,
+            jittered: false,
+            accumulate: false,
+            accumulator: None,
+            frame: None,
+            tone_map: None,
+            precision: None,
+            active_precision: Precision::default(),
+            worker: RenderWorker::spawn(Scheduler::default()),
+            job: None,
+            preview: None,
+            comparison_worker: RenderWorker::spawn(Scheduler::default()),
+            comparison_job: None,
+            max_iterations: ESCAPE_POINT,
+            pending_iterations: None,
+            preset: None,
+            toast: None,
+            title: String::new(),
+            locale,
+            control,
+            presenter,
         })
     }
 
-    /// Returns the color of the corresponding
-    /// number of `iterations`. The color gradient
-    /// used is the one used in the [Wikipedia page of 
-    /// the Mandelbrot set](https://en.wikipedia.org/wiki/Mandelbrot_set),
-    /// which seems to macth the color gradient used in Ultra Fractal.
-    /// 
-    /// (*Check [this](https://stackoverflow.com/questions/16500656/which-color-gradient-is-used-to-color-mandelbrot-in-wikipedia)
-    /// Stack Overflow question for reference*).
-    fn map_color(iterations: usize) -> [u8; 4] {
-        COLOR_MAP[iterations % 16]
+    /// Shows a short `message` over the fractal
+    /// for `TOAST_DURATION`; the same message is
+    /// not restarted while it's still shown.
+    fn show_toast(&mut self, message: String) {
+        match &self.toast {
+            Some((shown, since)) if *shown == message && since.elapsed() < TOAST_DURATION => {}
+            _ => self.toast = Some((message, Instant::now())),
+        }
     }
-}
 
-impl<const W: usize, const H: usize> event::EventHandler for MandelPlane<W, H>
-where
-    [(); H * W * 4]: ,
-    [(); W * 4]: ,
-{
-    /// The `update()` implementation of the `EventHandler` trait.
-    /// It constantly updates the cursor position.
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        let coords = mouse::position(ctx);
+    /// Returns the precision backend to iterate the view with, which
+    /// is the chosen one, or the one required by the view (see
+    /// `Precision::required()`), if it's automatic, showing a toast
+    /// when the view makes it switch to another one.
+    fn precision(&mut self) -> Precision {
+        let precision = self.precision.unwrap_or_else(|| Precision::required(&self.viewport, self.size));
 
-        let x = coords.x as usize;
-        let y = coords.y as usize;
+        if self.precision.is_none() && precision != self.active_precision {
+            log::debug!("switched the precision from {} to {}", self.active_precision, precision);
 
-        self.cursor.update((x, y));
+            self.show_toast(self.locale.format("toast.precision_auto", &[&precision]));
+        }
 
-        Ok(())
+        self.active_precision = precision;
+
+        precision
     }
 
-    /// The `draw()` implementation of the `EventHandler` trait.
-    /// It draws the Mandelbrot set and a red line, which shows
-    /// the first `utils::ESCAPE_POINT` bounces of the mouse-pointed value.
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        // draw background
-        graphics::clear(ctx, graphics::BLACK);
+    /// Scales the iteration cap by `ITERATIONS_FACTOR` for each
+    /// of the given `steps` of the mouse wheel, showing the new
+    /// value right away, while the fractal is rendered with it
+    /// only after `ITERATIONS_DEBOUNCE`.
+    fn scale_iterations(&mut self, steps: f32) {
+        let current = self.pending_iterations.map_or(self.max_iterations, |(iterations, _)| iterations);
+
+        let iterations = scaled_iterations(current, steps);
 
-        // build the Mandelbrot set
-        let mut rgba = vec![0; H * W * 4]; // has to be on the heap, otherwise it overflows the stack
+        self.pending_iterations = Some((iterations, Instant::now()));
 
-        rgba.par_chunks_mut(W * 4).enumerate().for_each(|(y, chunks_row)| {
-            let mut row = [0; W * 4];
+        self.show_toast(self.locale.format("toast.iterations", &[&iterations]));
+    }
 
-            row.par_chunks_mut(4).enumerate().for_each(|(x, chunks_pixel)| {
-                let pixel = Point::new((x, y));
+    /// Jumps to the given `preset` of the gallery, with its
+    /// iteration cap and coloring, on the Mandelbrot set.
+    fn go_to_preset(&mut self, preset: &Preset) {
+        let orientation = self.viewport.orientation();
 
-                let iter = MandelIter::new(pixel.into());
+        self.viewport = preset.viewport();
+        self.viewport.set_orientation(orientation);
 
-                let iterations = iter.enumerate().take_while(|(idx, _)| *idx <= ESCAPE_POINT).count();
+        self.fractal = 0;
+        self.multibrot = None;
+        self.max_iterations = preset.max_iterations.clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1);
+        self.pending_iterations = None;
+        self.sweep = None;
 
-                let colored_pixel = MandelPlane::<W, H>::map_color(iterations);
+        if let Some(coloring) = self.colorings.position(preset.coloring) {
+            self.coloring = coloring;
+        }
 
-                chunks_pixel.iter_mut().zip(colored_pixel).for_each(|(ch, co)| *ch = co);
-            });
+        self.show_toast(self.locale.format("toast.preset", &[&preset.name]));
+    }
 
-            chunks_row.iter_mut().zip(row).for_each(|(ch, p)| *ch = p);
+    /// Saves the current view, with its iteration cap, fractal
+    /// and coloring, as a new bookmark, writing all of them to
+    /// `BOOKMARKS_PATH`; returns the name of the bookmark.
+    fn save_bookmark(&mut self) -> io::Result<String> {
+        let name = self.bookmarks.next_name();
+
+        self.bookmarks.push(Bookmark {
+            name: name.clone(),
+            center: self.viewport.center().coordinates(),
+            zoom: self.viewport.zoom(),
+            rotation: self.viewport.rotation(),
+            iterations: self.max_iterations,
+            fractal: self.fractal().name().to_owned(),
+            power: self.power(),
+            coloring: self.mapper().name().to_owned(),
         });
 
-        // create the image of the Mandelbrot set
-        let screen = graphics::Image::from_rgba8(ctx, W.try_into().unwrap(), H.try_into().unwrap(), &rgba).unwrap();
+        self.bookmarks.save(Path::new(BOOKMARKS_PATH))?;
 
-        let cursor = self.cursor.coordinates();
+        Ok(name)
+    }
 
-        // invert the y coordinate of the center to preserve
-        // the canonical orientation of the axis of the Mandelbrot
-        // set (in the case of the Mandelbrot set visually
-        // nothing changes since the fractal is symmetric
-        // with respect to the x-axis)
-        let inverted_cursor = Point::new((cursor.0, H - cursor.1));
+    /// Returns the size (in pixels) of the preview of
+    /// the Julia set of the point under the cursor.
+    fn julia_preview_size(&self) -> (usize, usize) {
+        (self.size.0 / JULIA_PREVIEW_FACTOR, self.size.1 / JULIA_PREVIEW_FACTOR)
+    }
 
-        // map the position of the cursor
-        // to a point in the Mandelbrot plane
-        let mapped_cursor: MandelPoint = inverted_cursor.into();
+    /// Returns the top-left corner of the settings panel, on
+    /// the right side of the window, below the Julia preview.
+    fn settings_origin(&self) -> (f32, f32) {
+        (self.size.0 as f32 - panel::PANEL_WIDTH, self.julia_preview_size().1 as f32)
+    }
 
-        // check if the `Mesh` is drawable
-        if mapped_cursor.is_distance_less_than(MANDELPOINT_ZERO, CUSTOM_EPSILON) || !mapped_cursor.is_distance_less_than(MANDELPOINT_ZERO, 2.0) {
-            return Ok(())
+    /// Returns the lines of the HUD, after the iteration cap:
+    /// the value under the cursor (if it's in the window), the
+    /// center and the scale of the view, in scientific notation,
+    /// and the time the last frame took to be rendered.
+    fn hud_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        if let Some(point) = self.precise_cursor_point() {
+            lines.push(self.locale.format("hud.cursor", &[&self.viewport.format_point(point, self.size)]));
         }
 
-        let iter = MandelIter::new(mapped_cursor);
+        let zoom = format!("{:.3e}", self.viewport.zoom());
+        let pixel_size = format!("{:.3e}", self.viewport.pixel_size(self.size));
 
-        // build the set of points for the segments
-        let mut points = vec![Point2::new(cursor.0 as f32, cursor.1 as f32)];
-        
-        for (idx, next_mapped) in iter.enumerate() {
-            // there must be a maximum value of plotted segments
-            if idx == ESCAPE_POINT {
-                break;
+        lines.push(self.locale.format("hud.center", &[&self.viewport.format_point(self.viewport.precise_center(), self.size)]));
+        lines.push(self.locale.format("hud.scale", &[&zoom, &pixel_size]));
+
+        if let Some(frame_time) = self.frame_time {
+            lines.push(self.locale.format("hud.frame_time", &[&format!("{:.1}", frame_time.as_secs_f64() * 1e3)]));
+        }
+
+        lines
+    }
+
+    /// Lays out the settings panel, if it's shown, applying
+    /// the changes made with its widgets, and draws it.
+    fn draw_settings(&mut self, ctx: &mut Context) -> GameResult {
+        let mut settings = match self.settings.take() {
+            Some(settings) => settings,
+            None => return Ok(()),
+        };
+
+        let pointer = self.cursor.map(|cursor| {
+            let (x, y) = cursor.coordinates();
+
+            (x as f32, y as f32)
+        });
+
+        settings.begin(PanelInput {
+            pointer,
+            down: mouse::button_pressed(ctx, MouseButton::Left),
+            pressed: std::mem::take(&mut self.settings_pressed),
+        });
+
+        // the iteration cap goes by powers of 2, as it spans a few orders of magnitude
+        let iterations = self.pending_iterations.map_or(self.max_iterations, |(iterations, _)| iterations);
+        let mut exponent = (iterations as f32).log2();
+
+        if settings.slider(self.locale.format("settings.iterations", &[&iterations]), &mut exponent, ((ITERATIONS_RANGE.0 as f32).log2(), (ITERATIONS_RANGE.1 as f32).log2())) {
+            let scaled = (exponent.exp2().round() as usize).clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1);
+
+            if scaled != iterations {
+                self.pending_iterations = Some((scaled, Instant::now()));
             }
+        }
 
-            // remap the value back to the screen
-            let mut next: Point = next_mapped.into();
-            
-            let (x, y) = next.coordinates_mut();
+        let mut power = self.power() as f32;
 
-            // invert the y coordinate to correctly
-            // map the point on the screen
-            *y = H - *y;
+        if settings.slider(self.locale.format("settings.power", &[&self.power()]), &mut power, (POWER_RANGE.0 as f32, POWER_RANGE.1 as f32)) && power.round() as u32 != self.power() {
+            self.set_power(power.round() as u32);
+        }
+
+        let mut coloring = self.coloring as f32;
 
-            points.push(Point2::new(*x as f32, *y as f32));
+        if settings.slider(self.locale.format("settings.coloring", &[&self.colorings[self.coloring].name()]), &mut coloring, (0.0, self.colorings.len().saturating_sub(1) as f32)) {
+            self.coloring = coloring.round() as usize;
         }
 
-        // build the line
-        let line = graphics::Mesh::new_line(ctx, &points, 1.0, RED)?;
+        let name = self.gradient.map_or_else(|| self.locale.get("settings.gradient_custom").to_owned(), |idx| palette::PRESETS[idx].0.to_owned());
+        let mut gradient = self.gradient.unwrap_or(0) as f32;
 
-        // draw the fractal
-        graphics::draw(ctx, &screen, DrawParam::default())?;
+        if settings.slider(self.locale.format("settings.gradient", &[&name]), &mut gradient, (0.0, palette::PRESETS.len().saturating_sub(1) as f32)) {
+            let idx = gradient.round() as usize;
 
-        // draw the line
-        graphics::draw(ctx, &line, DrawParam::default())?;
+            if let Some(preset) = Gradient::preset(palette::PRESETS[idx].0).filter(|_| self.gradient != Some(idx)) {
+                self.gradient = Some(idx);
+
+                self.set_gradient(preset);
+            }
+        }
+
+        let mut rotation = self.viewport.rotation().to_degrees();
+
+        if settings.slider(self.locale.format("settings.rotation", &[&format!("{:.0}", rotation)]), &mut rotation, (0.0, 360.0)) {
+            self.viewport.rotate(rotation.to_radians() - self.viewport.rotation());
+        }
+
+        settings.checkbox(self.locale.get("settings.orbit").to_owned(), &mut self.show_orbit);
+        settings.checkbox(self.locale.get("settings.grid").to_owned(), &mut self.show_grid);
+        settings.checkbox(self.locale.get("settings.hud").to_owned(), &mut self.hud);
+
+        let (x, y, w, h) = settings.bounds();
+
+        let mut builder = graphics::MeshBuilder::new();
+
+        builder.rectangle(DrawMode::fill(), Rect::new(x, y, w, h), PANEL_BACKGROUND);
+
+        for item in settings.items() {
+            match *item {
+                Item::Slider { track: (x, y, w, h), fraction, .. } => {
+                    builder.line(&[Point2::new(x, y + h / 2.0), Point2::new(x + w, y + h / 2.0)], 2.0, WHITE)?;
+                    builder.rectangle(DrawMode::fill(), Rect::new(x + fraction * w - 3.0, y, 6.0, h), WHITE);
+                }
+                Item::Checkbox { check: (x, y, w, h), checked, .. } => {
+                    let mode = if checked { DrawMode::fill() } else { DrawMode::stroke(1.0) };
+
+                    builder.rectangle(mode, Rect::new(x, y, w, h), WHITE);
+                }
+            }
+        }
+
+        let widgets = builder.build(ctx)?;
+
+        graphics::draw(ctx, &widgets, DrawParam::default())?;
+
+        for item in settings.items() {
+            let (label, dest) = match item {
+                Item::Slider { label, track, .. } => (label, [track.0, track.1 - panel::LABEL_HEIGHT]),
+                Item::Checkbox { label, check, .. } => (label, [check.0 + check.2 + panel::PANEL_PADDING, check.1]),
+            };
+
+            graphics::draw(ctx, &graphics::Text::new(label.as_str()), DrawParam::default().dest(dest).color(WHITE))?;
+        }
+
+        self.settings = Some(settings);
 
-        graphics::present(ctx)?;
-        
         Ok(())
     }
-}
 
-fn main() -> GameResult {
-    let cb = ContextBuilder::new("MandelbRust", "ph04")
-        .window_setup(conf::WindowSetup {
-            title: "MandelbRust".to_owned(),
-            samples: conf::NumSamples::Eight,
-            vsync: true,
-            icon: "".to_owned(),
-            srgb: true,
-        }).window_mode(conf::WindowMode {
-            width: W as f32,
-            height: H as f32,
-            maximized: false,
-            fullscreen_type: conf::FullscreenType::Windowed,
-            borderless: false,
-            min_width: 0.0,
-            max_width: 0.0,
-            min_height: 0.0,
-            max_height: 0.0,
-            resizable: false,
-        });
+    /// Copies the current view, with its iteration cap, to the
+    /// clipboard as a location string (see `parse::Location`),
+    /// which is returned.
+    fn copy_location(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let location = Location { viewport: self.viewport, max_iterations: Some(self.max_iterations) };
 
-    let (ctx, event_loop) = &mut cb.build()?;
-    
-    let state = &mut MandelPlane::<W, H>::new()?;
+        // as many digits as the pixels need
+        let location = format!("{:.*}", self.viewport.center_precision(self.size), location);
+
+        ClipboardContext::new()?.set_contents(location.clone())?;
+
+        Ok(location)
+    }
+
+    /// Moves to the location string in the clipboard (see
+    /// `parse::Location`), setting its iteration cap, if any;
+    /// the orientation is kept unless the location has one.
+    fn paste_location(&mut self) -> Result<Location, Box<dyn std::error::Error + Send + Sync>> {
+        let location: Location = ClipboardContext::new()?.get_contents()?.parse()?;
+
+        self.viewport = location.viewport;
+
+        if let Some(max_iterations) = location.max_iterations {
+            self.max_iterations = max_iterations.clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1);
+            self.pending_iterations = None;
+            self.sweep = None;
+        }
+
+        Ok(location)
+    }
+
+    /// Jumps to the bookmark after the last one visited
+    /// (or to the first one), with its settings, like
+    /// `go_to_preset()`; the fractals and the colorings
+    /// that are not available anymore are left unchanged.
+    fn next_bookmark(&mut self) {
+        if self.bookmarks.is_empty() {
+            self.show_toast(self.locale.get("toast.no_bookmarks").to_owned());
+
+            return;
+        }
+
+        let idx = self.bookmark.map_or(0, |idx| (idx + 1) % self.bookmarks.len());
+        let bookmark = self.bookmarks[idx].clone();
+
+        self.bookmark = Some(idx);
+
+        let orientation = self.viewport.orientation();
+
+        self.viewport = bookmark.viewport();
+        self.viewport.set_orientation(orientation);
+
+        if let Some(fractal) = self.fractals.iter().position(|fractal| fractal.name() == bookmark.fractal) {
+            self.fractal = fractal;
+        }
+
+        let power = bookmark.power.clamp(POWER_RANGE.0, POWER_RANGE.1);
+
+        self.multibrot = Some(Multibrot::new(power)).filter(|_| power > 2);
+        self.max_iterations = bookmark.iterations.clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1);
+        self.pending_iterations = None;
+        self.sweep = None;
+
+        if let Some(coloring) = self.colorings.position(&bookmark.coloring) {
+            self.coloring = coloring;
+        }
+
+        self.show_toast(self.locale.format("toast.bookmark", &[&bookmark.name]));
+    }
+
+    /// Jumps to the preset of the gallery after the last one
+    /// visited (or to the first one), see `go_to_preset()`.
+    fn next_preset(&mut self) {
+        let idx = self.preset.map_or(0, |idx| (idx + 1) % gallery::PRESETS.len());
+
+        self.preset = Some(idx);
+
+        self.go_to_preset(&gallery::PRESETS[idx]);
+    }
+
+    /// Jumps to a random point near the boundary of the
+    /// fractal, zooming by a random factor and adjusting
+    /// the iteration cap (see `gallery::random_jump()`).
+    fn random_jump(&mut self) {
+        let jump = gallery::random_jump(self.fractal(), &self.viewport, self.max_iterations, &mut rand::thread_rng());
+
+        if let Some((viewport, max_iterations)) = jump {
+            log::info!("jumped to {}", viewport);
+
+            self.viewport = viewport;
+            self.max_iterations = max_iterations.clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1);
+            self.pending_iterations = None;
+            self.sweep = None;
+
+            self.show_toast(self.locale.format("toast.iterations", &[&self.max_iterations]));
+        }
+    }
+
+    /// Shows the comparison view, with the next coloring, 16
+    /// times the iteration cap and the same precision backend
+    /// on the right side, or hides it.
+    fn toggle_comparison(&mut self) {
+        self.comparison = match self.comparison {
+            Some(_) => None,
+            None => Some(Comparison {
+                coloring: (self.coloring + 1) % self.colorings.len(),
+                max_iterations: (self.max_iterations * 16).min(ITERATIONS_RANGE.1),
+                precision: self.active_precision,
+                divider: self.size.0 / 2,
+                dragging: false,
+                difference: false,
+            }),
+        };
+    }
+
+    /// Shows the difference between the two sides of the
+    /// comparison view (which is opened if needed) as a
+    /// heatmap, or goes back to showing them side by side.
+    fn toggle_difference(&mut self) {
+        if self.comparison.is_none() {
+            self.toggle_comparison();
+        }
+
+        if let Some(comparison) = &mut self.comparison {
+            comparison.difference = !comparison.difference;
+        }
+    }
+
+    /// Starts the iteration sweep, which raises the iteration
+    /// cap of the Mandelbrot set from 1 to its value over
+    /// `SWEEP_DURATION`, or stops it.
+    fn toggle_sweep(&mut self) {
+        self.sweep = match self.sweep {
+            Some(_) => None,
+            None => Some((Sweep::new(&self.viewport, self.size, Seed::default()), Instant::now())),
+        };
+    }
+
+    /// Returns the results of the current frame of the iteration
+    /// sweep, together with its iteration cap, which grows
+    /// exponentially, so that the early iterations, which change
+    /// the picture the most, are not over in a blink.
+    fn sweep_frame(&mut self) -> Option<(Vec<EscapeResult>, usize)> {
+        let (sweep, since) = self.sweep.as_mut()?;
+
+        // a new view starts the sweep over
+        if *sweep.viewport() != self.viewport {
+            *sweep = Sweep::new(&self.viewport, self.size, Seed::default());
+            *since = Instant::now();
+        }
+
+        let progress = (since.elapsed().as_secs_f32() / SWEEP_DURATION.as_secs_f32()).min(1.0);
+
+        let max_iterations = (self.max_iterations as f32).powf(progress).round() as usize;
+
+        sweep.advance(max_iterations);
+
+        Some((sweep.results(), max_iterations))
+    }
+
+    /// Zooms the view by `ZOOM_FACTOR` for each of the given
+    /// `steps` of the mouse wheel, around the zoom anchor
+    /// (the center is used while the cursor is outside).
+    fn zoom(&mut self, steps: f32) {
+        let factor = ZOOM_FACTOR.powf(steps);
+
+        match (self.zoom_anchor, self.precise_cursor_point()) {
+            (ZoomAnchor::Cursor, Some(point)) => self.viewport.zoom_at(point, factor),
+            _ => self.viewport.zoom_by(factor),
+        }
+    }
+
+    /// Returns the point of the plane under the cursor, if
+    /// the cursor is inside the window.
+    fn cursor_point(&self) -> Option<MandelPoint> {
+        // orient the y coordinate of the cursor, which by
+        // default preserves the canonical orientation of the
+        // axis of the Mandelbrot set (in the case of the
+        // Mandelbrot set visually nothing changes since the
+        // fractal is symmetric with respect to the x-axis)
+        let oriented_cursor = self.viewport.orient(Point::new(self.cursor?.coordinates()), self.size);
+
+        // map the position of the cursor
+        // to a point in the Mandelbrot plane
+        Some(oriented_cursor.to_mandel(&self.viewport, self.size))
+    }
+
+    /// Returns the point of the plane under the cursor, like
+    /// `cursor_point()`, but with the precision of the center.
+    fn precise_cursor_point(&self) -> Option<(Fixed, Fixed)> {
+        let oriented_cursor = self.viewport.orient(Point::new(self.cursor?.coordinates()), self.size);
+
+        Some(self.viewport.plane_point(oriented_cursor, self.size))
+    }
+
+    /// Draws the orbit of `mapped_point` as a line of the
+    /// given `color`, starting from the point itself.
+    fn draw_orbit(&self, ctx: &mut Context, mapped_point: MandelPoint, color: Color) -> GameResult {
+        // check if the `Mesh` is drawable
+        if mapped_point.is_distance_less_than(MANDELPOINT_ZERO, CUSTOM_EPSILON) || !mapped_point.is_distance_less_than(MANDELPOINT_ZERO, 2.0) {
+            return Ok(());
+        }
+
+        // there must be a maximum value of plotted segments
+        let orbit = self.fractal().orbit(mapped_point, self.orbit_style.max_points);
+
+        // the fractal may not provide its orbits
+        if orbit.is_empty() {
+            return Ok(());
+        }
+
+        // build the set of points for the segments
+        let points: Vec<_> = std::iter::once(mapped_point)
+            .chain(orbit)
+            .map(|next_mapped| {
+                // remap the value back to the screen, which
+                // also counter-rotates the line with the view
+                let next = next_mapped.to_screen(&self.viewport, self.size);
+
+                // orient the y coordinate to correctly
+                // map the point on the screen
+                let (x, y) = self.viewport.orient(next, self.size).coordinates();
+
+                Point2::new(x as f32, y as f32)
+            })
+            .collect();
+
+        // build the line
+        let line = graphics::Mesh::new_line(ctx, &points, self.orbit_style.width, color)?;
+
+        // draw the line
+        graphics::draw(ctx, &line, DrawParam::default())
+    }
+
+    /// Returns the number of samples of each pixel of the
+    /// view: all of them while they're accumulated, the ones
+    /// of the antialiasing, or just the full pass.
+    fn wanted_samples(&self) -> usize {
+        match (self.accumulate, self.jittered) {
+            (true, _) => render::MAX_SAMPLES,
+            (false, true) => self.samples,
+            (false, false) => 1,
+        }
+    }
+
+    /// Returns the colors of the current view, whose full pass
+    /// is `iterations`, averaged over the samples the worker
+    /// iterated since (see `Job::samples`), which are colored as
+    /// they come, so that they converge while the view doesn't
+    /// change; returns `None` if the view only takes one sample
+    /// (see `wanted_samples()`).
+    fn accumulated(&mut self, iterations: &IterationBuffer, colors: &ColorKey) -> Option<Vec<u8>> {
+        let wanted = self.wanted_samples();
+
+        // the frames of the sweep change at every frame
+        let (key, mut id, samples) = self.job.filter(|_| wanted > 1 && self.sweep.is_none())?;
+
+        let mut accumulator = match self.accumulator.take() {
+            Some((job, colored, accumulator)) if job == id && colored == *colors => accumulator,
+            previous => {
+                // the samples of the job were colored differently,
+                // or never asked for, so they're iterated again
+                if samples < wanted || previous.is_some_and(|(job, _, _)| job == id) {
+                    id = self.worker.submit(Job {
+                        fractal: self.shared_fractal(),
+                        viewport: key.viewport,
+                        size: key.size,
+                        max_iterations: key.max_iterations,
+                        precision: key.precision,
+                        coarse: Vec::new(),
+                        samples: 1..wanted,
+                    });
+
+                    self.job = Some((key, id, wanted));
+                }
+
+                // the full pass is the first sample
+                let mut accumulator = Accumulator::new(key.size);
+
+                accumulator.add(&iterations.colorize(&self.mapper()));
+
+                accumulator
+            }
+        };
+
+        // the passes of the canceled jobs are thrown away
+        while let Some(pass) = self.worker.try_recv() {
+            if pass.job == id && pass.sample > 0 {
+                accumulator.add(&render::colorize(&pass.results, &self.mapper(), iterations.max_iterations()));
+            }
+        }
+
+        let rgba = accumulator.rgba();
+
+        self.accumulator = Some((id, colors.clone(), accumulator));
+
+        Some(rgba)
+    }
+
+    /// Returns whether the colors of the current view can't change
+    /// anymore, which they do while samples are accumulated, and
+    /// until the right side of the comparison view is iterated.
+    fn settled(&self) -> bool {
+        let wanted = self.wanted_samples();
+        let accumulated = self.tone_map.is_some() || wanted == 1 || self.accumulator.as_ref().is_some_and(|(_, _, accumulator)| accumulator.samples() >= wanted);
+
+        accumulated && (self.comparison.is_none() || self.comparison_job.as_ref().is_some_and(|(_, _, results)| results.is_some()))
+    }
+
+    /// Submits the right side of the comparison view, iterated
+    /// with the settings of `key`, to its worker if it's new,
+    /// and keeps its results once they're ready.
+    fn poll_comparison(&mut self, key: IterationKey) {
+        if self.comparison_job.as_ref().is_none_or(|(submitted, _, _)| *submitted != key) {
+            let id = self.comparison_worker.submit(Job {
+                fractal: self.shared_fractal(),
+                viewport: key.viewport,
+                size: key.size,
+                max_iterations: key.max_iterations,
+                precision: key.precision,
+                coarse: Vec::new(),
+                samples: 0..1,
+            });
+
+            self.comparison_job = Some((key, id, None));
+        }
+
+        if let Some((_, id, results)) = &mut self.comparison_job {
+            // the passes of the canceled jobs are thrown away
+            while let Some(pass) = self.comparison_worker.try_recv() {
+                if pass.job == *id {
+                    *results = Some(pass.results);
+                }
+            }
+        }
+    }
+
+    /// Returns the key of the iterations of the current frame
+    /// (see `FrameCache`), or `None` during the iteration sweep.
+    fn iteration_key(&mut self) -> Option<IterationKey> {
+        if self.sweep.is_some() {
+            return None;
+        }
+
+        Some(IterationKey {
+            viewport: self.viewport,
+            size: self.size,
+            fractal: self.fractal,
+            power: self.power(),
+            max_iterations: self.max_iterations,
+            precision: self.precision(),
+        })
+    }
+
+    /// Returns the key of the colors of the current frame (see `FrameCache`).
+    fn color_key(&self) -> ColorKey {
+        ColorKey {
+            coloring: self.coloring,
+            colorings: self.colorings.len(),
+            contour_only: self.contour_mode == ContourMode::Only,
+            tone_map: self.tone_map,
+            boundary: self.boundary(),
+            jittered: self.jittered,
+            accumulate: self.accumulate,
+            comparison: self.comparison,
+            trap: self.trap,
+            interior: self.interior,
+            palette: self.palette_version,
+            cycle: self.cycle,
+        }
+    }
+
+    /// Returns the full pass of the view of `key` once the worker
+    /// iterated it, submitting it if it's new, and keeps the
+    /// latest of its coarse passes, stretched to the window
+    /// and colored, as the preview.
+    fn poll_worker(&mut self, ctx: &mut Context, key: IterationKey) -> Option<Pass> {
+        let id = match self.job {
+            Some((submitted, id, _)) if submitted == key => id,
+            _ => {
+                let samples = self.wanted_samples();
+
+                let id = self.worker.submit(Job {
+                    fractal: self.shared_fractal(),
+                    viewport: key.viewport,
+                    size: key.size,
+                    max_iterations: key.max_iterations,
+                    precision: key.precision,
+                    coarse: REFINEMENT_FACTORS.to_vec(),
+                    samples: 0..samples,
+                });
+
+                self.job = Some((key, id, samples));
+                self.preview = None;
+
+                id
+            }
+        };
+
+        let mut coarse = None;
+
+        // the passes of the canceled jobs are thrown away
+        while let Some(pass) = self.worker.try_recv() {
+            match pass.factor {
+                _ if pass.job != id => {}
+                // the jittered samples come after the full pass
+                1 if pass.sample == 0 => return Some(pass),
+                1 => {}
+                _ => coarse = Some(pass),
+            }
+        }
+
+        if let Some(pass) = coarse {
+            let rgba = render::upscale(&render::colorize(&pass.results, &self.mapper(), key.max_iterations), pass.size, self.size);
+
+            self.preview = image(ctx, &rgba, self.size).ok();
+        }
+
+        None
+    }
+
+    /// Draws the fractal, its contour lines (depending on the
+    /// contour mode) and the escape-direction arrows (if enabled);
+    /// a new view is iterated by the worker, starting with the
+    /// coarse passes of `REFINEMENT_FACTORS`, which are shown
+    /// (or the previous view, until the first one is ready)
+    /// until the full one is ready, while a view is only
+    /// iterated again when something its iterations depend on
+    /// changes, and only colored again when its colors do.
+    fn draw_fractal(&mut self, ctx: &mut Context) -> GameResult {
+        let start = Instant::now();
+
+        let (key, color_key) = (self.iteration_key(), self.color_key());
+
+        // nothing the iterations depend on changed since the last frame
+        let (cached, stale) = match self.frame.take() {
+            Some(frame) if frame.key.is_some() && frame.key == key => (Some(frame), None),
+            frame => (None, frame),
+        };
+
+        let mut iterated = None;
+
+        if let (None, Some(key)) = (&cached, key) {
+            iterated = self.poll_worker(ctx, key);
+
+            if iterated.is_none() {
+                let screen = self.preview.as_ref().or_else(|| stale.as_ref().and_then(|frame| frame.image.as_ref()));
+
+                if let Some(screen) = screen.filter(|_| self.contour_mode != ContourMode::Only) {
+                    graphics::draw(ctx, screen, DrawParam::default())?;
+                }
+
+                self.frame = stale;
+
+                return Ok(());
+            }
+        }
+
+        let elapsed = iterated.as_ref().map_or(Duration::ZERO, |pass| pass.elapsed);
+
+        let recolored = self.contour_mode != ContourMode::Only && (cached.as_ref().is_none_or(|frame| frame.colors.as_ref() != Some(&color_key)) || !self.settled());
+        let rendered = cached.is_none() || recolored;
+
+        let mut frame = match cached {
+            Some(frame) => frame,
+            None => {
+                // keep the result of every pixel, which the contour lines need
+                let (results, max_iterations) = match iterated {
+                    Some(pass) => {
+                        log::trace!("iterated the frame in {:?}", pass.elapsed);
+
+                        (pass.results, self.max_iterations)
+                    }
+                    // the frames without a key are the ones of the sweep
+                    None => match self.sweep_frame() {
+                        Some((results, max_iterations)) => {
+                            self.show_toast(self.locale.format("toast.iterations", &[&max_iterations]));
+
+                            (results, max_iterations)
+                        }
+                        None => return Ok(()),
+                    },
+                };
+
+                FrameCache { key, iterations: IterationBuffer::new(results, max_iterations), colors: None, rgba: None, image: None, contours: None, arrows: None }
+            }
+        };
+
+        let (results, max_iterations) = (frame.iterations.results(), frame.iterations.max_iterations());
+
+        if self.contour_mode != ContourMode::Only {
+            // the accumulated samples change the colors until they settle
+            if recolored {
+                // color the Mandelbrot set, sampling each
+                // pixel many times if the antialiasing is on,
+                // and more and more while the view is still
+                let mut rgba = match self.tone_map {
+                    Some(operator) => tonemap::tone_mapped(results, operator),
+                    // the cycled colors only recolor the iterations
+                    None if self.cycle.is_some() => frame.iterations.colorize(&self.mapper()),
+                    None => match self.accumulated(&frame.iterations, &color_key) {
+                        Some(rgba) => rgba,
+                        None => frame.iterations.colorize(&self.mapper()),
+                    },
+                };
+
+                if let Some(width) = self.boundary() {
+                    render::keep_boundary(&mut rgba, results, self.viewport.pixel_size(self.size), width);
+                }
+
+                // render the right side of the comparison view with its own
+                // settings, once its worker iterated it (the frames of the
+                // sweep have none)
+                let right_key = self.comparison.zip(frame.key).map(|(comparison, key)| IterationKey { max_iterations: comparison.max_iterations, precision: comparison.precision, ..key });
+
+                if let Some(key) = right_key {
+                    self.poll_comparison(key);
+                }
+
+                let right = self.comparison_job.as_ref().filter(|(submitted, _, _)| Some(*submitted) == right_key).and_then(|(_, _, results)| results.as_deref());
+
+                if let Some((comparison, right)) = self.comparison.zip(right) {
+                    if comparison.difference {
+                        rgba = render::difference(results, right);
+                    } else {
+                        let mut right_rgba = render::colorize(right, &self.cycled(&self.colorings[comparison.coloring]), comparison.max_iterations);
+
+                        if let Some(width) = self.boundary() {
+                            render::keep_boundary(&mut right_rgba, right, self.viewport.pixel_size(self.size), width);
+                        }
+
+                        render::splice(&mut rgba, &right_rgba, self.size, comparison.divider);
+                    }
+                }
+
+                frame.image = image(ctx, &rgba, self.size).ok();
+                frame.rgba = Some(rgba);
+                frame.colors = Some(color_key);
+            }
+
+            match (&frame.image, &frame.rgba) {
+                (Some(screen), _) => graphics::draw(ctx, screen, DrawParam::default())?,
+                // draw the fractal in tiles, or downscaled, if the GPU doesn't allow it as a whole
+                (None, Some(rgba)) => match draw_rgba(ctx, rgba, self.size)? {
+                    Upload::Whole => {}
+                    Upload::Tiled => self.show_toast(self.locale.get("toast.tiled").to_owned()),
+                    Upload::Downscaled(factor) => self.show_toast(self.locale.format("toast.downscaled", &[&factor])),
+                },
+                (None, None) => {}
+            }
+        }
+
+        if rendered {
+            let frame_time = elapsed + start.elapsed();
+
+            self.frame_time = Some(frame_time);
+
+            stats::record(&RenderStats::new(self.size, &self.viewport, max_iterations, self.fractal().name(), frame_time));
+        }
+
+        if self.contour_mode != ContourMode::Hidden {
+            let step = self.contour_step;
+
+            if frame.contours.as_ref().is_none_or(|(contour_step, _)| *contour_step != step) {
+                let levels: Vec<usize> = (step..=max_iterations).step_by(step).collect();
+
+                let iterations: Vec<usize> = frame.iterations.results().par_iter().map(|result| result.iterations).collect();
+
+                frame.contours = Some((step, contour::contours(&iterations, self.size, &levels)));
+            }
+
+            // draw the contour lines
+            if let Some((_, segments)) = &frame.contours {
+                draw_segments(ctx, segments, WHITE)?;
+            }
+        }
+
+        if self.show_field {
+            let results = frame.iterations.results();
+            let arrows = frame.arrows.get_or_insert_with(|| field::escape_arrows(results, self.size, ARROW_SPACING, ARROW_LENGTH, &self.viewport));
+
+            // draw the escape directions
+            draw_segments(ctx, arrows, WHITE)?;
+        }
+
+        self.frame = Some(frame);
+
+        Ok(())
+    }
+
+    /// Returns the width of the band around the boundary
+    /// that is shown, if the boundary-only mode is on.
+    fn boundary(&self) -> Option<f32> {
+        Some(self.boundary_width).filter(|_| self.boundary_only)
+    }
+
+    /// Returns the selected fractal, or
+    /// the Multibrot set, if there is one.
+    fn fractal(&self) -> &dyn Fractal {
+        match &self.multibrot {
+            Some(multibrot) => multibrot,
+            None => self.fractals[self.fractal].as_ref(),
+        }
+    }
+
+    /// Returns the fractal (see `fractal()`), for the worker.
+    fn shared_fractal(&self) -> Arc<dyn Fractal> {
+        match self.multibrot {
+            Some(multibrot) => Arc::new(multibrot),
+            None => Arc::clone(&self.fractals[self.fractal]),
+        }
+    }
+
+    /// Returns the exponent of the Multibrot set
+    /// (see `fractal()`), `2` if there is none.
+    fn power(&self) -> u32 {
+        self.multibrot.map_or(2, |multibrot| multibrot.power())
+    }
+
+    /// Shows the Multibrot set with the given exponent (clamped
+    /// to `POWER_RANGE`), or the selected fractal for `2`, moving
+    /// to the view that shows all of it, as the sets of the
+    /// larger exponents are smaller.
+    fn set_power(&mut self, power: u32) {
+        let power = power.clamp(POWER_RANGE.0, POWER_RANGE.1);
+
+        self.multibrot = Some(Multibrot::new(power)).filter(|_| power > 2);
+
+        let orientation = self.viewport.orientation();
+
+        self.viewport = self.fractal().default_viewport();
+        self.viewport.set_orientation(orientation);
+
+        self.show_toast(self.locale.format("toast.power", &[&power]));
+    }
+
+    /// Moves the orbit traps (see `coloring::OrbitTrap`) to `center`,
+    /// replacing the colorings of every shape.
+    fn move_traps(&mut self, center: MandelPoint) {
+        self.trap = center;
+
+        for shape in [TrapShape::Point, TrapShape::Line, TrapShape::Cross] {
+            self.colorings.register(Box::new(OrbitTrap { shape, center }));
+        }
+
+        self.show_toast(self.locale.format("toast.trap", &[&center]));
+    }
+
+    /// Replaces the colors of the palette colorings
+    /// (see `coloring::WikipediaPalette`) with `palette`.
+    fn set_palette(&mut self, palette: Palette) {
+        self.colorings.register(Box::new(coloring::WikipediaPalette { palette: palette.clone() }));
+        self.colorings.register(Box::new(coloring::SmoothPalette::from(&palette)));
+
+        self.palette_version += 1;
+    }
+
+    /// Replaces the palette colorings with `gradient`, sampled
+    /// into `palette::GRADIENT_LENGTH` colors by the banded one.
+    fn set_gradient(&mut self, gradient: Gradient) {
+        self.set_palette(gradient.to_palette(palette::GRADIENT_LENGTH));
+
+        self.colorings.register(Box::new(coloring::SmoothPalette { gradient, period: palette::GRADIENT_LENGTH as f32 }));
+    }
+
+    /// Applies the palette file again if it changed since it
+    /// was last checked, at most once every `PALETTE_POLL`.
+    fn poll_palette(&mut self) {
+        let reloaded = match &mut self.palette_file {
+            Some((file, checked)) if checked.elapsed() >= PALETTE_POLL => {
+                *checked = Instant::now();
+
+                file.reload().map(|palette| (palette, file.path().display().to_string()))
+            }
+            _ => None,
+        };
+
+        match reloaded {
+            Some((Ok(palette), path)) => {
+                self.set_palette(palette);
+                self.show_toast(self.locale.format("toast.palette", &[&path]));
+            }
+            Some((Err(err), path)) => log::error!("{}", self.locale.format("error.palette", &[&path, &err])),
+            None => {}
+        }
+    }
+
+    /// Records the view in the navigation history once it
+    /// stayed still for `HISTORY_SETTLE`, so that the views
+    /// passed through while panning or zooming are skipped.
+    fn record_view(&mut self) {
+        if self.viewport != self.last_view.0 {
+            self.last_view = (self.viewport, Instant::now());
+        } else if self.viewport != self.visited && self.drag.is_none() && self.last_view.1.elapsed() >= HISTORY_SETTLE {
+            self.history.record(self.visited);
+            self.visited = self.viewport;
+        }
+    }
+
+    /// Goes back to the previous view of the navigation
+    /// history, or forward again if `redo` is set.
+    fn navigate(&mut self, redo: bool) {
+        // the current view is recorded even if it's not still yet
+        if self.viewport != self.visited {
+            self.history.record(self.visited);
+            self.visited = self.viewport;
+        }
+
+        let viewport = if redo { self.history.redo(self.viewport) } else { self.history.undo(self.viewport) };
+
+        if let Some(viewport) = viewport {
+            self.viewport = viewport;
+            self.visited = viewport;
+        }
+    }
+
+    /// Returns the selected coloring, with the selected
+    /// coloring of the interior, cycled if it's on.
+    fn mapper(&self) -> Cycled<WithInterior<'_>> {
+        self.cycled(&self.colorings[self.coloring])
+    }
+
+    /// Returns `mapper` with the selected coloring
+    /// of the interior, cycled if it's on.
+    fn cycled<'a>(&self, mapper: &'a dyn ColorMapper) -> Cycled<WithInterior<'a>> {
+        Cycled { mapper: WithInterior { mapper, interior: self.interior }, offset: self.cycle.unwrap_or_default() }
+    }
+
+    /// Returns the state of the viewer, as
+    /// reported by the control interface.
+    fn state(&self) -> Value {
+        let (re, im) = self.viewport.center().coordinates();
+
+        json!({
+            "viewport": self.viewport.to_string(),
+            "re": re,
+            "im": im,
+            "zoom": self.viewport.zoom(),
+            "rotation": self.viewport.rotation().to_degrees(),
+            "iterations": self.max_iterations,
+            "fractal": self.fractal().name(),
+            "coloring": self.mapper().name(),
+            "width": self.size.0,
+            "height": self.size.1,
+        })
+    }
+
+    /// Runs a `command` received from the control interface.
+    fn run_command(&mut self, command: Command) -> Result<Value, String> {
+        log::debug!("running the control command {:?}", command);
+
+        match command {
+            Command::Goto(viewport) => {
+                viewport.check(self.size).map_err(|err| err.to_string())?;
+
+                self.viewport = viewport;
+            }
+            Command::SetColoring(name) => {
+                self.coloring = self.colorings.position(&name).ok_or_else(|| format!("unknown coloring `{}`", name))?;
+            }
+            Command::Export { path, size } => {
+                let size = size.unwrap_or(self.size);
+
+                // the largest exports are streamed into the file, instead of held in memory
+                if Path::new(&path).extension().and_then(|extension| extension.to_str()) == Some(PAM_EXTENSION) {
+                    render::render_pam(Path::new(&path), self.fractal(), &self.viewport, size, self.max_iterations, &self.mapper()).map_err(|err| format!("could not write `{}`: {}", path, err))?;
+                } else {
+                    let rgba = render::render_rgba(self.fractal(), &self.viewport, size, self.max_iterations, &self.mapper(), self.boundary()).map_err(|err| err.to_string())?;
+
+                    File::create(&path)
+                        .and_then(|file| render::write_png(BufWriter::new(file), &rgba, size))
+                        .map_err(|err| format!("could not write `{}`: {}", path, err))?;
+                }
+
+                log::info!("exported `{}`", path);
+            }
+            Command::ExportOrbits(path) => {
+                self.export_orbits(&path).map_err(|err| format!("could not write `{}`: {}", path, err))?;
+            }
+            Command::State => {}
+        }
+
+        Ok(self.state())
+    }
+
+    /// Exports the pinned orbits (or the orbit of the cursor,
+    /// if none is pinned) to the CSV file at `path` (see
+    /// `fractal::write_orbits_csv()`), up to the iteration cap.
+    fn export_orbits(&self, path: &str) -> io::Result<()> {
+        let points = if self.pins.is_empty() { self.cursor_point().into_iter().collect() } else { self.pins.clone() };
+
+        fractal::write_orbits_csv(BufWriter::new(File::create(path)?), self.fractal(), &points, self.max_iterations)?;
+
+        log::info!("exported {} orbits to `{}`", points.len(), path);
+
+        Ok(())
+    }
+
+    /// Loads a file dropped on the window: a script (see `script`)
+    /// adds its colorings, applying the first one, while any other
+    /// file is read as a view (like the `[VIEWPORT]` argument);
+    /// returns the message confirming what was loaded.
+    fn load_dropped(&mut self, path: &Path) -> Result<String, String> {
+        if path.extension().and_then(|extension| extension.to_str()) == Some(SCRIPT_EXTENSION) {
+            let script = script::load(path).map_err(|err| err.to_string())?;
+
+            let name = script.colorings.first().map(|coloring| coloring.name().to_owned()).ok_or("the script defines no coloring")?;
+
+            self.colorings.extend(script.colorings);
+            self.coloring = self.colorings.position(&name).unwrap_or_default();
+
+            return Ok(self.locale.format("toast.dropped_coloring", &[&name]));
+        }
+
+        if path.extension().and_then(|extension| extension.to_str()) == Some(PNG_EXTENSION) {
+            let file = File::open(path).map_err(|err| err.to_string())?;
+
+            let (viewport, max_iterations) = render::read_png_view(BufReader::new(file)).map_err(|err| err.to_string())?.ok_or("the image has no view")?;
+
+            viewport.check(self.size).map_err(|err| err.to_string())?;
+
+            self.viewport = viewport;
+            self.max_iterations = max_iterations.clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1);
+            self.pending_iterations = None;
+            self.sweep = None;
+
+            return Ok(self.locale.format("toast.dropped_image", &[&viewport]));
+        }
+
+        let viewport = fs::read_to_string(path).map_err(|err| err.to_string())?.trim().parse::<Viewport>().map_err(|err| err.to_string())?;
+
+        viewport.check(self.size).map_err(|err| err.to_string())?;
+
+        self.viewport = viewport;
+        self.sweep = None;
+
+        Ok(self.locale.format("toast.dropped_view", &[&viewport]))
+    }
+
+    /// Saves the current view as a PNG image in the working
+    /// directory, named after the time, with the view and the
+    /// iteration cap in its metadata (see
+    /// `render::write_png_with_view()`), returning its path.
+    fn save_screenshot(&self) -> io::Result<String> {
+        let rgba = render::render_rgba(self.fractal(), &self.viewport, self.size, self.max_iterations, &self.mapper(), self.boundary()).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        let seconds = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+
+        let path = format!("mandelbrust-{}.{}", seconds, PNG_EXTENSION);
+
+        render::write_png_with_view(BufWriter::new(File::create(&path)?), &rgba, self.size, &self.viewport, self.max_iterations)?;
+
+        log::info!("saved `{}`", path);
+
+        Ok(path)
+    }
+
+    /// Renders the current view at the resolution of the
+    /// monitor containing the window, and sets it as the
+    /// desktop wallpaper (see `wallpaper::set_wallpaper()`).
+    fn set_wallpaper(&self, ctx: &Context) -> io::Result<()> {
+        let dimensions = graphics::window(ctx).window().get_current_monitor().get_dimensions();
+
+        let size = (dimensions.width as usize, dimensions.height as usize);
+
+        let rgba = render::render_rgba(self.fractal(), &self.viewport, size, self.max_iterations, &self.mapper(), self.boundary()).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        let path = wallpaper::wallpaper_path();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        render::write_png(BufWriter::new(File::create(&path)?), &rgba, size)?;
+
+        wallpaper::set_wallpaper(&path)?;
+
+        log::info!("set `{}` as the wallpaper", path.display());
+
+        Ok(())
+    }
+}
+
+impl event::EventHandler for MandelPlane {
+    /// The `update()` implementation of the `EventHandler` trait.
+    /// It constantly updates the cursor position, applies the
+    /// iteration cap once it's settled, and runs the commands
+    /// received by the control interface.
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        let coords = mouse::position(ctx);
+
+        // positions outside of the window (which may be
+        // reported while dragging) have no orbit to show
+        self.cursor = Cursor::from_position((coords.x, coords.y), self.size);
+
+        if let Some((iterations, since)) = self.pending_iterations {
+            if since.elapsed() >= ITERATIONS_DEBOUNCE {
+                log::debug!("set the iteration cap to {}", iterations);
+
+                self.max_iterations = iterations;
+                self.contour_step = self.contour_step.min(iterations);
+                self.pending_iterations = None;
+            }
+        }
+
+        self.poll_palette();
+        self.record_view();
+
+        if let Some(offset) = &mut self.cycle {
+            *offset = offset.wrapping_add(CYCLE_STEP);
+        }
+
+        // run the commands of the control interface
+        while let Some(Call { command, reply }) = self.control.as_ref().and_then(|control| control.try_recv().ok()) {
+            // the client may have disconnected
+            let _ = reply.send(self.run_command(command));
+        }
+
+        // mirror the view on the instances in the session
+        if let Some(presenter) = &self.presenter {
+            presenter.broadcast(&self.viewport);
+        }
+
+        // show the view in the title, so that the windows
+        // (and their screenshots) can be told apart
+        let center = format!("{:.*}", TITLE_PRECISION, self.viewport.center());
+        let title = self.locale.format("window.title_view", &[&center, &format!("{:.3}", self.viewport.zoom())]);
+
+        if title != self.title {
+            graphics::set_window_title(ctx, &title);
+
+            self.title = title;
+        }
+
+        Ok(())
+    }
+
+    /// The `draw()` implementation of the `EventHandler` trait.
+    /// It draws the Mandelbrot set, its contour lines (depending
+    /// on the contour mode), the escape-direction arrows and the
+    /// grid of the plane (if enabled) and a line (red by default,
+    /// see `OrbitStyle`),
+    /// which shows the first bounces of the mouse-pointed value
+    /// (see `draw_fractal()`).
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        // draw background
+        graphics::clear(ctx, graphics::BLACK);
+
+        self.draw_fractal(ctx)?;
+
+        // the grid turns with the view, like the orbit line
+        if self.show_grid {
+            draw_segments(ctx, &self.viewport.grid_lines(self.size), GRID_COLOR)?;
+        }
+
+        if let Some(comparison) = self.comparison.filter(|comparison| !comparison.difference) {
+            let divider = comparison.divider as f32;
+
+            // draw the divider of the comparison view
+            draw_segments(ctx, &[((divider, 0.0), (divider, self.size.1 as f32))], WHITE)?;
+        }
+
+        // draw the rectangle that is being selected
+        if let Some(((x0, y0), (x1, y1))) = self.selection {
+            draw_segments(ctx, &[((x0, y0), (x1, y0)), ((x1, y0), (x1, y1)), ((x1, y1), (x0, y1)), ((x0, y1), (x0, y0))], WHITE)?;
+        }
+
+        // draw the Julia set of the point under the cursor in the top-right corner
+        if let Some(c) = self.cursor_point().filter(|_| self.julia_preview) {
+            let (w, h) = self.julia_preview_size();
+
+            let preview = image(ctx, &render::julia_preview(c, (w, h), self.max_iterations, &self.mapper()), (w, h))?;
+
+            let (left, bottom) = ((self.size.0 - w) as f32, h as f32);
+
+            graphics::draw(ctx, &preview, DrawParam::default().dest([left, 0.0]))?;
+
+            draw_segments(ctx, &[((left, 0.0), (left, bottom)), ((left, bottom), (self.size.0 as f32, bottom))], WHITE)?;
+        }
+
+        self.draw_settings(ctx)?;
+
+        // draw the iteration cap (the pending one, while it's being changed)
+        let iterations = self.pending_iterations.map_or(self.max_iterations, |(iterations, _)| iterations);
+
+        let mut readout = vec![self.locale.format("readout.iterations", &[&iterations])];
+
+        if self.hud {
+            readout.extend(self.hud_lines());
+        }
+
+        for (idx, line) in readout.iter().enumerate() {
+            let text = graphics::Text::new(line.as_str());
+
+            graphics::draw(ctx, &text, DrawParam::default().dest([10.0, 10.0 + idx as f32 * HUD_LINE_HEIGHT]).color(WHITE))?;
+        }
+
+        // draw the toast, if there is one
+        if let Some((message, since)) = &self.toast {
+            if since.elapsed() < TOAST_DURATION {
+                let text = graphics::Text::new(message.as_str());
+
+                graphics::draw(ctx, &text, DrawParam::default().dest([10.0, self.size.1 as f32 - 30.0]).color(WHITE))?;
+            }
+        }
+
+        // draw the pinned orbits, each one with its own color
+        for (idx, &pin) in self.pins.iter().enumerate() {
+            self.draw_orbit(ctx, pin, pin_color(idx))?;
+        }
+
+        // hide the orbit while the pointer is outside of the window
+        if let Some(mapped_cursor) = self.cursor_point().filter(|_| self.show_orbit) {
+            self.draw_orbit(ctx, mapped_cursor, self.orbit_style.color)?;
+        }
+
+        graphics::present(ctx)?;
+        
+        Ok(())
+    }
+
+    /// The `mouse_button_down_event()` implementation of the
+    /// `EventHandler` trait, which starts selecting a rectangle
+    /// when the left button is pressed (or moves the orbit traps
+    /// under the cursor, with `Shift`, or presses the settings
+    /// panel, if it's under the cursor), and starts dragging
+    /// the view when the right one is.
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Right {
+            self.drag = Some((x, y));
+        }
+
+        if button != MouseButton::Left {
+            return;
+        }
+
+        // the settings panel is over the fractal
+        if self.settings.as_ref().is_some_and(|settings| settings.contains((x, y))) {
+            self.settings_pressed = true;
+
+            return;
+        }
+
+        // grab the divider of the comparison view, if it's close
+        if let Some(comparison) = self.comparison.as_mut().filter(|comparison| !comparison.difference) {
+            if (x - comparison.divider as f32).abs() <= DIVIDER_GRAB {
+                comparison.dragging = true;
+
+                return;
+            }
+        }
+
+        if keyboard::active_mods(ctx).contains(KeyMods::SHIFT) {
+            if let Some(center) = self.cursor_point() {
+                self.move_traps(center);
+            }
+
+            return;
+        }
+
+        self.selection = Some(((x, y), (x, y)));
+    }
+
+    /// The `mouse_button_up_event()` implementation of the
+    /// `EventHandler` trait, which releases the divider of
+    /// the comparison view, or the view, and zooms on the
+    /// selected rectangle, or pins the orbit of the point
+    /// under the cursor if the left button was just clicked.
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
+        if button == MouseButton::Right {
+            self.drag = None;
+        }
+
+        if let Some(corners) = self.selection.take().filter(|_| button == MouseButton::Left) {
+            let ((x0, _), (x1, _)) = corners;
+
+            if (x1 - x0).abs() >= SELECTION_MIN {
+                self.viewport.zoom_to(corners, self.size);
+            } else if let Some(pin) = self.cursor_point() {
+                log::debug!("pinned the orbit of {}", pin);
+
+                self.pins.push(pin);
+            }
+        }
+
+        if let Some(comparison) = &mut self.comparison {
+            comparison.dragging = false;
+        }
+    }
+
+    /// The `mouse_motion_event()` implementation of the
+    /// `EventHandler` trait, which drags the divider of the
+    /// comparison view, or the view, or the corner of the
+    /// selected rectangle, keeping the aspect ratio of the window.
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if let Some((last_x, last_y)) = self.drag.replace((x, y)) {
+            self.viewport.drag((x - last_x, y - last_y), self.size);
+        }
+
+        if let Some(((x0, y0), corner)) = &mut self.selection {
+            // the side that moved the most sets the size
+            let scale = ((x - *x0).abs() / self.size.0 as f32).max((y - *y0).abs() / self.size.1 as f32);
+
+            *corner = (*x0 + scale * self.size.0 as f32 * (x - *x0).signum(), *y0 + scale * self.size.1 as f32 * (y - *y0).signum());
+        }
+
+        if let Some(comparison) = &mut self.comparison {
+            if comparison.dragging {
+                comparison.divider = x.clamp(0.0, self.size.0 as f32) as usize;
+            }
+        }
+    }
+
+    /// The `mouse_wheel_event()` implementation of the `EventHandler`
+    /// trait, which zooms the view, or scales the iteration
+    /// cap while `Ctrl` is held (the one of the right side
+    /// of the comparison view, if `Shift` is held too).
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) {
+        let keymods = keyboard::active_mods(ctx);
+
+        let comparison = self.comparison.as_mut().filter(|_| keymods.contains(KeyMods::CTRL | KeyMods::SHIFT));
+
+        if let Some(comparison) = comparison {
+            comparison.max_iterations = scaled_iterations(comparison.max_iterations, y);
+
+            let message = self.locale.format("toast.iterations", &[&comparison.max_iterations]);
+
+            self.show_toast(message);
+        } else if keymods.contains(KeyMods::CTRL) {
+            self.scale_iterations(y);
+        } else {
+            self.zoom(y);
+        }
+    }
+
+    /// The `mouse_enter_or_leave()` implementation of the `EventHandler`
+    /// trait, which hides the orbit when the pointer leaves the window.
+    fn mouse_enter_or_leave(&mut self, _ctx: &mut Context, entered: bool) {
+        if !entered {
+            self.cursor = None;
+        }
+    }
+
+    /// The `resize_event()` implementation of the `EventHandler`
+    /// trait, which maps the screen coordinates to the new size
+    /// of the window, so that the fractal is iterated again at
+    /// that size (the frame being keyed by it), instead of
+    /// stretching the old one.
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        // a minimized window may have no pixels at all
+        let size = (width.max(1.0) as usize, height.max(1.0) as usize);
+
+        if size == self.size {
+            return;
+        }
+
+        log::debug!("resized the window to {}x{}", size.0, size.1);
+
+        if let Err(err) = graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, size.0 as f32, size.1 as f32)) {
+            log::error!("{}", err);
+        }
+
+        self.size = size;
+        // the previews of the old size can't be reused
+        self.preview = None;
+        self.sweep = None;
+        self.selection = None;
+
+        if let Some(comparison) = &mut self.comparison {
+            comparison.divider = comparison.divider.min(size.0);
+        }
+
+        if self.settings.is_some() {
+            self.settings = Some(Panel::new(self.settings_origin()));
+        }
+    }
+
+    /// The `key_down_event()` implementation of the `EventHandler` trait.
+    /// `Q` and `E` rotate the view in opposite directions,
+    /// `C` cycles through the contour modes, `[` and `]`
+    /// change the distance between the contour lines,
+    /// `M` cycles through the colorings (of the right side of
+    /// the comparison view, with `Shift`), `P` through the
+    /// precision backends (in the same way), `U` cycles through
+    /// the colorings of the inside of the set, `L` toggles the
+    /// color cycling, `Backspace` goes back to the previous view
+    /// (and forward again, with `Shift`), `Home` resets the
+    /// view, `Insert` saves the view as a bookmark, `Tab` jumps
+    /// to the next bookmark, `V` toggles the
+    /// comparison view, `D` toggles the difference between
+    /// its sides, `I` toggles the iteration sweep, `B` toggles
+    /// the boundary-only mode, `N` cycles through
+    /// the fractals, `,` and `.` change the exponent
+    /// of the Multibrot sets, `F` toggles the
+    /// escape-direction arrows, `K` toggles the preview of
+    /// the Julia set of the point under the cursor, `+` and `-`
+    /// scale the iteration cap, `Y` flips the imaginary
+    /// axis, `Z` chooses whether the mouse wheel zooms on
+    /// the cursor or on the center, `X` clears the pinned
+    /// orbits, `S` saves the current view as a PNG image,
+    /// `W` sets the current view as the desktop wallpaper, while `Escape` closes the window.
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymods: KeyMods, _repeat: bool) {
+        log::debug!("pressed {:?}", keycode);
+
+        let keycode = self.bindings.get(&keycode).copied().unwrap_or(keycode);
+
+        match keycode {
+            KeyCode::Q => self.viewport.rotate(ROTATION_STEP),
+            KeyCode::E => self.viewport.rotate(-ROTATION_STEP),
+            KeyCode::C if keymods.contains(KeyMods::CTRL) => match self.copy_location() {
+                Ok(location) => self.show_toast(self.locale.format("toast.copied", &[&location])),
+                Err(err) => log::error!("{}", self.locale.format("error.clipboard", &[&err])),
+            },
+            KeyCode::C => self.contour_mode = self.contour_mode.next(),
+            KeyCode::LBracket => self.contour_step = (self.contour_step - 1).max(1),
+            KeyCode::RBracket => self.contour_step = (self.contour_step + 1).min(self.max_iterations),
+            KeyCode::M => match &mut self.comparison {
+                Some(comparison) if keymods.contains(KeyMods::SHIFT) => comparison.coloring = (comparison.coloring + 1) % self.colorings.len(),
+                _ => self.coloring = (self.coloring + 1) % self.colorings.len(),
+            },
+            KeyCode::U => {
+                self.interior = self.interior.next();
+
+                self.show_toast(self.locale.format("toast.interior", &[&self.interior]));
+            }
+            KeyCode::L => {
+                self.cycle = match self.cycle {
+                    Some(_) => None,
+                    None => Some(0),
+                };
+
+                let message = if self.cycle.is_some() { "toast.cycle_on" } else { "toast.cycle_off" };
+
+                self.show_toast(self.locale.get(message).to_owned());
+            }
+            KeyCode::Back => self.navigate(keymods.contains(KeyMods::SHIFT)),
+            KeyCode::Home => self.viewport = self.fractal().default_viewport(),
+            KeyCode::Insert => match self.save_bookmark() {
+                Ok(name) => self.show_toast(self.locale.format("toast.bookmark_saved", &[&name, &BOOKMARKS_PATH])),
+                Err(err) => log::error!("{}", self.locale.format("error.bookmarks", &[&err])),
+            },
+            KeyCode::Tab => self.next_bookmark(),
+            KeyCode::V if keymods.contains(KeyMods::CTRL) => match self.paste_location() {
+                Ok(location) => self.show_toast(self.locale.format("toast.pasted", &[&location])),
+                Err(err) => log::error!("{}", self.locale.format("error.clipboard", &[&err])),
+            },
+            KeyCode::V => self.toggle_comparison(),
+            KeyCode::I => self.toggle_sweep(),
+            KeyCode::B => self.boundary_only = !self.boundary_only,
+            KeyCode::A => self.jittered = !self.jittered,
+            KeyCode::T => self.accumulate = !self.accumulate,
+            KeyCode::H => {
+                self.tone_map = match self.tone_map {
+                    Some(operator) => operator.next(),
+                    None => Some(ToneMap::Reinhard),
+                };
+
+                let message = match self.tone_map {
+                    Some(operator) => self.locale.format("toast.tone_map", &[&operator]),
+                    None => self.locale.get("toast.tone_map_off").to_owned(),
+                };
+
+                self.show_toast(message);
+            }
+            KeyCode::P => match &mut self.comparison {
+                Some(comparison) if keymods.contains(KeyMods::SHIFT) => {
+                    comparison.precision = comparison.precision.next().unwrap_or(Precision::ALL[0]);
+
+                    let message = self.locale.format("toast.comparison_precision", &[&comparison.precision]);
+
+                    self.show_toast(message);
+                }
+                _ => {
+                    self.precision = match self.precision {
+                        Some(precision) => precision.next(),
+                        None => Some(Precision::ALL[0]),
+                    };
+
+                    let message = match self.precision {
+                        Some(precision) => self.locale.format("toast.precision", &[&precision]),
+                        None => self.locale.format("toast.precision_auto", &[&self.active_precision]),
+                    };
+
+                    self.show_toast(message);
+                }
+            },
+            KeyCode::D => self.toggle_difference(),
+            KeyCode::N => {
+                self.fractal = (self.fractal + 1) % self.fractals.len();
+                self.multibrot = None;
+            }
+            KeyCode::Comma => self.set_power(self.power().saturating_sub(1)),
+            KeyCode::Period => self.set_power(self.power() + 1),
+            KeyCode::F => self.show_field = !self.show_field,
+            KeyCode::K => self.julia_preview = !self.julia_preview,
+            KeyCode::F1 => self.hud = !self.hud,
+            KeyCode::F2 => {
+                self.settings = match self.settings {
+                    Some(_) => None,
+                    None => Some(Panel::new(self.settings_origin())),
+                };
+            }
+            KeyCode::Add | KeyCode::Equals => self.scale_iterations(1.0),
+            KeyCode::Subtract | KeyCode::Minus => self.scale_iterations(-1.0),
+            KeyCode::G => self.next_preset(),
+            KeyCode::J => self.random_jump(),
+            KeyCode::X => self.pins.clear(),
+            KeyCode::O => match self.export_orbits(ORBITS_PATH) {
+                Ok(()) => self.show_toast(self.locale.format("toast.orbits", &[&ORBITS_PATH])),
+                Err(err) => log::error!("{}", self.locale.format("error.orbits", &[&err])),
+            },
+            KeyCode::Y => self.viewport.set_orientation(self.viewport.orientation().flipped()),
+            KeyCode::Z => {
+                self.zoom_anchor = self.zoom_anchor.toggled();
+
+                let message = match self.zoom_anchor {
+                    ZoomAnchor::Cursor => self.locale.get("toast.zoom_cursor"),
+                    ZoomAnchor::Center => self.locale.get("toast.zoom_center"),
+                };
+
+                self.show_toast(message.to_owned());
+            }
+            KeyCode::W => {
+                if let Err(err) = self.set_wallpaper(ctx) {
+                    log::error!("{}", self.locale.format("error.wallpaper", &[&err]));
+                }
+            }
+            KeyCode::S => match self.save_screenshot() {
+                Ok(path) => self.show_toast(self.locale.format("toast.screenshot", &[&path])),
+                Err(err) => log::error!("{}", self.locale.format("error.screenshot", &[&err])),
+            },
+            KeyCode::Escape => event::quit(ctx),
+            _ => {}
+        }
+    }
+}
+
+/// The options shared by the terminal modes.
+struct TerminalOptions {
+    viewport: Viewport,
+    braille: bool,
+    /// The colors given by `--colors`, if any
+    /// (each mode has its own default).
+    depth: Option<ColorDepth>,
+}
+
+impl TerminalOptions {
+    /// Parses `args`, which can contain `--braille`,
+    /// `--colors <none|256|truecolor|auto>` and a `Viewport`
+    /// string (the default view is used if missing),
+    /// exiting with an error message if they are invalid.
+    fn parse(args: &[String]) -> Self {
+        let mut options = TerminalOptions {
+            viewport: Viewport::default(),
+            braille: false,
+            depth: None,
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--braille" => options.braille = true,
+                "--colors" => {
+                    let name = args.next().map(String::as_str).unwrap_or_default();
+
+                    options.depth = Some(ColorDepth::from_name(name).unwrap_or_else(|| {
+                        eprintln!("invalid color depth `{}` (expected `none`, `256`, `truecolor` or `auto`)", name);
+
+                        process::exit(2);
+                    }));
+                }
+                viewport => {
+                    options.viewport = viewport.parse().unwrap_or_else(|err| {
+                        eprintln!("invalid viewport: {}", err);
+
+                        process::exit(2);
+                    });
+                }
+            }
+        }
+
+        options
+    }
+}
+
+/// Prints the view described by `args` (see `TerminalOptions`)
+/// as character art, sized to fit the terminal, without
+/// opening any window.
+fn run_ascii(args: &[String]) {
+    let options = TerminalOptions::parse(args);
+
+    let (columns, rows) = terminal::terminal_size();
+
+    // leave a line for the prompt
+    let size = (columns, rows.saturating_sub(1).max(1));
+
+    // plain characters, unless asked otherwise
+    let depth = options.depth.unwrap_or(ColorDepth::Monochrome);
+
+    let art = if options.braille {
+        terminal::render_braille(&options.viewport, size, ESCAPE_POINT, depth)
+    } else {
+        terminal::render_ascii(&options.viewport, size, ESCAPE_POINT, depth)
+    };
+
+    print!("{}", art);
+}
+
+/// Exports the smooth iteration field of a `W`x`H` view as a
+/// heightmap mesh; `args` must start with the output path
+/// (ending in `.obj` or `.stl`), followed by `--scale <factor>`
+/// (the vertical scale, `1` by default), `--decimate <step>`
+/// (the distance between two samples, in pixels, `1` by default)
+/// and a `Viewport` string (the default view is used if missing).
+/// Renders the Buddhabrot (or, with `--anti`, the Anti-Buddhabrot)
+/// of the view given in `args` (or of the default one) to the PNG
+/// file given in `args`, tone mapped (see `buddhabrot::to_rgba()`).
+fn run_buddhabrot(args: &[String]) {
+    fn fail(message: String) -> ! {
+        eprintln!("{}", message);
+
+        process::exit(2);
+    }
+
+    let path = args.first().unwrap_or_else(|| fail("missing output path (expected e.g. `buddhabrot.png`)".to_owned()));
+
+    let mut viewport = Viewport::default();
+    let mut selection = Selection::Escaping;
+    let mut samples = BUDDHABROT_SAMPLES;
+    let mut max_iterations = BUDDHABROT_ITERATIONS;
+
+    let mut args = args[1..].iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--anti" => selection = Selection::Bounded,
+            "--samples" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                samples = value.parse().ok().filter(|&samples| samples > 0).unwrap_or_else(|| fail(format!("invalid sample count `{}` (expected a positive integer)", value)));
+            }
+            "--iterations" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                max_iterations = value.parse().ok().filter(|&iterations| iterations > 0).unwrap_or_else(|| fail(format!("invalid iteration cap `{}` (expected a positive integer)", value)));
+            }
+            other => viewport = other.parse().unwrap_or_else(|err| fail(format!("invalid viewport: {}", err))),
+        }
+    }
+
+    viewport.check((W, H)).unwrap_or_else(|err| fail(format!("invalid viewport: {}", err)));
+
+    let start = Instant::now();
+
+    let histogram = buddhabrot::histogram(&viewport, (W, H), max_iterations, samples, selection, rand::random());
+
+    stats::record(&RenderStats::new((W, H), &viewport, max_iterations, &selection.to_string(), start.elapsed()));
+
+    let rgba = buddhabrot::to_rgba(&histogram, ToneMap::Reinhard);
+
+    if let Err(err) = File::create(path).and_then(|file| render::write_png(BufWriter::new(file), &rgba, (W, H))) {
+        eprintln!("could not write `{}`: {}", path, err);
+
+        process::exit(1);
+    }
+
+    log::info!("exported `{}`", path);
+}
+
+/// Renders the view given in `args` (or the default one) to the
+/// PNG file given in `args`, with the size, the iteration cap, the
+/// coloring and the number of jittered samples of each pixel (see
+/// `render::jittered()`) given in `args`, without opening a window.
+fn run_render(args: &[String]) {
+    fn fail(message: String) -> ! {
+        eprintln!("{}", message);
+
+        process::exit(2);
+    }
+
+    let path = args.first().unwrap_or_else(|| fail("missing output path (expected e.g. `render.png`)".to_owned()));
+
+    let mut viewport = Viewport::default();
+    let (mut width, mut height) = (W, H);
+    let mut max_iterations = ESCAPE_POINT;
+    let mut samples = SAMPLES;
+    let mut coloring = "palette".to_owned();
+
+    let positive = |value: Option<&String>, what: &str| -> usize {
+        let value = value.map(String::as_str).unwrap_or_default();
+
+        value.parse().ok().filter(|&value| value > 0).unwrap_or_else(|| fail(format!("invalid {} `{}` (expected a positive integer)", what, value)))
+    };
+
+    let mut args = args[1..].iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--width" => width = positive(args.next(), "width"),
+            "--height" => height = positive(args.next(), "height"),
+            "--iterations" => max_iterations = positive(args.next(), "iteration cap"),
+            "--samples" => samples = positive(args.next(), "sample count").min(render::MAX_SAMPLES),
+            "--coloring" => coloring = args.next().cloned().unwrap_or_default(),
+            "--center" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                let center = parse::parse_complex::<Fixed>(value).unwrap_or_else(|err| fail(format!("invalid center `{}`: {}", value, err)));
+
+                viewport = Viewport::with_precise_center(center, viewport.zoom(), viewport.rotation());
+            }
+            "--zoom" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                let zoom = value.parse().ok().filter(|zoom: &f32| zoom.is_finite() && *zoom > 0.0).unwrap_or_else(|| fail(format!("invalid zoom `{}` (expected a positive number)", value)));
+
+                viewport = Viewport::with_precise_center(viewport.precise_center(), zoom, viewport.rotation());
+            }
+            other => viewport = other.parse().unwrap_or_else(|err| fail(format!("invalid viewport: {}", err))),
+        }
+    }
+
+    viewport.check((width, height)).unwrap_or_else(|err| fail(format!("invalid viewport: {}", err)));
+
+    let colorings = Registry::builtin();
+
+    let mapper = colorings.find(&coloring).unwrap_or_else(|| fail(format!("unknown coloring `{}` (expected one of {})", coloring, colorings.names().join(", "))));
+
+    let start = Instant::now();
+
+    let rgba = render::jittered(&fractal::Mandelbrot, &viewport, (width, height), max_iterations, mapper, samples);
+
+    stats::record(&RenderStats::new((width, height), &viewport, max_iterations, fractal::Mandelbrot.name(), start.elapsed()));
+
+    if let Err(err) = File::create(path).and_then(|file| render::write_png_with_view(BufWriter::new(file), &rgba, (width, height), &viewport, max_iterations)) {
+        eprintln!("could not write `{}`: {}", path, err);
+
+        process::exit(1);
+    }
+
+    log::info!("exported `{}` in {:?}", path, start.elapsed());
+}
+
+/// Renders the exponential map (see `render::exponential_map()`)
+/// of the view given in `args` (or of the default one), `W`
+/// pixels wide, to the PNG file given in `args`.
+fn run_exponential_map(args: &[String]) {
+    fn fail(message: String) -> ! {
+        eprintln!("{}", message);
+
+        process::exit(2);
+    }
+
+    let path = args.first().unwrap_or_else(|| fail("missing output path (expected e.g. `map.png`)".to_owned()));
+
+    let mut viewport = Viewport::default();
+    let mut rows = H;
+    let mut max_iterations = ESCAPE_POINT;
+
+    let mut args = args[1..].iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rows" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                rows = value.parse().ok().filter(|&rows| rows > 0).unwrap_or_else(|| fail(format!("invalid row count `{}` (expected a positive integer)", value)));
+            }
+            "--iterations" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                max_iterations = value.parse().ok().filter(|&iterations| iterations > 0).unwrap_or_else(|| fail(format!("invalid iteration cap `{}` (expected a positive integer)", value)));
+            }
+            other => viewport = other.parse().unwrap_or_else(|err| fail(format!("invalid viewport: {}", err))),
+        }
+    }
+
+    viewport.check((W, H)).unwrap_or_else(|err| fail(format!("invalid viewport: {}", err)));
+
+    let start = Instant::now();
+
+    let results = render::exponential_map(&fractal::Mandelbrot, &viewport, (W, rows), max_iterations);
+
+    stats::record(&RenderStats::new((W, rows), &viewport, max_iterations, fractal::Mandelbrot.name(), start.elapsed()));
+
+    let rgba = render::colorize(&results, &coloring::WikipediaPalette::default(), max_iterations);
+
+    if let Err(err) = File::create(path).and_then(|file| render::write_png(BufWriter::new(file), &rgba, (W, rows))) {
+        eprintln!("could not write `{}`: {}", path, err);
+
+        process::exit(1);
+    }
+
+    log::info!("exported `{}`", path);
+}
+
+/// Renders the standard scenes (the default view and the presets
+/// of the gallery) at the size of the window, with the precision
+/// backend given in `args` (chosen for each scene, by default),
+/// printing the throughput and the timings of each phase of the
+/// renders, so that machines and settings can be compared without
+/// building the benchmarks.
+fn run_bench(args: &[String]) {
+    fn fail(message: String) -> ! {
+        eprintln!("{}", message);
+
+        process::exit(2);
+    }
+
+    let mut precision = None;
+    let mut repeats = BENCH_REPEATS;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--precision" => {
+                let name = args.next().map(String::as_str).unwrap_or_default();
+
+                precision = match name {
+                    "auto" => None,
+                    name => Some(Precision::from_name(name).unwrap_or_else(|| fail(format!("unknown precision `{}`", name)))),
+                };
+            }
+            "--repeat" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                repeats = value.parse().ok().filter(|&repeats| repeats > 0).unwrap_or_else(|| fail(format!("invalid repeat count `{}` (expected a positive integer)", value)));
+            }
+            other => fail(format!("unknown option `{}`", other)),
+        }
+    }
+
+    let (colorings, fallback) = (Registry::builtin(), coloring::WikipediaPalette::default());
+
+    let scenes = std::iter::once(("default", Viewport::default(), ESCAPE_POINT, "palette"))
+        .chain(gallery::PRESETS.iter().map(|preset| (preset.name, preset.viewport(), preset.max_iterations, preset.coloring)));
+
+    println!("{:<20} {:>14} {:>10} {:>10} {:>10} {:>12}", "scene", "precision", "iterate", "color", "encode", "Mpixels/s");
+
+    let mut total = Duration::ZERO;
+
+    for (name, viewport, max_iterations, coloring) in scenes {
+        let mapper = colorings.find(coloring).unwrap_or(&fallback);
+
+        let precision = precision.unwrap_or_else(|| Precision::required(&viewport, (W, H)));
+
+        let mut phases = [Duration::MAX; 3];
+
+        for _ in 0..repeats {
+            let start = Instant::now();
+            let results = precision.escape_results(&fractal::Mandelbrot, &viewport, (W, H), max_iterations);
+            let iterated = Instant::now();
+            let rgba = render::colorize(&results, mapper, max_iterations);
+            let colored = Instant::now();
+            render::write_png(io::sink(), &rgba, (W, H)).unwrap_or_else(|err| fail(err.to_string()));
+
+            for (phase, elapsed) in phases.iter_mut().zip([iterated - start, colored - iterated, colored.elapsed()]) {
+                *phase = (*phase).min(elapsed);
+            }
+        }
+
+        let elapsed: Duration = phases.iter().sum();
+
+        total += elapsed;
+
+        let [iterate, color, encode] = phases.map(|phase| format!("{:.1}ms", phase.as_secs_f64() * 1000.0));
+
+        println!("{:<20} {:>14} {:>10} {:>10} {:>10} {:>12.2}", name, precision.to_string(), iterate, color, encode, (W * H) as f64 / elapsed.as_secs_f64() / 1e6);
+    }
+
+    println!("total: {:.1}ms", total.as_secs_f64() * 1000.0);
+}
+
+/// Returns the fractal of the formula given in `args` (see
+/// `formula::Formula`), or read from the file after `@`,
+/// exiting with an error if it's not valid.
+fn load_formula(arg: &str) -> Formula {
+    let fail = |message: String| -> ! {
+        eprintln!("could not load the formula `{}`: {}", arg, message);
+
+        process::exit(1);
+    };
+
+    let source = match arg.strip_prefix('@') {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|err| fail(err.to_string())),
+        None => arg.to_owned(),
+    };
+
+    Formula::parse(&source).unwrap_or_else(|err| fail(err.to_string()))
+}
+
+/// Returns the palette of the dominant colors of the PNG image
+/// at `path` (see `palette::ImagePalette`), called like the
+/// file, exiting with an error if it can't be read.
+fn load_palette(path: &str, colors: usize) -> ImagePalette {
+    let fail = |message: String| -> ! {
+        eprintln!("could not load the palette of `{}`: {}", path, message);
+
+        process::exit(1);
+    };
+
+    let (rgba, _) = File::open(path).and_then(|file| palette::read_png(io::BufReader::new(file))).unwrap_or_else(|err| fail(err.to_string()));
+
+    let name = Path::new(path).file_stem().and_then(|stem| stem.to_str()).unwrap_or(path);
+
+    ImagePalette::from_image(name, &rgba, colors).unwrap_or_else(|| fail("the image has no opaque pixels".to_owned()))
+}
+
+/// Returns the gradient of `PRESETS` called `arg`, or the one in the
+/// file at `arg` (see `palette::Gradient::parse()`), exiting with
+/// an error if it can't be read.
+fn load_gradient(arg: &str) -> Gradient {
+    let fail = |message: String| -> ! {
+        eprintln!("could not load the gradient `{}`: {}", arg, message);
+
+        process::exit(1);
+    };
+
+    if let Some(gradient) = Gradient::preset(arg) {
+        return gradient;
+    }
+
+    let source = fs::read_to_string(arg).unwrap_or_else(|err| {
+        let names: Vec<&str> = palette::PRESETS.iter().map(|(name, _)| *name).collect();
+
+        fail(format!("{} (expected a file or one of {})", err, names.join(", ")))
+    });
+
+    Gradient::parse(&source).unwrap_or_else(|err| fail(err.to_string()))
+}
+
+/// Extracts the palette of the PNG image given in `args` (see
+/// `load_palette()`), printing its gradient, and writes a preview
+/// of it to the PNG file given in `args`: the default view colored
+/// with the palette, above the gradient.
+fn run_palette(args: &[String]) {
+    fn fail(message: String) -> ! {
+        eprintln!("{}", message);
+
+        process::exit(2);
+    }
+
+    let (image, path) = match args {
+        [image, path, ..] => (image, path),
+        _ => fail("missing image or output path (expected e.g. `photo.png preview.png`)".to_owned()),
+    };
+
+    let mut colors = palette::PALETTE_COLORS;
+
+    let mut args = args[2..].iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--colors" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                colors = value.parse().ok().filter(|&colors| colors > 0).unwrap_or_else(|| fail(format!("invalid color count `{}` (expected a positive integer)", value)));
+            }
+            other => fail(format!("unknown option `{}`", other)),
+        }
+    }
+
+    let palette = load_palette(image, colors);
+
+    for [r, g, b, _] in palette.gradient() {
+        println!("#{:02x}{:02x}{:02x}", r, g, b);
+    }
+
+    let mut rgba = render::render_rgba(&fractal::Mandelbrot, &Viewport::default(), (W, H), ESCAPE_POINT, &palette, None).unwrap_or_else(|err| fail(err.to_string()));
+
+    rgba.extend(palette.preview((W, PALETTE_STRIP)));
+
+    if let Err(err) = File::create(path).and_then(|file| render::write_png(BufWriter::new(file), &rgba, (W, H + PALETTE_STRIP))) {
+        eprintln!("could not write `{}`: {}", path, err);
+
+        process::exit(1);
+    }
+
+    log::info!("exported `{}`", path);
+}
+
+/// Renders the suite of the golden-image regression test (see
+/// `golden::suite()`), comparing the hashes of the images with
+/// the references in the file given in `args` (or `GOLDEN_PATH`),
+/// or replacing them with `--update`; exits with an error if
+/// any image differs from its reference.
+fn run_golden(args: &[String]) {
+    let update = args.iter().any(|arg| arg == "--update");
+    let path = args.iter().find(|arg| !arg.starts_with("--")).map(String::as_str).unwrap_or(GOLDEN_PATH);
+
+    let colorings = Registry::builtin();
+
+    let mut hashes = BTreeMap::new();
+
+    for case in golden::suite(&colorings) {
+        let rgba = case.render(&colorings).unwrap_or_else(|err| {
+            eprintln!("could not render `{}`: {}", case.name, err);
+
+            process::exit(1);
+        });
+
+        hashes.insert(case.name, golden::hash(&rgba));
+    }
+
+    if update {
+        if let Err(err) = File::create(path).and_then(|file| golden::write_references(BufWriter::new(file), &hashes)) {
+            eprintln!("could not write `{}`: {}", path, err);
+
+            process::exit(1);
+        }
+
+        println!("updated {} references in `{}`", hashes.len(), path);
+
+        return;
+    }
+
+    let references = File::open(path).and_then(|file| golden::read_references(io::BufReader::new(file))).unwrap_or_else(|err| {
+        eprintln!("could not read `{}`: {} (run `--golden --update` to create it)", path, err);
+
+        process::exit(1);
+    });
+
+    let mut failures = 0;
+
+    for (name, hash) in &hashes {
+        match references.get(name) {
+            Some(reference) if reference == hash => println!("ok       {}", name),
+            Some(reference) => {
+                println!("FAILED   {} (expected {:016x}, got {:016x})", name, reference, hash);
+
+                failures += 1;
+            }
+            None => println!("missing  {} (no reference)", name),
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} of {} images differ from their references", failures, hashes.len());
+
+        process::exit(1);
+    }
+}
+
+fn run_mesh(args: &[String]) {
+    fn fail(message: String) -> ! {
+        eprintln!("{}", message);
+
+        process::exit(2);
+    }
+
+    let path = args.first().unwrap_or_else(|| fail("missing output path (expected e.g. `fractal.obj`)".to_owned()));
+
+    let format = MeshFormat::from_path(path).unwrap_or_else(|| fail(format!("unsupported mesh format `{}` (expected `.obj` or `.stl`)", path)));
+
+    let mut viewport = Viewport::default();
+    let mut scale = 1.0;
+    let mut decimation = 1;
+
+    let mut args = args[1..].iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scale" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                scale = value.parse().unwrap_or_else(|_| fail(format!("invalid vertical scale `{}`", value)));
+            }
+            "--decimate" => {
+                let value = args.next().map(String::as_str).unwrap_or_default();
+
+                decimation = value.parse().ok().filter(|&step| step > 0).unwrap_or_else(|| fail(format!("invalid decimation `{}` (expected a positive integer)", value)));
+            }
+            other => viewport = other.parse().unwrap_or_else(|err| fail(format!("invalid viewport: {}", err))),
+        }
+    }
+
+    viewport.check((W, H)).unwrap_or_else(|err| fail(format!("invalid viewport: {}", err)));
+
+    let start = Instant::now();
+
+    let heightfield = Heightfield::new(&viewport, (W, H), ESCAPE_POINT, decimation, scale);
+
+    stats::record(&RenderStats::new((W, H), &viewport, ESCAPE_POINT, fractal::Mandelbrot.name(), start.elapsed()));
+
+    let result = File::create(path).and_then(|file| heightfield.write(BufWriter::new(file), format));
+
+    if let Err(err) = result {
+        eprintln!("could not write `{}`: {}", path, err);
+
+        process::exit(1);
+    }
+
+    log::info!("exported `{}`", path);
+}
+
+/// Starts the JSON-RPC control interface (see the `rpc`
+/// module) on the standard input and output if `target` is
+/// `stdio`, or on the Unix socket at `target` otherwise,
+/// exiting with an error message if it can't be started.
+fn spawn_control(target: &str) -> Receiver<Call> {
+    if target == "stdio" {
+        return rpc::spawn_stdio();
+    }
+
+    if target.is_empty() {
+        eprintln!("missing control target (expected `stdio` or the path of a socket)");
+
+        process::exit(2);
+    }
+
+    #[cfg(unix)]
+    let control = rpc::spawn_unix(target);
+
+    #[cfg(not(unix))]
+    let control: io::Result<Receiver<Call>> = Err(io::Error::new(io::ErrorKind::Unsupported, "sockets are only supported on Unix"));
+
+    control.unwrap_or_else(|err| {
+        eprintln!("could not start the control interface on `{}`: {}", target, err);
+
+        process::exit(1);
+    })
+}
+
+/// Hosts a session on `address` (see `rpc::Presenter`),
+/// exiting with an error message if it can't be hosted.
+fn host_session(address: &str) -> Presenter {
+    Presenter::host(address).unwrap_or_else(|err| {
+        eprintln!("could not host the session on `{}`: {}", address, err);
+
+        process::exit(1);
+    })
+}
+
+/// Joins the session hosted at `address` (see `rpc::spawn_tcp()`),
+/// exiting with an error message if it can't be joined.
+fn join_session(address: &str) -> Receiver<Call> {
+    if address.is_empty() {
+        eprintln!("missing session address (expected e.g. `192.168.1.2:7878`)");
+
+        process::exit(2);
+    }
+
+    rpc::spawn_tcp(address).unwrap_or_else(|err| {
+        eprintln!("could not join the session at `{}`: {}", address, err);
+
+        process::exit(1);
+    })
+}
+
+/// Loads the plugins in the directory `dir`,
+/// reporting the ones that fail to load.
+fn load_plugins(dir: &str) -> Plugins {
+    let mut plugins = Plugins::default();
+
+    // plugins are trusted like the executable itself
+    match unsafe { plugins.load_dir(Path::new(dir)) } {
+        Ok(errors) => {
+            for (path, err) in errors {
+                log::warn!("could not load the plugin `{}`: {}", path, err);
+            }
+        }
+        Err(err) => log::warn!("could not read the plugin directory `{}`: {}", dir, err),
+    }
+
+    plugins
+}
+
+/// Sets up the logger, which writes to the standard error the
+/// messages allowed by the filter given with `--log <FILTER>`
+/// (which is removed from `args`), or else by the `LOG_ENV`
+/// environment variable, showing only the warnings by default;
+/// the filters follow the syntax of `env_logger`, like `info`
+/// or `mandelbrust::server=debug`.
+fn init_logging(args: &mut Vec<String>) {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::new().filter_or(LOG_ENV, "warn"));
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--log") {
+        let filter = args.get(idx + 1).cloned().unwrap_or_default();
+
+        builder.parse_filters(&filter);
+
+        args.drain(idx..(idx + 2).min(args.len()));
+    }
+
+    builder.init();
+}
+
+/// Starts appending the statistics of every render to the
+/// file given with `--stats <FILE>` (which is removed from
+/// `args`), if any, exiting with an error message if it
+/// can't be opened.
+fn init_stats(args: &mut Vec<String>) {
+    if let Some(idx) = args.iter().position(|arg| arg == "--stats") {
+        let path = args.get(idx + 1).cloned().unwrap_or_default();
+
+        if let Err(err) = stats::enable(Path::new(&path)) {
+            eprintln!("could not open the statistics file `{}`: {}", path, err);
+
+            process::exit(1);
+        }
+
+        args.drain(idx..(idx + 2).min(args.len()));
+    }
+}
+
+/// Reads the configuration of the file given with `--config <FILE>`
+/// (which is removed from `args`), or of `CONFIG_PATH`, which is
+/// the default one if it doesn't exist, exiting with an error
+/// message if it can't be read.
+fn load_config(args: &mut Vec<String>) -> Config {
+    let path = match args.iter().position(|arg| arg == "--config") {
+        Some(idx) => {
+            let path = args.get(idx + 1).cloned().unwrap_or_default();
+
+            args.drain(idx..(idx + 2).min(args.len()));
+
+            path
+        }
+        None => CONFIG_PATH.to_owned(),
+    };
+
+    Config::load(Path::new(&path)).unwrap_or_else(|err| {
+        eprintln!("could not load the configuration `{}`: {}", path, err);
+
+        process::exit(1);
+    })
+}
+
+/// The window of `ggez` as a `RenderBackend`, which
+/// `backend::explore()` draws on with `--backend ggez`.
+struct GgezBackend<'a> {
+    ctx: &'a mut Context,
+    events_loop: &'a mut event::EventsLoop,
+    /// The size (in pixels) of the window.
+    size: (usize, usize),
+}
+
+impl GgezBackend<'_> {
+    /// Translates a key code of `ggez` into one of `backend::explore()`.
+    fn key(keycode: KeyCode) -> Option<backend::Key> {
+        match keycode {
+            KeyCode::Left => Some(backend::Key::Left),
+            KeyCode::Right => Some(backend::Key::Right),
+            KeyCode::Up => Some(backend::Key::Up),
+            KeyCode::Down => Some(backend::Key::Down),
+            KeyCode::Add | KeyCode::Equals => Some(backend::Key::Plus),
+            KeyCode::Subtract | KeyCode::Minus => Some(backend::Key::Minus),
+            KeyCode::R => Some(backend::Key::R),
+            KeyCode::Escape => Some(backend::Key::Escape),
+            _ => None,
+        }
+    }
+}
+
+impl RenderBackend for GgezBackend<'_> {
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn present(&mut self, rgba: &[u8]) -> io::Result<()> {
+        let ctx = &mut *self.ctx;
+
+        graphics::clear(ctx, graphics::BLACK);
+
+        image(ctx, rgba, self.size)
+            .and_then(|screen| graphics::draw(ctx, &screen, DrawParam::default()))
+            .and_then(|_| graphics::present(ctx))
+            .map_err(|err| io::Error::other(err.to_string()))
+    }
+
+    fn poll_input(&mut self) -> Vec<Input> {
+        let mut inputs = Vec::new();
+
+        let (ctx, size) = (&mut *self.ctx, &mut self.size);
+
+        ctx.timer_context.tick();
+
+        self.events_loop.poll_events(|winit_event| {
+            ctx.process_event(&winit_event);
+
+            let window_event = match winit_event {
+                Event::WindowEvent { event, .. } => event,
+                _ => return,
+            };
+
+            match window_event {
+                WindowEvent::CloseRequested => inputs.push(Input::Close),
+                // a minimized window may have no pixels at all
+                WindowEvent::Resized(resized) if resized.width >= 1.0 && resized.height >= 1.0 => {
+                    if let Err(err) = graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, resized.width as f32, resized.height as f32)) {
+                        log::error!("{}", err);
+                    }
+
+                    *size = (resized.width as usize, resized.height as usize);
+
+                    inputs.push(Input::Resize(*size));
+                }
+                WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(keycode), .. }, .. } => inputs.extend(Self::key(keycode).map(Input::Key)),
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let steps = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => y,
+                        MouseScrollDelta::PixelDelta(position) => position.y as f32,
+                    };
+
+                    inputs.push(Input::Scroll(steps));
+                }
+                // like in the explorer, the view is dragged with the right button
+                WindowEvent::CursorMoved { .. } if mouse::button_pressed(ctx, MouseButton::Right) => {
+                    let delta = mouse::delta(ctx);
+
+                    inputs.push(Input::Drag((delta.x, delta.y)));
+                }
+                _ => {}
+            }
+        });
+
+        inputs
+    }
+}
+
+/// Explores the fractal with `backend::explore()` on a window
+/// of `pixels` (see `--backend`), which doesn't need `ggez`,
+/// with the size and the view of `config`.
+#[cfg(feature = "pixels-backend")]
+fn run_pixels(locale: &Locale, config: &Config) {
+    let result = mandelbrust::pixels_backend::PixelsBackend::new(locale.get("window.title"), config.size()).and_then(|mut window| backend::explore(&mut window, config.viewport, RenderSettings::default()));
+
+    if let Err(err) = result {
+        eprintln!("window error: {}", err);
+
+        process::exit(1);
+    }
+}
+
+/// Fails, as the `pixels` backend needs the `pixels-backend` feature.
+#[cfg(not(feature = "pixels-backend"))]
+fn run_pixels(_locale: &Locale, _config: &Config) {
+    eprintln!("the `pixels` backend needs the `pixels-backend` feature");
+
+    process::exit(2);
+}
+
+/// Runs the event loop of the window like `event::run()`,
+/// which `ggez 0.5` can't extend with more events, but
+/// also loads the files dropped on the window (see
+/// `MandelPlane::load_dropped()`).
+fn run(ctx: &mut Context, events_loop: &mut event::EventsLoop, state: &mut MandelPlane) -> GameResult {
+    while ctx.continuing {
+        ctx.timer_context.tick();
+
+        events_loop.poll_events(|winit_event| {
+            ctx.process_event(&winit_event);
+
+            let window_event = match winit_event {
+                Event::WindowEvent { event, .. } => event,
+                _ => return,
+            };
+
+            match window_event {
+                WindowEvent::CloseRequested => event::quit(ctx),
+                WindowEvent::Resized(size) => state.resize_event(ctx, size.width as f32, size.height as f32),
+                WindowEvent::DroppedFile(path) => {
+                    let message = state.load_dropped(&path).unwrap_or_else(|err| state.locale.format("error.dropped", &[&path.display(), &err]));
+
+                    state.show_toast(message);
+                }
+                WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(keycode), modifiers, .. }, .. } => {
+                    let repeat = keyboard::is_key_repeated(ctx);
+
+                    state.key_down_event(ctx, keycode, modifiers.into(), repeat);
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let (x, y) = match delta {
+                        MouseScrollDelta::LineDelta(x, y) => (x, y),
+                        MouseScrollDelta::PixelDelta(position) => (position.x as f32, position.y as f32),
+                    };
+
+                    state.mouse_wheel_event(ctx, x, y);
+                }
+                WindowEvent::MouseInput { state: element_state, button, .. } => {
+                    let position = mouse::position(ctx);
+
+                    match element_state {
+                        ElementState::Pressed => state.mouse_button_down_event(ctx, button, position.x, position.y),
+                        ElementState::Released => state.mouse_button_up_event(ctx, button, position.x, position.y),
+                    }
+                }
+                WindowEvent::CursorMoved { .. } => {
+                    let (position, delta) = (mouse::position(ctx), mouse::delta(ctx));
+
+                    state.mouse_motion_event(ctx, position.x, position.y, delta.x, delta.y);
+                }
+                WindowEvent::CursorEntered { .. } => state.mouse_enter_or_leave(ctx, true),
+                WindowEvent::CursorLeft { .. } => state.mouse_enter_or_leave(ctx, false),
+                _ => {}
+            }
+        });
+
+        state.update(ctx)?;
+        state.draw(ctx)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> GameResult {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    init_logging(&mut args);
+    init_stats(&mut args);
+
+    let config = load_config(&mut args);
+
+    if let Some(threads) = config.threads {
+        // the pool can only be built once, before any render
+        if let Err(err) = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global() {
+            log::warn!("could not use {} threads: {}", threads, err);
+        }
+    }
+
+    let locale = Locale::from_env();
+
+    match args.first().map(String::as_str) {
+        Some("--ascii") => {
+            run_ascii(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--bench") => {
+            run_bench(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--buddhabrot") => {
+            run_buddhabrot(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--exp-map") => {
+            run_exponential_map(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--extract-palette") => {
+            run_palette(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--golden") => {
+            run_golden(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--mesh") => {
+            run_mesh(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--render") => {
+            run_render(&args[1..]);
+
+            return Ok(());
+        }
+        Some("--run-script") => {
+            let path = args.get(1).map(String::as_str).unwrap_or_default();
+
+            if let Err(err) = script::run(Path::new(path)) {
+                eprintln!("script error: {}", err);
+
+                process::exit(1);
+            }
+
+            return Ok(());
+        }
+        Some("--serve") => {
+            let address = args.get(1).map(String::as_str).unwrap_or(DEFAULT_ADDRESS);
+
+            println!("serving renders on http://{}", address);
+
+            if let Err(err) = server::serve(address) {
+                eprintln!("server error: {}", err);
+
+                process::exit(1);
+            }
+
+            return Ok(());
+        }
+        Some("--tui") => {
+            let options = TerminalOptions::parse(&args[1..]);
+
+            let depth = options.depth.unwrap_or(ColorDepth::TrueColor);
+
+            if let Err(err) = tui::run(options.viewport, ESCAPE_POINT, options.braille, depth, &locale) {
+                eprintln!("terminal error: {}", err);
+
+                process::exit(1);
+            }
+
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let orbit_style = OrbitStyle::parse(&args);
+
+    let boundary_width = match args.iter().position(|arg| arg == "--boundary-width") {
+        Some(idx) => {
+            let value = args.get(idx + 1).map(String::as_str).unwrap_or_default();
+
+            value.parse().ok().filter(|&width: &f32| width.is_finite() && width > 0.0).unwrap_or_else(|| {
+                eprintln!("invalid boundary width `{}` (expected a positive number)", value);
+
+                process::exit(2);
+            })
+        }
+        None => BOUNDARY_WIDTH,
+    };
+
+    let samples = match args.iter().position(|arg| arg == "--samples") {
+        Some(idx) => {
+            let value = args.get(idx + 1).map(String::as_str).unwrap_or_default();
+
+            value.parse().ok().filter(|samples| (1..=render::MAX_SAMPLES).contains(samples)).unwrap_or_else(|| {
+                eprintln!("invalid sample count `{}` (expected a number between 1 and {})", value, render::MAX_SAMPLES);
+
+                process::exit(2);
+            })
+        }
+        None => SAMPLES,
+    };
+
+    let backend_name = args.iter().position(|arg| arg == "--backend").map(|idx| args.get(idx + 1).cloned().unwrap_or_default());
+
+    match backend_name.as_deref() {
+        None | Some("ggez") => {}
+        Some("pixels") => {
+            run_pixels(&locale, &config);
+
+            return Ok(());
+        }
+        Some(name) => {
+            eprintln!("unknown backend `{}` (expected `ggez` or `pixels`)", name);
+
+            process::exit(2);
+        }
+    }
+
+    let window_size = ((config.width as f32).max(MIN_WINDOW_SIZE.0), (config.height as f32).max(MIN_WINDOW_SIZE.1));
+
+    let cb = ContextBuilder::new("MandelbRust", "ph04")
+        .window_setup(conf::WindowSetup {
+            title: locale.get("window.title").to_owned(),
+            samples: if orbit_style.antialiasing { conf::NumSamples::Eight } else { conf::NumSamples::One },
+            vsync: true,
+            icon: "".to_owned(),
+            srgb: true,
+        }).window_mode(conf::WindowMode {
+            width: window_size.0,
+            height: window_size.1,
+            maximized: false,
+            fullscreen_type: conf::FullscreenType::Windowed,
+            borderless: false,
+            min_width: MIN_WINDOW_SIZE.0,
+            max_width: 0.0,
+            min_height: MIN_WINDOW_SIZE.1,
+            max_height: 0.0,
+            resizable: true,
+        });
+
+    let control = args
+        .iter()
+        .position(|arg| arg == "--control")
+        .map(|idx| spawn_control(args.get(idx + 1).map(String::as_str).unwrap_or_default()))
+        .or_else(|| args.iter().position(|arg| arg == "--join").map(|idx| join_session(args.get(idx + 1).map(String::as_str).unwrap_or_default())));
+
+    let presenter = args
+        .iter()
+        .position(|arg| arg == "--host")
+        .map(|idx| host_session(args.get(idx + 1).map(String::as_str).filter(|arg| !arg.starts_with("--")).unwrap_or(DEFAULT_SESSION_ADDRESS)));
+
+    let mut plugins = args
+        .iter()
+        .position(|arg| arg == "--plugins")
+        .map(|idx| load_plugins(args.get(idx + 1).map(String::as_str).unwrap_or_default()))
+        .unwrap_or_default();
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--script") {
+        let path = args.get(idx + 1).map(String::as_str).unwrap_or_default();
+
+        match script::load(Path::new(path)) {
+            Ok(script) => {
+                plugins.fractals.extend(script.fractals);
+                plugins.colorings.extend(script.colorings);
+            }
+            Err(err) => {
+                eprintln!("could not load the script `{}`: {}", path, err);
+
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--formula") {
+        plugins.fractals.push(Box::new(load_formula(args.get(idx + 1).map(String::as_str).unwrap_or_default())));
+    }
+
+    // the images give a coloring of their own, the
+    // palette files the colors of the palette colorings
+    let palette_path = args
+        .iter()
+        .position(|arg| arg == "--palette")
+        .map(|idx| args.get(idx + 1).map(String::as_str).unwrap_or_default())
+        .or_else(|| config.palette.as_deref().and_then(Path::to_str));
+
+    let (palette, palette_file) = match palette_path {
+        Some(path) if Path::new(path).extension().and_then(|extension| extension.to_str()) == Some(PNG_EXTENSION) => (Some(load_palette(path, palette::PALETTE_COLORS)), None),
+        Some(path) => (None, Some(PaletteFile::open(Path::new(path)).unwrap_or_else(|err| {
+            eprintln!("could not load the palette `{}`: {}", path, err);
+
+            process::exit(1);
+        }))),
+        None => (None, None),
+    };
+
+    let gradient = args.iter().position(|arg| arg == "--gradient").map(|idx| load_gradient(args.get(idx + 1).map(String::as_str).unwrap_or_default()));
+
+    if let Some(palette) = &palette {
+        plugins.colorings.push(Box::new(palette.clone()));
+    }
+
+    let preset = args.iter().position(|arg| arg == "--preset").map(|idx| {
+        let name = args.get(idx + 1).map(String::as_str).unwrap_or_default();
+
+        gallery::find(name).unwrap_or_else(|| {
+            let names: Vec<&str> = gallery::PRESETS.iter().map(|preset| preset.name).collect();
+
+            eprintln!("unknown preset `{}` (expected one of {})", name, names.join(", "));
+
+            process::exit(2);
+        })
+    });
+
+    let bindings = config.keys.iter().map(|(key, control)| match (key_code(key), key_code(control)) {
+        (Some(key), Some(control)) => (key, control),
+        _ => {
+            eprintln!("invalid key binding `{} = {}` in the configuration", key, control);
+
+            process::exit(2);
+        }
+    }).collect();
+
+    // a file that can't be read would be overwritten by the next bookmark
+    let bookmarks = Bookmarks::load(Path::new(BOOKMARKS_PATH)).unwrap_or_else(|err| {
+        eprintln!("could not load the bookmarks of `{}`: {}", BOOKMARKS_PATH, err);
+
+        process::exit(1);
+    });
+
+    let (ctx, event_loop) = &mut cb.build()?;
+
+    if backend_name.is_some() {
+        return backend::explore(&mut GgezBackend { ctx, events_loop: event_loop, size: (window_size.0 as usize, window_size.1 as usize) }, config.viewport, RenderSettings::default()).map_err(GameError::from);
+    }
+    
+    let state = &mut MandelPlane::new(locale, control, presenter, plugins, orbit_style, boundary_width, samples)?;
+
+    state.bookmarks = bookmarks;
+    state.bindings = bindings;
+    state.viewport = config.viewport;
+    state.max_iterations = config.max_iterations.clamp(ITERATIONS_RANGE.0, ITERATIONS_RANGE.1);
+    state.resize_event(ctx, window_size.0, window_size.1);
+
+    if let Some(preset) = preset {
+        state.go_to_preset(preset);
+    }
+
+    if let Some(palette) = &palette {
+        state.coloring = state.colorings.position(palette.name()).unwrap_or(state.coloring);
+    }
+
+    if let Some(gradient) = gradient {
+        state.set_gradient(gradient);
+    }
+
+    if let Some((file, palette)) = palette_file {
+        state.set_palette(palette);
+        state.palette_file = Some((file, Instant::now()));
+    }
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--coloring") {
+        let name = args.get(idx + 1).map(String::as_str).unwrap_or_default();
+
+        state.coloring = state.colorings.position(name).unwrap_or_else(|| {
+            eprintln!("unknown coloring `{}` (expected one of {})", name, state.colorings.names().join(", "));
+
+            process::exit(2);
+        });
+    }
+
+    if let Some(idx) = args.iter().position(|arg| arg == "--precision") {
+        let name = args.get(idx + 1).map(String::as_str).unwrap_or_default();
+
+        state.precision = match name {
+            "auto" => None,
+            name => Some(Precision::from_name(name).unwrap_or_else(|| {
+                let names: Vec<String> = Precision::ALL.iter().map(Precision::to_string).collect();
+
+                eprintln!("unknown precision `{}` (expected `auto` or one of {})", name, names.join(", "));
+
+                process::exit(2);
+            })),
+        };
+    }
     
-    event::run(ctx, event_loop, state)
+    run(ctx, event_loop, state)
 }
\ No newline at end of file
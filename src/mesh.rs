@@ -0,0 +1,213 @@
+use crate::{kernel, utils::*};
+use rayon::prelude::*;
+use std::io::{self, Write};
+
+/// The file formats a `Heightfield` can be exported to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MeshFormat {
+    /// Wavefront OBJ, as text.
+    Obj,
+    /// Binary STL.
+    Stl,
+}
+
+impl MeshFormat {
+    /// Returns the format matching the extension
+    /// of `path` (either `.obj` or `.stl`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::mesh::MeshFormat;
+    /// assert_eq!(MeshFormat::from_path("fractal.STL"), Some(MeshFormat::Stl));
+    /// assert_eq!(MeshFormat::from_path("fractal.png"), None);
+    /// ```
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+
+        match extension.to_lowercase().as_str() {
+            "obj" => Some(MeshFormat::Obj),
+            "stl" => Some(MeshFormat::Stl),
+            _ => None,
+        }
+    }
+}
+
+/// A grid of heights sampled from the smooth
+/// iteration field (see `EscapeResult::smooth_iterations()`),
+/// which can be exported as a triangle mesh;
+/// the grid is laid on the `xy` plane, one unit
+/// per sample, with the heights along `z`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightfield {
+    heights: Vec<f32>,
+    size: (usize, usize),
+}
+
+impl Heightfield {
+    /// Samples the area of the Mandelbrot plane shown by
+    /// `viewport` on a screen of the given `size`, taking
+    /// one pixel every `decimation` in both directions,
+    /// and multiplying the heights by `scale`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{mesh::Heightfield, utils::Viewport};
+    /// let heightfield = Heightfield::new(&Viewport::default(), (300, 200), 128, 4, 0.5);
+    ///
+    /// assert_eq!(heightfield.size(), (75, 50));
+    /// assert_eq!(heightfield.height(37, 25), 64.5); // inside the set
+    /// ```
+    pub fn new(viewport: &Viewport, size: (usize, usize), max_iterations: usize, decimation: usize, scale: f32) -> Self {
+        let decimation = decimation.max(1);
+
+        let grid = (size.0.div_ceil(decimation), size.1.div_ceil(decimation));
+
+        let heights = (0..grid.0 * grid.1)
+            .into_par_iter()
+            .map(|idx| {
+                let pixel = Point::new((idx % grid.0 * decimation, idx / grid.0 * decimation));
+
+                kernel::escape(pixel.to_mandel(viewport, size), max_iterations).smooth_iterations() * scale
+            })
+            .collect();
+
+        Self { heights, size: grid }
+    }
+
+    /// Returns the number of samples in each direction.
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Returns the height of the sample in `(x, y)`.
+    pub fn height(&self, x: usize, y: usize) -> f32 {
+        self.heights[y * self.size.0 + x]
+    }
+
+    /// Returns the position of the vertex of the sample in `(x, y)`.
+    fn vertex(&self, x: usize, y: usize) -> [f32; 3] {
+        [x as f32, y as f32, self.height(x, y)]
+    }
+
+    /// Returns the triangles of the surface, as indices of
+    /// samples, in counter-clockwise order seen from above.
+    fn triangles(&self) -> impl Iterator<Item = [(usize, usize); 3]> + '_ {
+        let (w, h) = self.size;
+
+        (0..h.saturating_sub(1)).flat_map(move |y| {
+            (0..w.saturating_sub(1)).flat_map(move |x| {
+                [
+                    [(x, y), (x + 1, y), (x + 1, y + 1)],
+                    [(x, y), (x + 1, y + 1), (x, y + 1)],
+                ]
+            })
+        })
+    }
+
+    /// Writes the surface as a Wavefront OBJ file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{mesh::Heightfield, utils::Viewport};
+    /// let heightfield = Heightfield::new(&Viewport::default(), (30, 20), 128, 10, 1.0);
+    ///
+    /// let mut obj = Vec::new();
+    ///
+    /// heightfield.write_obj(&mut obj).unwrap();
+    ///
+    /// let obj = String::from_utf8(obj).unwrap();
+    ///
+    /// assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), 6);
+    /// assert_eq!(obj.lines().filter(|line| line.starts_with("f ")).count(), 4);
+    /// ```
+    pub fn write_obj<T: Write>(&self, mut writer: T) -> io::Result<()> {
+        let (w, h) = self.size;
+
+        for y in 0..h {
+            for x in 0..w {
+                let [vx, vy, vz] = self.vertex(x, y);
+
+                writeln!(writer, "v {} {} {}", vx, vy, vz)?;
+            }
+        }
+
+        // OBJ indices start from 1
+        for triangle in self.triangles() {
+            let [a, b, c] = triangle.map(|(x, y)| y * w + x + 1);
+
+            writeln!(writer, "f {} {} {}", a, b, c)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the surface as a binary STL file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{mesh::Heightfield, utils::Viewport};
+    /// let heightfield = Heightfield::new(&Viewport::default(), (30, 20), 128, 10, 1.0);
+    ///
+    /// let mut stl = Vec::new();
+    ///
+    /// heightfield.write_stl(&mut stl).unwrap();
+    ///
+    /// // header, triangle count and 4 triangles
+    /// assert_eq!(stl.len(), 80 + 4 + 4 * 50);
+    /// ```
+    pub fn write_stl<T: Write>(&self, mut writer: T) -> io::Result<()> {
+        let mut header = [0; 80];
+
+        header[..11].copy_from_slice(b"MandelbRust");
+
+        writer.write_all(&header)?;
+
+        let count = self.size.0.saturating_sub(1) * self.size.1.saturating_sub(1) * 2;
+
+        writer.write_all(&(count as u32).to_le_bytes())?;
+
+        for triangle in self.triangles() {
+            let vertices = triangle.map(|(x, y)| self.vertex(x, y));
+
+            for component in normal(&vertices).iter().chain(vertices.iter().flatten()) {
+                writer.write_all(&component.to_le_bytes())?;
+            }
+
+            // attribute byte count
+            writer.write_all(&[0, 0])?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the surface in the given `format`.
+    pub fn write<T: Write>(&self, writer: T, format: MeshFormat) -> io::Result<()> {
+        match format {
+            MeshFormat::Obj => self.write_obj(writer),
+            MeshFormat::Stl => self.write_stl(writer),
+        }
+    }
+}
+
+/// Returns the unit normal of a triangle
+/// whose vertices are in counter-clockwise order.
+fn normal(vertices: &[[f32; 3]; 3]) -> [f32; 3] {
+    let [a, b, c] = vertices;
+
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+
+    let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+
+    let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+
+    if length == 0.0 {
+        [0.0, 0.0, 1.0]
+    } else {
+        [n[0] / length, n[1] / length, n[2] / length]
+    }
+}
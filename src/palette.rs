@@ -0,0 +1,609 @@
+use crate::{coloring::ColorMapper, kernel::EscapeResult, utils::COLOR_MAP};
+use std::{
+    error, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// The number of dominant colors extracted
+/// from an image, if not specified otherwise.
+pub const PALETTE_COLORS: usize = 5;
+
+/// The number of colors sampled from the gradient of an `ImagePalette`
+/// (or a `Gradient`), which is repeated every as many iterations,
+/// like `COLOR_MAP`.
+pub const GRADIENT_LENGTH: usize = 16;
+
+/// The largest number of pixels of an image that are
+/// clustered, sampled evenly from the whole image.
+const MAX_SAMPLES: usize = 1 << 16;
+
+/// The number of refinements of the clusters of `dominant_colors()`.
+const ROUNDS: usize = 16;
+
+/// An error in the source of a `Palette`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PaletteError {
+    /// The line (counting from 1) isn't a valid color.
+    InvalidLine(usize),
+    /// The source has no colors.
+    Empty,
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaletteError::InvalidLine(line) => write!(f, "the line {} is not a valid color", line),
+            PaletteError::Empty => write!(f, "the palette has no colors"),
+        }
+    }
+}
+
+impl error::Error for PaletteError {}
+
+/// A list of colors, of any length, repeated along the
+/// iterations by the palette colorings (see
+/// `coloring::WikipediaPalette`), which is `COLOR_MAP`
+/// unless it's loaded from a file.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{palette::Palette, utils::COLOR_MAP};
+/// let palette = Palette::default();
+///
+/// assert_eq!(palette.colors(), &COLOR_MAP[..]);
+/// assert_eq!(palette.color(17), COLOR_MAP[1]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Palette {
+    colors: Vec<[u8; 4]>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self { colors: COLOR_MAP.to_vec() }
+    }
+}
+
+impl Palette {
+    /// Returns the palette of the given `colors`,
+    /// or `None` if there are none.
+    pub fn new(colors: Vec<[u8; 4]>) -> Option<Self> {
+        if colors.is_empty() {
+            None
+        } else {
+            Some(Self { colors })
+        }
+    }
+
+    /// Parses a Fractint palette (a `.map` file), with
+    /// the decimal red, green and blue channels of a
+    /// color on each line, followed by an optional
+    /// comment; the empty lines are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::palette::{Palette, PaletteError};
+    /// let palette = Palette::from_map("0 0 0 black\n255 128 0\n\n  0 0 255 blue").unwrap();
+    ///
+    /// assert_eq!(palette.colors(), &[[0, 0, 0, 255], [255, 128, 0, 255], [0, 0, 255, 255]]);
+    /// assert_eq!(Palette::from_map("0 0 0\n256 0 0"), Err(PaletteError::InvalidLine(2)));
+    /// assert_eq!(Palette::from_map(""), Err(PaletteError::Empty));
+    /// ```
+    pub fn from_map(source: &str) -> Result<Self, PaletteError> {
+        let colors = parse_lines(source, |line| {
+            let mut channels = line.split_whitespace().map(|channel| channel.parse::<u8>().ok());
+
+            Some([channels.next()??, channels.next()??, channels.next()??, 255])
+        })?;
+
+        Self::new(colors).ok_or(PaletteError::Empty)
+    }
+
+    /// Parses a palette with a color on each line, as hexadecimal
+    /// `RRGGBB` or `RRGGBBAA` (optionally preceded by `#`); the
+    /// empty lines and the ones starting with `;` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::palette::{Palette, PaletteError};
+    /// let palette = Palette::from_hex("; sunset\n#ff8000\n00ff0080\n").unwrap();
+    ///
+    /// assert_eq!(palette.colors(), &[[255, 128, 0, 255], [0, 255, 0, 128]]);
+    /// assert_eq!(Palette::from_hex("#ff8000\n#ff80"), Err(PaletteError::InvalidLine(2)));
+    /// ```
+    pub fn from_hex(source: &str) -> Result<Self, PaletteError> {
+        parse_lines(source, hex_color).and_then(|colors| Self::new(colors).ok_or(PaletteError::Empty))
+    }
+
+    /// Reads the palette file at `path`, which is a Fractint
+    /// palette if its extension is `map` (see `from_map()`),
+    /// or a list of hexadecimal colors otherwise (see `from_hex()`).
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let source = fs::read_to_string(path)?;
+
+        let palette = match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("map") => Self::from_map(&source),
+            _ => Self::from_hex(&source),
+        };
+
+        palette.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Returns the colors of the palette.
+    pub fn colors(&self) -> &[[u8; 4]] {
+        &self.colors
+    }
+
+    /// Returns the color of the given number of `iterations`,
+    /// repeating the palette every as many iterations as its colors.
+    pub fn color(&self, iterations: usize) -> [u8; 4] {
+        self.colors[iterations % self.colors.len()]
+    }
+}
+
+/// Parses the lines of `source` with `parse`, skipping
+/// the empty lines and the comments (starting with `;`).
+fn parse_lines<T, F: Fn(&str) -> Option<T>>(source: &str, parse: F) -> Result<Vec<T>, PaletteError> {
+    source
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with(';'))
+        .map(|(idx, line)| parse(line).ok_or(PaletteError::InvalidLine(idx + 1)))
+        .collect()
+}
+
+/// Parses a hexadecimal `RRGGBB` or `RRGGBBAA`
+/// color, optionally preceded by `#`.
+fn hex_color(text: &str) -> Option<[u8; 4]> {
+    let digits = text.strip_prefix('#').unwrap_or(text);
+
+    if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+        return None;
+    }
+
+    let mut color = [255; 4];
+
+    for (channel, idx) in color.iter_mut().zip((0..digits.len()).step_by(2)) {
+        *channel = u8::from_str_radix(&digits[idx..idx + 2], 16).ok()?;
+    }
+
+    Some(color)
+}
+
+/// A stop of a `Gradient`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stop {
+    /// The position of the stop along the gradient
+    /// (between `0.0` and `1.0`).
+    pub position: f32,
+    /// The color of the gradient at the stop.
+    pub color: [u8; 4],
+}
+
+impl Stop {
+    /// Returns the stop with the given `color` at `position`.
+    pub const fn new(position: f32, color: [u8; 4]) -> Self {
+        Self { position, color }
+    }
+}
+
+/// The gradients of the gallery of `Gradient::preset()`,
+/// modeled after the ones of Ultra Fractal.
+pub const PRESETS: &[(&str, &[Stop])] = &[
+    // the default gradient of Ultra Fractal, which `COLOR_MAP` resembles
+    (
+        "ultra-fractal",
+        &[
+            Stop::new(0.0, [0, 7, 100, 255]),
+            Stop::new(0.16, [32, 107, 203, 255]),
+            Stop::new(0.42, [237, 255, 255, 255]),
+            Stop::new(0.6425, [255, 170, 0, 255]),
+            Stop::new(0.8575, [0, 2, 0, 255]),
+        ],
+    ),
+    (
+        "fire",
+        &[
+            Stop::new(0.0, [0, 0, 0, 255]),
+            Stop::new(0.3, [190, 30, 0, 255]),
+            Stop::new(0.6, [255, 190, 30, 255]),
+            Stop::new(0.8, [255, 255, 210, 255]),
+        ],
+    ),
+    (
+        "ocean",
+        &[
+            Stop::new(0.0, [0, 10, 40, 255]),
+            Stop::new(0.4, [0, 90, 160, 255]),
+            Stop::new(0.7, [120, 220, 240, 255]),
+            Stop::new(0.85, [240, 255, 255, 255]),
+        ],
+    ),
+    ("grayscale", &[Stop::new(0.0, [0, 0, 0, 255]), Stop::new(0.5, [255, 255, 255, 255])]),
+];
+
+/// A gradient defined by its stops, interpolated between
+/// them, which wraps around from the last one to the first
+/// one, so that it can be repeated without seams.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::palette::{Gradient, Stop};
+/// let gradient = Gradient::new(vec![Stop::new(0.5, [200, 100, 0, 255]), Stop::new(0.0, [0, 0, 0, 255])]).unwrap();
+///
+/// assert_eq!(gradient.sample(0.25), [100, 50, 0, 255]);
+/// assert_eq!(gradient.sample(0.75), [100, 50, 0, 255]);
+/// // `t` wraps around
+/// assert_eq!(gradient.sample(1.5), gradient.sample(0.5));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<Stop>,
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self::from(&Palette::default())
+    }
+}
+
+impl From<&Palette> for Gradient {
+    /// Returns the gradient with the colors
+    /// of `palette` as evenly spaced stops.
+    fn from(palette: &Palette) -> Self {
+        let len = palette.colors().len();
+
+        Self { stops: palette.colors().iter().enumerate().map(|(idx, &color)| Stop::new(idx as f32 / len as f32, color)).collect() }
+    }
+}
+
+impl Gradient {
+    /// Returns the gradient with the given `stops` (in any order,
+    /// with their positions clamped between `0.0` and `1.0`),
+    /// or `None` if there are none.
+    pub fn new(mut stops: Vec<Stop>) -> Option<Self> {
+        if stops.is_empty() {
+            return None;
+        }
+
+        stops.iter_mut().for_each(|stop| stop.position = stop.position.clamp(0.0, 1.0));
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+        Some(Self { stops })
+    }
+
+    /// Parses a gradient with a stop on each line, as its position
+    /// followed by its color as hexadecimal `RRGGBB` or `RRGGBBAA`
+    /// (optionally preceded by `#`); the empty lines and
+    /// the ones starting with `;` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::palette::{Gradient, PaletteError, Stop};
+    /// let gradient = Gradient::parse("; dusk\n0.0 #000000\n0.5 #ff8000").unwrap();
+    ///
+    /// assert_eq!(gradient.stops(), &[Stop::new(0.0, [0, 0, 0, 255]), Stop::new(0.5, [255, 128, 0, 255])]);
+    /// assert_eq!(Gradient::parse("0.0 #000000\nhalf #ff8000"), Err(PaletteError::InvalidLine(2)));
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, PaletteError> {
+        let stops = parse_lines(source, |line| {
+            let (position, color) = line.split_once(char::is_whitespace)?;
+
+            Some(Stop::new(position.parse().ok().filter(|position: &f32| position.is_finite())?, hex_color(color.trim())?))
+        })?;
+
+        Self::new(stops).ok_or(PaletteError::Empty)
+    }
+
+    /// Returns the gradient called `name` of `PRESETS`, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::palette::Gradient;
+    /// assert_eq!(Gradient::preset("ultra-fractal").unwrap().sample(0.0), [0, 7, 100, 255]);
+    /// assert!(Gradient::preset("rainbow").is_none());
+    /// ```
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS.iter().find(|(preset, _)| *preset == name).and_then(|(_, stops)| Self::new(stops.to_vec()))
+    }
+
+    /// Returns the stops of the gradient, sorted by position.
+    pub fn stops(&self) -> &[Stop] {
+        &self.stops
+    }
+
+    /// Returns the color of the gradient at `t`, which wraps
+    /// around `1.0`, interpolating between the stops around it.
+    pub fn sample(&self, t: f32) -> [u8; 4] {
+        let t = t.rem_euclid(1.0);
+
+        // the first stop after `t`, wrapping around to the first one
+        let next = self.stops.iter().position(|stop| stop.position > t).unwrap_or(0);
+        let (from, to) = (self.stops[(next + self.stops.len() - 1) % self.stops.len()], self.stops[next]);
+
+        let span = (to.position - from.position).rem_euclid(1.0);
+
+        if span == 0.0 {
+            return from.color;
+        }
+
+        mix(from.color, to.color, (t - from.position).rem_euclid(1.0) / span)
+    }
+
+    /// Returns the palette of `len` colors sampled
+    /// evenly from the gradient (at least one).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::palette::{Gradient, Palette};
+    /// assert_eq!(Gradient::default().to_palette(16), Palette::default());
+    /// assert_eq!(Gradient::preset("grayscale").unwrap().to_palette(4).colors()[1], [128, 128, 128, 255]);
+    /// ```
+    pub fn to_palette(&self, len: usize) -> Palette {
+        let len = len.max(1);
+
+        Palette { colors: (0..len).map(|idx| self.sample(idx as f32 / len as f32)).collect() }
+    }
+}
+
+/// Returns the color at `fraction` of the way from `low` to `high`.
+fn mix(low: [u8; 4], high: [u8; 4], fraction: f32) -> [u8; 4] {
+    let mut color = [255; 4];
+
+    color.iter_mut().zip(low.iter().zip(&high)).for_each(|(channel, (&low, &high))| *channel = (low as f32 + (high as f32 - low as f32) * fraction).round() as u8);
+
+    color
+}
+
+/// A palette file (see `Palette::load()`), which is
+/// read again whenever it's modified.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::palette::PaletteFile;
+/// # use std::{fs::{self, File}, time::{Duration, SystemTime}};
+/// let path = std::env::temp_dir().join("mandelbrust-doctest.hex");
+///
+/// fs::write(&path, "#000000\n#ffffff").unwrap();
+///
+/// let (mut file, palette) = PaletteFile::open(&path).unwrap();
+///
+/// assert_eq!(palette.colors().len(), 2);
+/// assert!(file.reload().is_none());
+///
+/// fs::write(&path, "#ff0000").unwrap();
+/// File::options().write(true).open(&path).unwrap().set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+///
+/// assert_eq!(file.reload().unwrap().unwrap().colors(), &[[255, 0, 0, 255]]);
+/// assert!(file.reload().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PaletteFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl PaletteFile {
+    /// Reads the palette file at `path`, which is then watched.
+    pub fn open(path: &Path) -> io::Result<(Self, Palette)> {
+        let mut file = Self { path: path.to_owned(), modified: None };
+
+        file.modified = file.modified();
+
+        Palette::load(path).map(|palette| (file, palette))
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the time the file was last modified, if it can be read.
+    fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    /// Reads the file again if it was modified since it was last
+    /// read, returning its palette (or the error in reading it),
+    /// or returns `None` if it wasn't modified.
+    pub fn reload(&mut self) -> Option<io::Result<Palette>> {
+        let modified = self.modified();
+
+        if modified == self.modified {
+            return None;
+        }
+
+        self.modified = modified;
+
+        Some(Palette::load(&self.path))
+    }
+}
+
+/// Returns the luminance of an RGB `color` (between `0.0` and `255.0`).
+fn luminance(color: [f32; 3]) -> f32 {
+    0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2]
+}
+
+/// Returns the squared distance between two RGB colors.
+fn distance_sqr(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(&b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// Returns (at most) `count` dominant colors of an `rgba` image,
+/// from the darkest to the brightest, by clustering its opaque
+/// pixels with k-means, starting from the colors at evenly spaced
+/// quantiles of the luminance, so that the result is deterministic.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::palette::dominant_colors;
+/// // an image that is mostly red, with some blue
+/// let rgba: Vec<u8> = (0..100).flat_map(|idx| if idx < 70 { [200, 10, 10, 255] } else { [10, 10, 200, 255] }).collect();
+///
+/// assert_eq!(dominant_colors(&rgba, 2), vec![[10, 10, 200, 255], [200, 10, 10, 255]]);
+/// assert!(dominant_colors(&[0, 0, 0, 0], 2).is_empty());
+/// ```
+pub fn dominant_colors(rgba: &[u8], count: usize) -> Vec<[u8; 4]> {
+    let step = (rgba.len() / 4).div_ceil(MAX_SAMPLES).max(1);
+
+    let mut pixels: Vec<[f32; 3]> = rgba.chunks_exact(4).step_by(step).filter(|pixel| pixel[3] > 0).map(|pixel| [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32]).collect();
+
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    pixels.sort_by(|a, b| luminance(*a).total_cmp(&luminance(*b)));
+
+    let mut centroids: Vec<[f32; 3]> = (0..count).map(|idx| pixels[(2 * idx + 1) * pixels.len() / (2 * count)]).collect();
+
+    centroids.dedup();
+
+    for _ in 0..ROUNDS {
+        let mut sums = vec![([0.0; 3], 0usize); centroids.len()];
+
+        for &pixel in &pixels {
+            let nearest = (0..centroids.len()).min_by(|&a, &b| distance_sqr(pixel, centroids[a]).total_cmp(&distance_sqr(pixel, centroids[b]))).unwrap_or_default();
+
+            let (sum, n) = &mut sums[nearest];
+
+            sum.iter_mut().zip(&pixel).for_each(|(sum, channel)| *sum += channel);
+            *n += 1;
+        }
+
+        // the clusters left without pixels keep their centroid
+        for (centroid, (sum, n)) in centroids.iter_mut().zip(sums) {
+            if n > 0 {
+                *centroid = sum.map(|channel| channel / n as f32);
+            }
+        }
+    }
+
+    centroids.sort_by(|a, b| luminance(*a).total_cmp(&luminance(*b)));
+
+    centroids.into_iter().map(|[r, g, b]| [r.round() as u8, g.round() as u8, b.round() as u8, 255]).collect()
+}
+
+/// Reads a PNG image, returning its pixels as RGBA
+/// (whatever its color type) and its size.
+pub fn read_png<R: Read>(reader: R) -> io::Result<(Vec<u8>, (usize, usize))> {
+    let mut decoder = png::Decoder::new(reader);
+
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+
+    let info = reader.next_frame(&mut buffer).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]]).collect(),
+        // the indexed images are expanded to RGB(A)
+        png::ColorType::Grayscale | png::ColorType::Indexed => buffer.iter().flat_map(|&gray| [gray, gray, gray, 255]).collect(),
+    };
+
+    Ok((rgba, (info.width as usize, info.height as usize)))
+}
+
+/// A coloring that repeats, every `GRADIENT_LENGTH` iterations, a
+/// gradient going from the darkest to the brightest dominant color
+/// of an image (see `dominant_colors()`) and back, so that it has no
+/// seams; the points that don't escape are black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::ColorMapper, kernel::escape, palette::ImagePalette, utils::{Plottable, MandelPoint}};
+/// let rgba: Vec<u8> = (0..100).flat_map(|idx| if idx < 50 { [0, 0, 100, 255] } else { [250, 200, 0, 255] }).collect();
+///
+/// let palette = ImagePalette::from_image("sunset", &rgba, 2).unwrap();
+///
+/// assert_eq!(palette.name(), "sunset");
+/// assert_eq!(palette.gradient()[0], [0, 0, 100, 255]);
+/// assert_eq!(palette.gradient()[8], [250, 200, 0, 255]);
+/// assert_eq!(palette.color(&escape(MandelPoint::new((0.0, 0.0)), 64), 64), [0, 0, 0, 255]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImagePalette {
+    name: String,
+    gradient: Vec<[u8; 4]>,
+}
+
+impl ImagePalette {
+    /// Returns the palette called `name` of (at most) `count`
+    /// dominant colors of an `rgba` image, or `None` if
+    /// the image has no opaque pixels.
+    pub fn from_image(name: &str, rgba: &[u8], count: usize) -> Option<Self> {
+        let stops = dominant_colors(rgba, count);
+
+        if stops.is_empty() {
+            return None;
+        }
+
+        let gradient = (0..GRADIENT_LENGTH)
+            .map(|idx| {
+                // from the first stop to the last one, and back
+                let t = 1.0 - (2.0 * idx as f32 / GRADIENT_LENGTH as f32 - 1.0).abs();
+                let position = t * (stops.len() - 1) as f32;
+
+                let (low, high) = (stops[position.floor() as usize], stops[position.ceil() as usize]);
+
+                mix(low, high, position.fract())
+            })
+            .collect();
+
+        Some(Self { name: name.to_owned(), gradient })
+    }
+
+    /// Returns the colors of the gradient.
+    pub fn gradient(&self) -> &[[u8; 4]] {
+        &self.gradient
+    }
+
+    /// Returns a preview of the gradient, as an RGBA image of
+    /// the given `size`, showing it once from left to right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::palette::ImagePalette;
+    /// let palette = ImagePalette::from_image("gray", &[128, 128, 128, 255], 3).unwrap();
+    ///
+    /// assert_eq!(palette.preview((32, 4)), [128, 128, 128, 255].repeat(32 * 4));
+    /// ```
+    pub fn preview(&self, size: (usize, usize)) -> Vec<u8> {
+        let row: Vec<u8> = (0..size.0).flat_map(|x| self.gradient[x * self.gradient.len() / size.0]).collect();
+
+        row.repeat(size.1)
+    }
+}
+
+impl ColorMapper for ImagePalette {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn color(&self, result: &EscapeResult, _max_iterations: usize) -> [u8; 4] {
+        if result.escaped {
+            self.gradient[result.iterations % self.gradient.len()]
+        } else {
+            [0, 0, 0, 255]
+        }
+    }
+}
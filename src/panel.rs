@@ -0,0 +1,185 @@
+//! An immediate-mode settings panel: at each frame, the window
+//! lays out the widgets of the panel, one per row, getting back
+//! whether the pointer changed them, and then draws the `Item`s
+//! the panel was left with; the panel itself only remembers
+//! which slider is being dragged.
+
+/// The width (in pixels) of a `Panel`.
+pub const PANEL_WIDTH: f32 = 280.0;
+
+/// The height (in pixels) of a row of a `Panel`.
+pub const ROW_HEIGHT: f32 = 36.0;
+
+/// The space (in pixels) around the widgets of a `Panel`.
+pub const PANEL_PADDING: f32 = 10.0;
+
+/// The height (in pixels) of the label of a row,
+/// above the track of a slider.
+pub const LABEL_HEIGHT: f32 = 18.0;
+
+/// A rectangle, as its top-left corner, its width and its height.
+pub type Bounds = (f32, f32, f32, f32);
+
+/// Returns whether `point` is inside of `bounds`.
+fn contains((x, y, w, h): Bounds, (px, py): (f32, f32)) -> bool {
+    px >= x && px <= x + w && py >= y && py <= y + h
+}
+
+/// The state of the pointer at a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PanelInput {
+    /// The position of the pointer, if it's in the window.
+    pub pointer: Option<(f32, f32)>,
+    /// Whether the button is held down.
+    pub down: bool,
+    /// Whether the button was pressed since the last frame.
+    pub pressed: bool,
+}
+
+/// A widget laid out by a `Panel`, to be drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    /// A slider, with the position of its
+    /// value along the track, from `0` to `1`.
+    Slider { label: String, track: Bounds, fraction: f32 },
+    /// A checkbox, with its box.
+    Checkbox { label: String, check: Bounds, checked: bool },
+}
+
+/// A settings panel, with its top-left corner at `origin`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::panel::{Item, Panel, PanelInput, PANEL_PADDING, PANEL_WIDTH, ROW_HEIGHT};
+/// let mut panel = Panel::new((0.0, 0.0));
+/// let (mut iterations, mut hud) = (256.0, false);
+///
+/// // pressing the button on the middle of the track of the slider
+/// let middle = (PANEL_WIDTH / 2.0, PANEL_PADDING + ROW_HEIGHT - 8.0);
+///
+/// panel.begin(PanelInput { pointer: Some(middle), down: true, pressed: true });
+///
+/// assert!(panel.slider("iterations".to_owned(), &mut iterations, (0.0, 1000.0)));
+/// assert!(!panel.checkbox("HUD".to_owned(), &mut hud));
+/// assert_eq!(iterations, 500.0);
+///
+/// // dragging it to the end, even past the panel
+/// panel.begin(PanelInput { pointer: Some((2.0 * PANEL_WIDTH, 0.0)), down: true, pressed: false });
+///
+/// assert!(panel.slider("iterations".to_owned(), &mut iterations, (0.0, 1000.0)));
+/// assert!(!panel.checkbox("HUD".to_owned(), &mut hud));
+/// assert_eq!(iterations, 1000.0);
+///
+/// // clicking on the second row
+/// panel.begin(PanelInput { pointer: Some((20.0, PANEL_PADDING + 1.5 * ROW_HEIGHT)), down: true, pressed: true });
+///
+/// assert!(!panel.slider("iterations".to_owned(), &mut iterations, (0.0, 1000.0)));
+/// assert!(panel.checkbox("HUD".to_owned(), &mut hud));
+/// assert!(hud);
+/// assert!(matches!(panel.items(), [Item::Slider { fraction, .. }, Item::Checkbox { checked: true, .. }] if *fraction == 1.0));
+/// assert!(panel.contains((20.0, 20.0)));
+/// assert!(!panel.contains((PANEL_WIDTH + 1.0, 20.0)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Panel {
+    origin: (f32, f32),
+    /// The index of the slider that is being dragged.
+    active: Option<usize>,
+    input: PanelInput,
+    items: Vec<Item>,
+}
+
+impl Panel {
+    /// Returns an empty panel, with its
+    /// top-left corner at `origin`.
+    pub fn new(origin: (f32, f32)) -> Self {
+        Self { origin, ..Self::default() }
+    }
+
+    /// Starts a new frame, forgetting the widgets of the
+    /// last one; the slider that was being dragged stays
+    /// so until the button is released (or pressed again).
+    pub fn begin(&mut self, input: PanelInput) {
+        if !input.down || input.pressed {
+            self.active = None;
+        }
+
+        self.input = input;
+        self.items.clear();
+    }
+
+    /// Returns the bounds of the next row.
+    fn next_row(&self) -> Bounds {
+        let (x, y) = self.origin;
+
+        (x + PANEL_PADDING, y + PANEL_PADDING + self.items.len() as f32 * ROW_HEIGHT, PANEL_WIDTH - 2.0 * PANEL_PADDING, ROW_HEIGHT)
+    }
+
+    /// Returns whether the button was pressed on `bounds`.
+    fn pressed_on(&self, bounds: Bounds) -> bool {
+        self.input.pressed && self.input.pointer.is_some_and(|pointer| contains(bounds, pointer))
+    }
+
+    /// Lays out a slider for `value`, within `range`, which
+    /// follows the pointer while the button is held after
+    /// being pressed on its row; returns whether `value` changed.
+    pub fn slider(&mut self, label: String, value: &mut f32, range: (f32, f32)) -> bool {
+        let idx = self.items.len();
+        let row = self.next_row();
+        let track = (row.0, row.1 + LABEL_HEIGHT, row.2, row.3 - LABEL_HEIGHT);
+
+        if self.pressed_on(row) {
+            self.active = Some(idx);
+        }
+
+        let mut changed = false;
+
+        if let (Some(pointer), Some(true)) = (self.input.pointer, self.active.map(|active| active == idx)) {
+            let fraction = ((pointer.0 - track.0) / track.2).clamp(0.0, 1.0);
+            let dragged = range.0 + fraction * (range.1 - range.0);
+
+            changed = dragged != *value;
+            *value = dragged;
+        }
+
+        let fraction = if range.1 > range.0 { ((*value - range.0) / (range.1 - range.0)).clamp(0.0, 1.0) } else { 0.0 };
+
+        self.items.push(Item::Slider { label, track, fraction });
+
+        changed
+    }
+
+    /// Lays out a checkbox for `checked`, which is toggled by
+    /// pressing the button on its row; returns whether it was.
+    pub fn checkbox(&mut self, label: String, checked: &mut bool) -> bool {
+        let row = self.next_row();
+        let side = row.3 - 2.0 * PANEL_PADDING;
+
+        let toggled = self.pressed_on(row);
+
+        if toggled {
+            *checked = !*checked;
+        }
+
+        self.items.push(Item::Checkbox { label, check: (row.0, row.1 + PANEL_PADDING, side, side), checked: *checked });
+
+        toggled
+    }
+
+    /// Returns the widgets laid out since the last `begin()`.
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// Returns the bounds of the whole panel,
+    /// with the widgets laid out so far.
+    pub fn bounds(&self) -> Bounds {
+        (self.origin.0, self.origin.1, PANEL_WIDTH, self.items.len() as f32 * ROW_HEIGHT + 2.0 * PANEL_PADDING)
+    }
+
+    /// Returns whether `point` is on the panel.
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        contains(self.bounds(), point)
+    }
+}
@@ -0,0 +1,421 @@
+use crate::{fixed::Fixed, utils::*};
+use std::{error, fmt, str::FromStr};
+
+/// The error returned when a string can't
+/// be parsed into one of the crate types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The string is empty.
+    Empty,
+    /// A value is not a valid number.
+    InvalidNumber(String),
+    /// A value is not a valid complex number.
+    InvalidComplex(String),
+    /// A field is not in the form `key=value`.
+    InvalidField(String),
+    /// A key is not recognized.
+    UnknownKey(String),
+    /// A key appears more than once.
+    DuplicateKey(String),
+    /// A required key is missing.
+    MissingKey(&'static str),
+    /// The zoom is not a positive finite number.
+    InvalidZoom(f32),
+    /// A value is not a valid `Orientation`.
+    InvalidOrientation(String),
+    /// A value is not a valid hexadecimal color.
+    InvalidColor(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty string"),
+            ParseError::InvalidNumber(value) => write!(f, "`{}` is not a valid number", value),
+            ParseError::InvalidComplex(value) => write!(f, "`{}` is not a valid complex number (expected e.g. `-0.7436+0.1318i`)", value),
+            ParseError::InvalidField(field) => write!(f, "`{}` is not in the form `key=value`", field),
+            ParseError::UnknownKey(key) => write!(f, "unknown key `{}` (expected `center`, `re`, `im`, `zoom`, `rotation` or `orientation`)", key),
+            ParseError::DuplicateKey(key) => write!(f, "the key `{}` appears more than once", key),
+            ParseError::MissingKey(key) => write!(f, "the key `{}` is missing", key),
+            ParseError::InvalidZoom(zoom) => write!(f, "the zoom must be a positive number, found `{}`", zoom),
+            ParseError::InvalidOrientation(value) => write!(f, "`{}` is not a valid orientation (expected `math` or `screen`)", value),
+            ParseError::InvalidColor(value) => write!(f, "`{}` is not a valid color (expected e.g. `#ff0000` or `ff000080`)", value),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parses a single real number.
+fn parse_number<T: FromStr>(value: &str) -> Result<T, ParseError> {
+    value.parse().map_err(|_| ParseError::InvalidNumber(value.to_owned()))
+}
+
+/// Parses the imaginary part of a complex number
+/// (without the trailing `i`), where an empty
+/// coefficient, or a lonely sign, means `1`.
+fn parse_imaginary<T: FromStr + From<i8>>(value: &str) -> Result<T, ParseError> {
+    match value {
+        "" | "+" => Ok(T::from(1)),
+        "-" => Ok(T::from(-1)),
+        _ => parse_number(value),
+    }
+}
+
+/// Parses the real and the imaginary part of a complex
+/// number, in the forms accepted by `MandelPoint` (see
+/// its `FromStr` implementation), with the precision of
+/// `T` (e.g. the one of the center of a `Viewport`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::parse::parse_complex;
+/// assert_eq!(parse_complex::<f64>("-0.743643887037151 + 0.13182590420533i"), Ok((-0.743643887037151, 0.13182590420533)));
+/// assert_eq!(parse_complex::<f64>("(0.25, -1)"), Ok((0.25, -1.0)));
+/// ```
+pub fn parse_complex<T: FromStr + From<i8>>(s: &str) -> Result<(T, T), ParseError> {
+    let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if compact.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    // tuple form, as in `(re, im)`
+    if let Some(inner) = compact.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) {
+        let (re, im) = inner.split_once(',').ok_or_else(|| ParseError::InvalidComplex(s.trim().to_owned()))?;
+
+        return Ok((parse_number(re)?, parse_number(im)?));
+    }
+
+    let imaginary = match compact.strip_suffix('i') {
+        Some(imaginary) => imaginary,
+        None => return Ok((parse_number(&compact)?, T::from(0))),
+    };
+
+    // the sign between the real and the imaginary part is
+    // the last one that is not the sign of an exponent
+    let bytes = imaginary.as_bytes();
+
+    let split = (1..bytes.len())
+        .rev()
+        .find(|&idx| (bytes[idx] == b'+' || bytes[idx] == b'-') && !matches!(bytes[idx - 1], b'e' | b'E'));
+
+    let result = match split {
+        Some(idx) => parse_number(&imaginary[..idx]).and_then(|re| Ok((re, parse_imaginary(&imaginary[idx..])?))),
+        None => parse_imaginary(imaginary).map(|im| (T::from(0), im)),
+    };
+
+    result.map_err(|_| ParseError::InvalidComplex(s.trim().to_owned()))
+}
+
+/// Parses a `MandelPoint` from strings like `-0.7436+0.1318i`,
+/// `-0.7436 - 0.1318i`, `1.2e-4+3.5e0i`, `0.25`, `-2i` or
+/// `(-0.7436, 0.1318)`, which include the outputs of its
+/// `Display`, `LowerExp` and `Debug` implementations.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+/// let mandelpoint: MandelPoint = "-0.75 + 0.1i".parse().unwrap();
+///
+/// assert_eq!(mandelpoint, MandelPoint::new((-0.75, 0.1)));
+/// assert_eq!("(-0.75, 0.1)".parse(), Ok(mandelpoint));
+/// assert_eq!("-i".parse(), Ok(MandelPoint::new((0.0, -1.0))));
+/// assert!("-0.75 + 0.1j".parse::<MandelPoint>().is_err());
+/// ```
+impl FromStr for MandelPoint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_complex(s).map(MandelPoint::new)
+    }
+}
+
+/// Parses an `Orientation` from `math` or `screen`
+/// (see its `Display` implementation).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::Orientation;
+/// assert_eq!("screen".parse(), Ok(Orientation::Screen));
+/// assert!("up".parse::<Orientation>().is_err());
+/// ```
+impl FromStr for Orientation {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "math" => Ok(Orientation::Math),
+            "screen" => Ok(Orientation::Screen),
+            _ => Err(ParseError::InvalidOrientation(s.trim().to_owned())),
+        }
+    }
+}
+
+/// Parses an RGBA color from its hexadecimal notation,
+/// `RRGGBB` or `RRGGBBAA`, optionally preceded by `#`
+/// (the color is opaque if the alpha is missing).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::parse::parse_color;
+/// assert_eq!(parse_color("#ff8000"), Ok([255, 128, 0, 255]));
+/// assert_eq!(parse_color("FF800080"), Ok([255, 128, 0, 128]));
+/// assert!(parse_color("#f80").is_err());
+/// ```
+pub fn parse_color(s: &str) -> Result<[u8; 4], ParseError> {
+    let invalid = || ParseError::InvalidColor(s.trim().to_owned());
+
+    let hex = s.trim().trim_start_matches('#');
+
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let mut color = [255; 4];
+
+    for (channel, idx) in color.iter_mut().zip((0..hex.len()).step_by(2)) {
+        *channel = u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| invalid())?;
+    }
+
+    Ok(color)
+}
+
+/// Splits a list of `key=value` fields, separated by commas,
+/// semicolons or whitespace; since complex numbers may contain
+/// spaces (as in `-0.75 + 0.1i`), whitespace-separated pieces
+/// without a `=` are glued to the previous field.
+fn split_fields(s: &str) -> Vec<String> {
+    fn push(current: &mut String, separated_by_comma: bool, fields: &mut Vec<String>) {
+        if current.is_empty() {
+            return;
+        }
+
+        match fields.last_mut() {
+            Some(last) if !separated_by_comma && !current.contains('=') => last.push_str(current),
+            _ => fields.push(current.clone()),
+        }
+
+        current.clear();
+    }
+
+    let mut fields: Vec<String> = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    let mut separated_by_comma = true;
+
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+
+        if depth == 0 && (c == ',' || c == ';') {
+            push(&mut current, separated_by_comma, &mut fields);
+
+            separated_by_comma = true;
+        } else if depth == 0 && c.is_whitespace() {
+            if !current.is_empty() {
+                push(&mut current, separated_by_comma, &mut fields);
+
+                separated_by_comma = false;
+            }
+        } else {
+            current.push(c);
+        }
+    }
+
+    push(&mut current, separated_by_comma, &mut fields);
+
+    fields
+}
+
+/// Stores `value` in `slot`, failing if it was already set.
+fn set_once<T>(slot: &mut Option<T>, key: &str, value: T) -> Result<(), ParseError> {
+    match slot.replace(value) {
+        Some(_) => Err(ParseError::DuplicateKey(key.to_owned())),
+        None => Ok(()),
+    }
+}
+
+/// Parses a `Viewport` from a list of `key=value` fields, like
+/// `center=-0.7436+0.1318i, zoom=2, rotation=30`, where:
+/// - `center` is the center of the view, which can
+///   also be given through its `re` and `im` parts,
+///   and is read with the precision of `Fixed`
+/// - `zoom` is optional, and defaults to `1`
+/// - `rotation` is optional, it's expressed in
+///   degrees, and defaults to `0`
+/// - `orientation` is optional, it's either `math`
+///   (the default) or `screen` (see `Orientation`)
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fixed::Fixed, utils::{Orientation, Viewport}};
+/// let viewport: Viewport = "center=-0.75+0.1i,zoom=20".parse().unwrap();
+///
+/// assert_eq!(viewport, Viewport::with_precise_center(("-0.75".parse().unwrap(), "0.1".parse().unwrap()), 20.0, 0.0));
+/// assert_eq!("re=-0.75 im=0.1 zoom=20".parse(), Ok(viewport));
+/// assert_eq!(viewport.to_string().parse(), Ok(viewport));
+/// assert_eq!("center=-0.75+0.1i,zoom=20,orientation=screen".parse::<Viewport>().map(|viewport| viewport.orientation()), Ok(Orientation::Screen));
+/// assert!("center=-0.75+0.1i,zoom=0".parse::<Viewport>().is_err());
+/// ```
+impl FromStr for Viewport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = split_fields(s);
+
+        if fields.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let mut center = None;
+        let mut re: Option<Fixed> = None;
+        let mut im: Option<Fixed> = None;
+        let mut zoom: Option<f32> = None;
+        let mut rotation: Option<f32> = None;
+        let mut orientation = None;
+
+        for field in fields {
+            let (key, value) = field.split_once('=').ok_or_else(|| ParseError::InvalidField(field.clone()))?;
+
+            match key.to_lowercase().as_str() {
+                "center" | "c" => set_once(&mut center, key, parse_complex(value)?)?,
+                "re" | "x" => set_once(&mut re, key, parse_number(value)?)?,
+                "im" | "y" => set_once(&mut im, key, parse_number(value)?)?,
+                "zoom" => set_once(&mut zoom, key, parse_number(value)?)?,
+                "rotation" => set_once(&mut rotation, key, parse_number(value)?)?,
+                "orientation" => set_once(&mut orientation, key, value.parse::<Orientation>()?)?,
+                _ => return Err(ParseError::UnknownKey(key.to_owned())),
+            }
+        }
+
+        let center = match (center, re, im) {
+            (Some(_), Some(_), _) | (Some(_), _, Some(_)) => return Err(ParseError::DuplicateKey("center".to_owned())),
+            (Some(center), None, None) => center,
+            (None, None, None) => return Err(ParseError::MissingKey("center")),
+            (None, re, im) => (re.unwrap_or_default(), im.unwrap_or_default()),
+        };
+
+        let zoom = zoom.unwrap_or(1.0);
+
+        if !(zoom.is_finite() && zoom > 0.0) {
+            return Err(ParseError::InvalidZoom(zoom));
+        }
+
+        let mut viewport = Viewport::with_precise_center(center, zoom, rotation.unwrap_or(0.0).to_radians());
+
+        viewport.set_orientation(orientation.unwrap_or_default());
+
+        Ok(viewport)
+    }
+}
+
+/// A location to share, as a compact string (see its `Display`
+/// implementation): a view, with the iteration cap it needs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    /// The view of the location.
+    pub viewport: Viewport,
+    /// The maximum number of iterations, if given.
+    pub max_iterations: Option<usize>,
+}
+
+/// The `Display` implementation of `Location` shows it as
+/// `re=… im=… zoom=… iter=…` (with the rotation in degrees,
+/// and the orientation, when they are not the default ones),
+/// in the shortest form that is parsed back into the same
+/// `Location`, unless a precision is specified, which is the
+/// number of decimal digits of the center (e.g. the ones given
+/// by `Viewport::center_precision()`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{parse::Location, utils::{Plottable, MandelPoint, Viewport}};
+/// let location = Location { viewport: Viewport::new(MandelPoint::new((-0.75, 0.125)), 1e9, 0.0), max_iterations: Some(2000) };
+///
+/// assert_eq!(location.to_string(), "re=-0.75 im=0.125 zoom=1000000000 iter=2000");
+/// assert_eq!(format!("{:.3}", location), "re=-0.750 im=0.125 zoom=1000000000 iter=2000");
+/// ```
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (re, im) = self.viewport.precise_center();
+
+        match f.precision() {
+            Some(precision) => write!(f, "re={:.*} im={:.*} zoom={}", precision, re, precision, im, self.viewport.zoom())?,
+            None => write!(f, "re={} im={} zoom={}", re, im, self.viewport.zoom())?,
+        }
+
+        if self.viewport.rotation() != 0.0 {
+            write!(f, " rotation={}", self.viewport.rotation().to_degrees())?;
+        }
+
+        if self.viewport.orientation() != Orientation::default() {
+            write!(f, " orientation={}", self.viewport.orientation())?;
+        }
+
+        if let Some(max_iterations) = self.max_iterations {
+            write!(f, " iter={}", max_iterations)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a `Location` from the fields of a `Viewport`
+/// (see its `FromStr` implementation), with an optional
+/// iteration cap, given as `iter` (or `iterations`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{parse::Location, utils::{Plottable, MandelPoint, Orientation, Viewport}};
+/// let location: Location = "re=-0.743643887 im=0.131825904 zoom=1e9 iter=2000".parse().unwrap();
+///
+/// assert_eq!(location.viewport, Viewport::with_precise_center(("-0.743643887".parse().unwrap(), "0.131825904".parse().unwrap()), 1e9, 0.0));
+/// assert_eq!(location.max_iterations, Some(2000));
+/// assert_eq!(location.to_string().parse(), Ok(location));
+///
+/// // the iteration cap is optional
+/// assert_eq!("center=-0.75+0.1i, zoom=20".parse::<Location>().map(|location| location.max_iterations), Ok(None));
+///
+/// // the rotation and the orientation round-trip too
+/// let mut viewport = Viewport::new(MandelPoint::new((0.25, -0.5)), 3.5, 30f32.to_radians());
+///
+/// viewport.set_orientation(Orientation::Screen);
+///
+/// let location = Location { viewport, max_iterations: None };
+///
+/// assert_eq!(location.to_string().parse(), Ok(location));
+/// assert!("re=-0.75 iter=many".parse::<Location>().is_err());
+/// assert!("re=-0.75 iter=1 iter=2".parse::<Location>().is_err());
+/// ```
+impl FromStr for Location {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut max_iterations = None;
+        let mut fields = Vec::new();
+
+        for field in split_fields(s) {
+            match field.split_once('=') {
+                Some((key, value)) if matches!(key.to_lowercase().as_str(), "iter" | "iterations") => {
+                    let iterations = value.parse().map_err(|_| ParseError::InvalidNumber(value.to_owned()))?;
+
+                    set_once(&mut max_iterations, key, iterations)?;
+                }
+                _ => fields.push(field),
+            }
+        }
+
+        Ok(Self { viewport: fields.join(", ").parse()?, max_iterations })
+    }
+}
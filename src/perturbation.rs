@@ -0,0 +1,277 @@
+use crate::{fixed::Fixed, kernel::EscapeResult, precision::Real, utils::*};
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// The tolerance of the glitch detection: a point is glitched
+/// when its `z` gets closer to the origin than this fraction of
+/// the reference `Z` (see `ReferenceOrbit::try_escape()`).
+pub const GLITCH_TOLERANCE: f64 = 1e-3;
+
+/// The maximum number of secondary reference orbits a
+/// render iterates the glitched pixels against.
+pub const MAX_REFERENCES: usize = 16;
+
+/// The orbit of a reference point `C` under `z = z^2 + c`, iterated
+/// in a precise arithmetic and stored as `f64`, which the points `c`
+/// close to `C` are iterated against as small differences (see
+/// `ReferenceOrbit::escape()`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fixed::Fixed, perturbation::ReferenceOrbit, precision::Real};
+/// let reference = ReferenceOrbit::new((Fixed::from_f32(-1.0), Fixed::default()), 64);
+///
+/// // `-1` never escapes, so its orbit lasts until the iteration cap
+/// assert_eq!(reference.len(), 66);
+/// assert_eq!(ReferenceOrbit::new((1.0f64, 1.0f64), 64).len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceOrbit {
+    c: (f64, f64),
+    orbit: Vec<(f64, f64)>,
+}
+
+impl ReferenceOrbit {
+    /// Iterates the reference point `c` in the arithmetic of `T`,
+    /// from `z = 0` until it escapes, or for `max_iterations`.
+    pub fn new<T: Real>(c: (T, T), max_iterations: usize) -> Self {
+        let four = T::from_f32(4.0);
+
+        let (c_re, c_im) = c;
+        let (mut re, mut im) = (T::default(), T::default());
+
+        let mut orbit = vec![(0.0, 0.0)];
+
+        // the pixels take up to `max_iterations + 1` steps
+        while orbit.len() <= max_iterations + 1 {
+            let (re_sqr, im_sqr) = (re * re, im * im);
+
+            im = (re + re) * im + c_im;
+            re = re_sqr - im_sqr + c_re;
+
+            orbit.push((re.to_f64(), im.to_f64()));
+
+            if T::norm_sqr(re, im) > four {
+                break;
+            }
+        }
+
+        Self { c: (c_re.to_f64(), c_im.to_f64()), orbit }
+    }
+
+    /// Returns the number of values of the orbit, including `z = 0`.
+    pub fn len(&self) -> usize {
+        self.orbit.len()
+    }
+
+    /// Returns whether the orbit has no values, which never happens.
+    pub fn is_empty(&self) -> bool {
+        self.orbit.is_empty()
+    }
+
+    /// Iterates the point `C + delta_c` like `precision::escape()`,
+    /// but only tracking the difference `δ` of its orbit from the
+    /// reference one, with `δ' = (2Z + δ)δ + δc`, which `f64` keeps
+    /// precise however small the differences are; whenever `z` gets
+    /// closer to the origin than `δ` (or the reference orbit ends),
+    /// `δ` is rebased on the start of the reference orbit, which
+    /// avoids the glitches of the points whose orbit departs from it.
+    pub fn escape(&self, delta_c: (f64, f64), max_iterations: usize) -> EscapeResult {
+        self.iterate(delta_c, max_iterations, true).unwrap_or_default()
+    }
+
+    /// Iterates the point `C + delta_c` like `escape()`, but without
+    /// rebasing, returning `None` if it glitches: when `|Z + δ|`
+    /// gets below `GLITCH_TOLERANCE` times `|Z|` (Pauldelbrot's
+    /// criterion), the precision of `δ` is lost, and when the
+    /// reference orbit escapes first, there is nothing left to
+    /// iterate against; either way, the point needs another
+    /// reference (see `escape_rows()`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{fixed::Fixed, perturbation::ReferenceOrbit};
+    /// // the reference `0.3` escapes after 12 iterations
+    /// let reference = ReferenceOrbit::new((0.3f64, 0.0f64), 256);
+    ///
+    /// assert_eq!(reference.try_escape((0.0, 0.0), 256), Some(reference.escape((0.0, 0.0), 256)));
+    /// assert_eq!(reference.try_escape((-0.3, 0.0), 256), None);
+    /// assert_eq!(ReferenceOrbit::new((0.0f64, 0.0f64), 256).try_escape((0.3, 0.0), 256), Some(reference.escape((0.0, 0.0), 256)));
+    ///
+    /// // the orbit of a point next to `0` gets far closer to the origin than the one of `-1`
+    /// assert_eq!(ReferenceOrbit::new((-1.0f64, 0.0f64), 256).try_escape((1.0 - 1e-5, 0.0), 256), None);
+    /// assert!(ReferenceOrbit::new((-1.0f64, 0.0f64), 256).try_escape((0.7, 0.0), 256).is_some());
+    /// ```
+    pub fn try_escape(&self, delta_c: (f64, f64), max_iterations: usize) -> Option<EscapeResult> {
+        self.iterate(delta_c, max_iterations, false)
+    }
+
+    /// Iterates the point `C + delta_c`, either rebasing `δ`
+    /// (see `escape()`) or giving up on the glitches (see
+    /// `try_escape()`).
+    fn iterate(&self, delta_c: (f64, f64), max_iterations: usize, rebase: bool) -> Option<EscapeResult> {
+        let (dc_re, dc_im) = delta_c;
+
+        let (mut re, mut im) = (0.0, 0.0);
+        let (mut delta_re, mut delta_im) = (0.0, 0.0);
+        let mut idx = 0;
+
+        let mandel_c = MandelPoint::new((self.c.0 + dc_re, self.c.1 + dc_im));
+
+        let mut result = EscapeResult { c: mandel_c, ..EscapeResult::default() };
+        let mut min_norm_sqr = f64::INFINITY;
+
+        while !result.escaped && result.iterations <= max_iterations {
+            // the derivative only needs the precision of `f64`
+            let z = MandelPoint::new((re, im));
+
+            result.derivative = (z * 2.0).complex_mul(result.derivative) + MandelPoint::new((1.0, 0.0));
+
+            let (reference_re, reference_im) = self.orbit[idx];
+            let (factor_re, factor_im) = (2.0 * reference_re + delta_re, 2.0 * reference_im + delta_im);
+
+            (delta_re, delta_im) = (factor_re * delta_re - factor_im * delta_im + dc_re, factor_re * delta_im + factor_im * delta_re + dc_im);
+
+            idx += 1;
+
+            let (reference_re, reference_im) = self.orbit[idx];
+
+            (re, im) = (reference_re + delta_re, reference_im + delta_im);
+
+            let norm_sqr = re * re + im * im;
+
+            if !rebase && norm_sqr < GLITCH_TOLERANCE * GLITCH_TOLERANCE * (reference_re * reference_re + reference_im * reference_im) {
+                return None;
+            }
+
+            result.iterations += 1;
+
+            if norm_sqr > 4.0 {
+                result.escaped = true;
+            } else {
+                result.orbit.push(norm_sqr.sqrt() as f32);
+
+                if norm_sqr < min_norm_sqr {
+                    min_norm_sqr = norm_sqr;
+                    result.atom_domain = result.iterations;
+                }
+
+                // the reference escaped first
+                if idx + 1 == self.orbit.len() && result.iterations <= max_iterations && !rebase {
+                    return None;
+                }
+
+                if norm_sqr < delta_re * delta_re + delta_im * delta_im && rebase || idx + 1 == self.orbit.len() {
+                    (delta_re, delta_im) = (re, im);
+                    idx = 0;
+                }
+            }
+        }
+
+        result.z = MandelPoint::new((re, im));
+
+        Some(result)
+    }
+}
+
+/// Iterates the Mandelbrot set on every pixel of the `rows` of a
+/// screen of the given `size` showing `viewport`, by perturbation:
+/// the orbit of the (`f64`) center is iterated once, with `Fixed`,
+/// and every pixel is iterated as a difference from it (see
+/// `ReferenceOrbit::try_escape()`), which is about as fast as `f64`,
+/// but as precise as the reference orbit; the glitched pixels are
+/// iterated again against the orbit of one of them, up to
+/// `MAX_REFERENCES` times, and the ones still glitched after
+/// that are rebased on the center (see `ReferenceOrbit::escape()`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fixed::Fixed, perturbation::{self, ReferenceOrbit}, precision, utils::{Plottable, MandelPoint, Point, Viewport}};
+/// let viewport = Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e6, 0.0);
+///
+/// let results = perturbation::escape_rows(&viewport, (30, 20), 0..20, 512);
+/// let fixed = precision::Precision::Fixed.escape_results(&mandelbrust::fractal::Mandelbrot, &viewport, (30, 20), 512);
+///
+/// let mismatches = results.iter().zip(&fixed).filter(|(result, fixed)| result.iterations != fixed.iterations).count();
+///
+/// assert_eq!(results.len(), 30 * 20);
+/// assert!(mismatches <= 6);
+///
+/// // the centers closer than the precision of `f32` are still apart
+/// let iterations = |re: f64| {
+///     let viewport = Viewport::new(MandelPoint::new((re, 0.131825904)), 1e9, 0.0);
+///
+///     perturbation::escape_rows(&viewport, (30, 20), 0..20, 2000).iter().map(|result| result.iterations).collect::<Vec<_>>()
+/// };
+///
+/// assert_ne!(iterations(-0.743643887), iterations(-0.743643887 + 3e-9));
+///
+/// // the center is 3 pixels to the left of the nucleus of the period-3
+/// // minibrot, whose orbit gets to the origin, unlike the one of the center
+/// let nucleus = -1.7548776662466927;
+/// let pixel_size = Viewport::new(MandelPoint::new((nucleus, 0.0)), 1e6, 0.0).pixel_size((16, 12));
+///
+/// let viewport = Viewport::new(MandelPoint::new((nucleus - 3.0 * pixel_size, 0.0)), 1e6, 0.0);
+/// let reference = ReferenceOrbit::new((Fixed::from_f64(nucleus - 3.0 * pixel_size), Fixed::default()), 2000);
+///
+/// let glitched = (0..16 * 12)
+///     .filter(|idx| {
+///         let pixel = viewport.orient(Point::new((idx % 16, idx / 16)), (16, 12));
+///
+///         reference.try_escape(viewport.pixel_offset(pixel, (16, 12)), 2000).is_none()
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let results = perturbation::escape_rows(&viewport, (16, 12), 0..12, 2000);
+/// let fixed = precision::Precision::Fixed.escape_results(&mandelbrust::fractal::Mandelbrot, &viewport, (16, 12), 2000);
+///
+/// assert_eq!(glitched, [6 * 16 + 8 + 3]);
+/// assert!(results.iter().zip(&fixed).all(|(result, fixed)| result.iterations == fixed.iterations));
+/// ```
+pub fn escape_rows(viewport: &Viewport, size: (usize, usize), rows: Range<usize>, max_iterations: usize) -> Vec<EscapeResult> {
+    let w = size.0;
+    let first = rows.start * w;
+
+    let offset = |idx: usize| viewport.pixel_offset(viewport.orient(Point::new((idx % w, idx / w)), size), size);
+
+    // the differences from the reference are the offsets of the pixels
+    let (re, im) = viewport.precise_center();
+    let reference = ReferenceOrbit::new((re, im), max_iterations);
+
+    let mut results: Vec<_> = (first..rows.end * w).into_par_iter().map(|idx| reference.try_escape(offset(idx), max_iterations)).collect();
+
+    for _ in 0..MAX_REFERENCES {
+        let glitched: Vec<_> = results.iter().enumerate().filter(|(_, result)| result.is_none()).map(|(idx, _)| idx).collect();
+
+        if glitched.is_empty() {
+            break;
+        }
+
+        // the secondary reference never glitches against itself,
+        // so at least a pixel is fixed at each round
+        let (secondary_re, secondary_im) = offset(first + glitched[glitched.len() / 2]);
+        let secondary = ReferenceOrbit::new((re + Fixed::from_f64(secondary_re), im + Fixed::from_f64(secondary_im)), max_iterations);
+
+        let retried: Vec<_> = glitched
+            .par_iter()
+            .map(|&idx| {
+                let (delta_re, delta_im) = offset(first + idx);
+
+                secondary.try_escape((delta_re - secondary_re, delta_im - secondary_im), max_iterations)
+            })
+            .collect();
+
+        for (idx, result) in glitched.into_iter().zip(retried) {
+            results[idx] = result;
+        }
+    }
+
+    results
+        .into_par_iter()
+        .enumerate()
+        .map(|(idx, result)| result.unwrap_or_else(|| reference.escape(offset(first + idx), max_iterations)))
+        .collect()
+}
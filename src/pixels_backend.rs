@@ -0,0 +1,143 @@
+use crate::backend::{Input, Key, RenderBackend};
+use pixels::{Pixels, SurfaceTexture};
+use std::io;
+use winit::{
+    dpi::LogicalSize,
+    event::{ElementState, Event, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    platform::run_return::EventLoopExtRunReturn,
+    window::{Window, WindowBuilder},
+};
+
+/// The pixels scrolled by a touchpad that
+/// count as a step of the wheel.
+const SCROLL_STEP: f32 = 100.0;
+
+/// Translates a key code of `winit` into one of `explore()`.
+fn key(keycode: VirtualKeyCode) -> Option<Key> {
+    match keycode {
+        VirtualKeyCode::Left => Some(Key::Left),
+        VirtualKeyCode::Right => Some(Key::Right),
+        VirtualKeyCode::Up => Some(Key::Up),
+        VirtualKeyCode::Down => Some(Key::Down),
+        VirtualKeyCode::Plus | VirtualKeyCode::Equals | VirtualKeyCode::NumpadAdd => Some(Key::Plus),
+        VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => Some(Key::Minus),
+        VirtualKeyCode::R => Some(Key::R),
+        VirtualKeyCode::Escape => Some(Key::Escape),
+        _ => None,
+    }
+}
+
+/// A window drawn with `pixels` (on top of `wgpu`), whose
+/// events come from `winit`, which doesn't need `ggez`;
+/// the view is dragged with the right button, like in
+/// the explorer. The frames are copied into the buffer of
+/// the texture of `pixels`, which is updated in place,
+/// instead of creating a new texture at each frame; the
+/// buffer follows the size of the window.
+pub struct PixelsBackend {
+    // the surface must be dropped before the window
+    pixels: Pixels,
+    window: Window,
+    event_loop: EventLoop<()>,
+    size: (usize, usize),
+    cursor: Option<(f32, f32)>,
+    dragging: bool,
+}
+
+impl PixelsBackend {
+    /// Opens a window called `title`, showing frames of the given `size`.
+    pub fn new(title: &str, size: (usize, usize)) -> io::Result<Self> {
+        let event_loop = EventLoop::new();
+
+        let window = WindowBuilder::new()
+            .with_title(title)
+            .with_inner_size(LogicalSize::new(size.0 as f64, size.1 as f64))
+            .with_resizable(true)
+            .build(&event_loop)
+            .map_err(io::Error::other)?;
+
+        let physical = window.inner_size();
+
+        let pixels = Pixels::new(size.0 as u32, size.1 as u32, SurfaceTexture::new(physical.width, physical.height, &window)).map_err(io::Error::other)?;
+
+        Ok(Self { pixels, window, event_loop, size, cursor: None, dragging: false })
+    }
+}
+
+impl RenderBackend for PixelsBackend {
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn present(&mut self, rgba: &[u8]) -> io::Result<()> {
+        self.pixels.frame_mut().copy_from_slice(rgba);
+
+        self.pixels.render().map_err(io::Error::other)
+    }
+
+    fn poll_input(&mut self) -> Vec<Input> {
+        let mut inputs = Vec::new();
+
+        let scale_factor = self.window.scale_factor();
+        let (pixels, size, cursor, dragging) = (&mut self.pixels, &mut self.size, &mut self.cursor, &mut self.dragging);
+
+        // runs the event loop until it's out of events
+        self.event_loop.run_return(|event, _, control_flow| {
+            *control_flow = ControlFlow::Poll;
+
+            let window_event = match event {
+                Event::WindowEvent { event, .. } => event,
+                Event::MainEventsCleared => {
+                    *control_flow = ControlFlow::Exit;
+
+                    return;
+                }
+                _ => return,
+            };
+
+            match window_event {
+                WindowEvent::CloseRequested => inputs.push(Input::Close),
+                // a minimized window may have no pixels at all
+                WindowEvent::Resized(physical) if physical.width > 0 && physical.height > 0 => {
+                    let logical = physical.to_logical::<u32>(scale_factor);
+                    let resized = (logical.width.max(1), logical.height.max(1));
+
+                    let result = pixels.resize_surface(physical.width, physical.height).and_then(|_| pixels.resize_buffer(resized.0, resized.1));
+
+                    match result {
+                        Ok(()) => {
+                            *size = (resized.0 as usize, resized.1 as usize);
+
+                            inputs.push(Input::Resize(*size));
+                        }
+                        Err(err) => log::error!("could not resize the window: {}", err),
+                    }
+                }
+                WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(keycode), .. }, .. } => inputs.extend(key(keycode).map(Input::Key)),
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let steps = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => y,
+                        MouseScrollDelta::PixelDelta(position) => position.to_logical::<f32>(scale_factor).y / SCROLL_STEP,
+                    };
+
+                    inputs.push(Input::Scroll(steps));
+                }
+                WindowEvent::MouseInput { state, button: MouseButton::Right, .. } => *dragging = state == ElementState::Pressed,
+                WindowEvent::CursorMoved { position, .. } => {
+                    let position = position.to_logical::<f32>(scale_factor);
+                    let position = (position.x, position.y);
+
+                    if let (Some(last), true) = (*cursor, *dragging) {
+                        inputs.push(Input::Drag((position.0 - last.0, position.1 - last.1)));
+                    }
+
+                    *cursor = Some(position);
+                }
+                _ => {}
+            }
+        });
+
+        inputs
+    }
+}
@@ -0,0 +1,300 @@
+//! Plugins are dynamic libraries that add fractals and colorings
+//! to the explorer without recompiling it. To keep working across
+//! compiler versions, they talk to the explorer through a C ABI:
+//! a plugin exports a function named `mandelbrust_plugin`
+//! (see `PLUGIN_SYMBOL`) returning a pointer to a static
+//! `PluginDeclaration`, like:
+//!
+//! ```
+//! # pub use mandelbrust::{kernel, plugin::*, utils::{Plottable, MandelPoint}};
+//! extern "C" fn escape(re: f64, im: f64, max_iterations: u64) -> PluginEscapeResult {
+//!     // the conjugate of `z`, as in the Tricorn
+//!     kernel::escape(MandelPoint::new((re, -im)), max_iterations as usize).into()
+//! }
+//!
+//! static FRACTALS: [PluginFractal; 1] = [PluginFractal { name: b"mirror\0".as_ptr(), escape }];
+//!
+//! static DECLARATION: PluginDeclaration = PluginDeclaration {
+//!     abi_version: ABI_VERSION,
+//!     fractals: FRACTALS.as_ptr(),
+//!     fractal_count: 1,
+//!     colorings: std::ptr::null(),
+//!     coloring_count: 0,
+//! };
+//!
+//! #[no_mangle]
+//! pub extern "C" fn mandelbrust_plugin() -> *const PluginDeclaration {
+//!     &DECLARATION
+//! }
+//! ```
+
+use crate::{coloring::ColorMapper, fractal::Fractal, kernel::{EscapeResult, OrbitStats}, utils::*};
+use libloading::Library;
+use std::{ffi::CStr, fmt, fs, io, os::raw::c_char, path::Path, sync::Arc};
+
+/// The version of the plugin ABI; plugins
+/// declaring another version are rejected.
+pub const ABI_VERSION: u32 = 2;
+
+/// The name of the function exported by every plugin.
+pub const PLUGIN_SYMBOL: &[u8] = b"mandelbrust_plugin";
+
+/// The result of the iteration of a point (see
+/// `EscapeResult`), in a form that can cross the ABI.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PluginEscapeResult {
+    pub iterations: u64,
+    pub z_re: f64,
+    pub z_im: f64,
+    pub derivative_re: f64,
+    pub derivative_im: f64,
+    pub escaped: bool,
+    pub atom_domain: u64,
+}
+
+impl From<EscapeResult> for PluginEscapeResult {
+    fn from(result: EscapeResult) -> Self {
+        Self {
+            iterations: result.iterations as u64,
+            z_re: result.z.coordinates().0,
+            z_im: result.z.coordinates().1,
+            derivative_re: result.derivative.coordinates().0,
+            derivative_im: result.derivative.coordinates().1,
+            escaped: result.escaped,
+            atom_domain: result.atom_domain as u64,
+        }
+    }
+}
+
+impl From<PluginEscapeResult> for EscapeResult {
+    fn from(result: PluginEscapeResult) -> Self {
+        Self {
+            iterations: result.iterations as usize,
+            z: MandelPoint::new((result.z_re, result.z_im)),
+            derivative: MandelPoint::new((result.derivative_re, result.derivative_im)),
+            escaped: result.escaped,
+            atom_domain: result.atom_domain as usize,
+            // the plugins don't provide them
+            orbit: OrbitStats::default(),
+            c: MANDELPOINT_ZERO,
+            power: 2,
+        }
+    }
+}
+
+/// An RGBA color, in a form that can cross the ABI.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PluginColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// A fractal declared by a plugin.
+#[repr(C)]
+pub struct PluginFractal {
+    /// The name of the fractal, as a nul-terminated UTF-8 string.
+    pub name: *const u8,
+    /// Iterates the point `re + im * i` (see `Fractal::escape()`).
+    pub escape: extern "C" fn(re: f64, im: f64, max_iterations: u64) -> PluginEscapeResult,
+}
+
+/// A coloring declared by a plugin.
+#[repr(C)]
+pub struct PluginColoring {
+    /// The name of the coloring, as a nul-terminated UTF-8 string.
+    pub name: *const u8,
+    /// Returns the RGBA color of a pixel (see `ColorMapper::color()`).
+    pub color: extern "C" fn(result: *const PluginEscapeResult, max_iterations: u64) -> PluginColor,
+}
+
+/// The fractals and the colorings declared by a plugin.
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// The version of the ABI used by the plugin (see `ABI_VERSION`).
+    pub abi_version: u32,
+    pub fractals: *const PluginFractal,
+    pub fractal_count: usize,
+    pub colorings: *const PluginColoring,
+    pub coloring_count: usize,
+}
+
+// the declarations are immutable statics of the plugins
+unsafe impl Sync for PluginFractal {}
+unsafe impl Sync for PluginColoring {}
+unsafe impl Sync for PluginDeclaration {}
+
+/// Reads a name declared by a plugin.
+///
+/// # Safety
+///
+/// `name` must be null or point to a nul-terminated string.
+unsafe fn read_name(name: *const u8) -> io::Result<String> {
+    if name.is_null() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "a plugin declared a null name"));
+    }
+
+    Ok(CStr::from_ptr(name as *const c_char).to_string_lossy().into_owned())
+}
+
+/// Reads an array declared by a plugin.
+///
+/// # Safety
+///
+/// `pointer` must be null or point to `count` values
+/// that outlive the returned slice.
+unsafe fn declared<'a, T>(pointer: *const T, count: usize) -> &'a [T] {
+    if pointer.is_null() || count == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(pointer, count)
+    }
+}
+
+/// A fractal loaded from a plugin, which keeps its library loaded.
+struct ForeignFractal {
+    name: String,
+    escape: extern "C" fn(f64, f64, u64) -> PluginEscapeResult,
+    _library: Arc<Library>,
+}
+
+impl fmt::Debug for ForeignFractal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ForeignFractal").field("name", &self.name).finish()
+    }
+}
+
+impl Fractal for ForeignFractal {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn escape(&self, c: MandelPoint, max_iterations: usize) -> EscapeResult {
+        let (re, im) = c.coordinates();
+
+        EscapeResult { c, ..(self.escape)(re, im, max_iterations as u64).into() }
+    }
+}
+
+/// A coloring loaded from a plugin, which keeps its library loaded.
+struct ForeignColoring {
+    name: String,
+    color: extern "C" fn(*const PluginEscapeResult, u64) -> PluginColor,
+    _library: Arc<Library>,
+}
+
+impl fmt::Debug for ForeignColoring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ForeignColoring").field("name", &self.name).finish()
+    }
+}
+
+impl ColorMapper for ForeignColoring {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn color(&self, result: &EscapeResult, max_iterations: usize) -> [u8; 4] {
+        let result = PluginEscapeResult::from(*result);
+
+        let PluginColor { r, g, b, a } = (self.color)(&result, max_iterations as u64);
+
+        [r, g, b, a]
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// The fractals and the colorings loaded from plugins.
+#[derive(Debug, Default)]
+pub struct Plugins {
+    /// The loaded fractals.
+    pub fractals: Vec<Box<dyn Fractal>>,
+    /// The loaded colorings.
+    pub colorings: Vec<Box<dyn ColorMapper>>,
+}
+
+impl Plugins {
+    /// Loads the plugin at `path`, adding its fractals and colorings.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization code, and
+    /// the plugin must uphold the contract of its declaration
+    /// (see the documentation of the module); there is no
+    /// way to check that a library is a well-behaved plugin.
+    pub unsafe fn load(&mut self, path: &Path) -> io::Result<()> {
+        let library = Library::new(path).map_err(io::Error::other)?;
+
+        let declaration = {
+            let plugin = library
+                .get::<extern "C" fn() -> *const PluginDeclaration>(PLUGIN_SYMBOL)
+                .map_err(io::Error::other)?;
+
+            plugin()
+        };
+
+        let declaration = declaration
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "the plugin returned a null declaration"))?;
+
+        if declaration.abi_version != ABI_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the plugin uses the ABI version {}, but {} is supported", declaration.abi_version, ABI_VERSION),
+            ));
+        }
+
+        let library = Arc::new(library);
+
+        log::info!("loaded the plugin `{}`", path.display());
+
+        for fractal in declared(declaration.fractals, declaration.fractal_count) {
+            self.fractals.push(Box::new(ForeignFractal {
+                name: read_name(fractal.name)?,
+                escape: fractal.escape,
+                _library: Arc::clone(&library),
+            }));
+        }
+
+        for coloring in declared(declaration.colorings, declaration.coloring_count) {
+            self.colorings.push(Box::new(ForeignColoring {
+                name: read_name(coloring.name)?,
+                color: coloring.color,
+                _library: Arc::clone(&library),
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Loads every plugin (every file with the extension of
+    /// the dynamic libraries of the platform) in `dir`,
+    /// returning the errors of the ones that failed to load.
+    ///
+    /// # Safety
+    ///
+    /// See `Plugins::load()`.
+    pub unsafe fn load_dir(&mut self, dir: &Path) -> io::Result<Vec<(String, io::Error)>> {
+        let mut errors = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|extension| extension.to_str()) != Some(std::env::consts::DLL_EXTENSION) {
+                continue;
+            }
+
+            if let Err(err) = self.load(&path) {
+                errors.push((path.display().to_string(), err));
+            }
+        }
+
+        Ok(errors)
+    }
+}
@@ -0,0 +1,365 @@
+use crate::{double_double::DoubleDouble, fixed::{self, Fixed}, fractal::{Fractal, Mandelbrot}, kernel::EscapeResult, perturbation, render, utils::*};
+use rayon::prelude::*;
+use std::{fmt, ops::{Add, Mul, Range, Sub}};
+
+/// How many times larger than the resolution of a backend
+/// (at the center of a view) the pixels must be, for the
+/// backend to draw the view without visible blocks.
+pub const PIXEL_MARGIN: f64 = 16.0;
+
+/// A real number type that the points can be
+/// iterated with (see `escape()`).
+pub trait Real: Copy + Default + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Send + Sync {
+    /// Returns the number closest to `value`.
+    fn from_f32(value: f32) -> Self;
+
+    /// Returns the number closest to `value`.
+    fn from_f64(value: f64) -> Self;
+
+    /// Returns the number closest to `value` (e.g. the
+    /// center of a `Viewport`, see `Viewport::precise_center()`).
+    fn from_fixed(value: Fixed) -> Self;
+
+    /// Returns the `f32` closest to the number.
+    fn to_f32(self) -> f32;
+
+    /// Returns the `f64` closest to the number.
+    fn to_f64(self) -> f64;
+
+    /// Returns the squared norm of `re + im i`, the escape
+    /// test of `escape()`, which must not overflow
+    /// (e.g. saturating at the bounds of `Fixed`).
+    fn norm_sqr(re: Self, im: Self) -> Self {
+        re * re + im * im
+    }
+}
+
+impl Real for f32 {
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn from_fixed(value: Fixed) -> Self {
+        value.to_f32()
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Real for f64 {
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn from_fixed(value: Fixed) -> Self {
+        value.to_f64()
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// Iterates `z = z^2 + c` from `z = 0` like `kernel::escape()`,
+/// but with the point `c` given (and `z` computed) in the
+/// arithmetic of `T`, so that the points closer than the
+/// precision of `f64` are still told apart; the values of the
+/// result are rounded to `f64`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{double_double::DoubleDouble, fixed::Fixed, kernel, precision::{escape, Real}, utils::{Plottable, MandelPoint}};
+/// let c = MandelPoint::new((0.5, 0.5));
+///
+/// let result = escape((Fixed::from_f32(0.5), Fixed::from_f32(0.5)), 128);
+///
+/// assert_eq!(result.iterations, kernel::escape(c, 128).iterations);
+/// assert_eq!(escape((0.5f64, 0.5f64), 128).iterations, result.iterations);
+/// assert!(result.escaped);
+/// assert_eq!(escape((DoubleDouble::from(0.5), DoubleDouble::from(0.5)), 128), result);
+/// assert!(!escape((Fixed::from_f32(-1.0), Fixed::default()), 128).escaped);
+///
+/// // `|z|^2 = 144` is out of the range of `Fixed`, but it still escapes
+/// assert_eq!(escape((Fixed::from_f32(12.0), Fixed::default()), 128).iterations, 1);
+/// ```
+pub fn escape<T: Real>(c: (T, T), max_iterations: usize) -> EscapeResult {
+    let four = T::from_f32(4.0);
+
+    let (c_re, c_im) = c;
+    let (mut re, mut im) = (T::default(), T::default());
+
+    let mandel_c = MandelPoint::new((c_re.to_f64(), c_im.to_f64()));
+
+    let mut result = EscapeResult { c: mandel_c, ..EscapeResult::default() };
+    let mut min_norm_sqr = f64::INFINITY;
+
+    while !result.escaped && result.iterations <= max_iterations {
+        // the derivative only needs the precision of `f64`
+        let z = MandelPoint::new((re.to_f64(), im.to_f64()));
+
+        result.derivative = (z * 2.0).complex_mul(result.derivative) + MandelPoint::new((1.0, 0.0));
+
+        let (re_sqr, im_sqr) = (re * re, im * im);
+
+        im = (re + re) * im + c_im;
+        re = re_sqr - im_sqr + c_re;
+
+        let norm_sqr = T::norm_sqr(re, im);
+
+        result.iterations += 1;
+
+        if norm_sqr > four {
+            result.escaped = true;
+        } else {
+            let norm_sqr = norm_sqr.to_f64();
+
+            result.orbit.push(norm_sqr.sqrt() as f32);
+
+            if norm_sqr < min_norm_sqr {
+                min_norm_sqr = norm_sqr;
+                result.atom_domain = result.iterations;
+            }
+        }
+    }
+
+    result.z = MandelPoint::new((re.to_f64(), im.to_f64()));
+
+    result
+}
+
+/// Iterates the Mandelbrot set on every pixel of the `rows` of a
+/// screen of the given `size` showing `viewport` (see `escape()`),
+/// with the points offset from the (`Fixed`) center in the arithmetic
+/// of `T` (see `Viewport::plane_point()`), so that the pixels smaller
+/// than the precision of the center still differ.
+fn escape_rows<T: Real>(viewport: &Viewport, size: (usize, usize), rows: Range<usize>, max_iterations: usize) -> Vec<EscapeResult> {
+    let w = size.0;
+
+    (rows.start * w..rows.end * w)
+        .into_par_iter()
+        .map(|idx| {
+            let pixel = viewport.orient(Point::new((idx % w, idx / w)), size);
+
+            escape(viewport.plane_point::<T>(pixel, size), max_iterations)
+        })
+        .collect()
+}
+
+/// The arithmetic the points of a view are iterated with.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Precision {
+    /// The `f32` arithmetic, which can't tell apart the
+    /// pixels of the moderate zooms already, and only
+    /// iterates the Mandelbrot set.
+    Float,
+    /// The `f64` arithmetic of the fractal itself,
+    /// which is the fastest one every fractal
+    /// supports, but can't tell apart the pixels
+    /// of the deepest zooms.
+    #[default]
+    Double,
+    /// The double-double arithmetic of `DoubleDouble`,
+    /// which only iterates the Mandelbrot set.
+    DoubleDouble,
+    /// The 128-bit fixed-point arithmetic of `Fixed`,
+    /// which only iterates the Mandelbrot set.
+    Fixed,
+    /// The perturbation of a reference orbit iterated with
+    /// `Fixed` (see `perturbation::escape_rows()`), which is
+    /// as precise, but far faster, and only iterates the
+    /// Mandelbrot set.
+    Perturbation,
+}
+
+impl Precision {
+    /// The backends, from the least to the most precise,
+    /// in the order they are cycled through in the window.
+    pub const ALL: [Precision; 5] = [Precision::Float, Precision::Double, Precision::DoubleDouble, Precision::Fixed, Precision::Perturbation];
+
+    /// Returns the next backend, in the order of `ALL`.
+    pub fn next(self) -> Option<Self> {
+        let idx = Self::ALL.iter().position(|&precision| precision == self)?;
+
+        Self::ALL.get(idx + 1).copied()
+    }
+
+    /// Returns the smallest difference between the numbers
+    /// around `1.0` that the backend can tell apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::precision::Precision;
+    /// assert_eq!(Precision::Float.epsilon(), f32::EPSILON as f64);
+    /// assert!(Precision::ALL.windows(2).all(|pair| pair[0].epsilon() >= pair[1].epsilon()));
+    /// ```
+    pub fn epsilon(&self) -> f64 {
+        match self {
+            Precision::Float => f32::EPSILON as f64,
+            Precision::Double => f64::EPSILON,
+            // the mantissas of the two `f64` are 106 bits
+            Precision::DoubleDouble => (-105.0f64).exp2(),
+            // the differences from the reference orbit are far smaller than it
+            Precision::Fixed | Precision::Perturbation => (-(fixed::FRACTION_BITS as f64)).exp2(),
+        }
+    }
+
+    /// Returns the least precise backend (which is the fastest)
+    /// that can tell apart the pixels of a screen of the given
+    /// `size` showing `viewport`, that is, whose resolution at
+    /// the center of the view is at least `PIXEL_MARGIN` times
+    /// smaller than the pixels, or the most precise one, if
+    /// none can; `Float` is never chosen, since it's hardly
+    /// faster than `Double`, and neither is `Fixed`, since
+    /// `Perturbation` is as precise, but faster.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{precision::Precision, utils::{Plottable, MandelPoint, Viewport}};
+    /// let center = MandelPoint::new((-0.75, 0.1));
+    ///
+    /// assert_eq!(Precision::required(&Viewport::default(), (300, 200)), Precision::Double);
+    /// assert_eq!(Precision::required(&Viewport::new(center, 1e6, 0.0), (300, 200)), Precision::Double);
+    /// assert_eq!(Precision::required(&Viewport::new(center, 1e18, 0.0), (300, 200)), Precision::DoubleDouble);
+    /// assert_eq!(Precision::required(&Viewport::new(center, 1e30, 0.0), (300, 200)), Precision::Perturbation);
+    /// ```
+    pub fn required(viewport: &Viewport, size: (usize, usize)) -> Self {
+        // the orbits reach the magnitude of `c`, and at least `1`
+        let (re, im) = viewport.center().coordinates();
+        let magnitude = re.hypot(im).max(1.0);
+
+        let pixel_size = viewport.pixel_size(size);
+
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|&precision| precision != Precision::Float && precision != Precision::Fixed)
+            .find(|precision| precision.epsilon() * magnitude * PIXEL_MARGIN <= pixel_size)
+            .unwrap_or(Precision::Perturbation)
+    }
+
+    /// Returns whether the backend can iterate `fractal`,
+    /// which all but `Double` can only do for the
+    /// Mandelbrot set.
+    pub fn supports(&self, fractal: &dyn Fractal) -> bool {
+        *self == Precision::Double || fractal.name() == Mandelbrot.name()
+    }
+
+    /// Returns the backend with the given `name`, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::precision::Precision;
+    /// assert_eq!(Precision::from_name("fixed"), Some(Precision::Fixed));
+    /// assert_eq!(Precision::from_name("bigfloat"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|precision| precision.to_string() == name)
+    }
+
+    /// Iterates `fractal` on every pixel of a screen of the given
+    /// `size` like `render::escape_results()`, but with the arithmetic
+    /// of the backend; the points of the more precise backends are
+    /// offset from the center of `viewport` in their arithmetic, so
+    /// that the pixels smaller than the precision of the center still
+    /// differ; the fractals the backend doesn't support (see
+    /// `supports()`) are iterated with `Double`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{fractal::Mandelbrot, kernel::EscapeResult, precision::Precision, render::escape_results, utils::{Plottable, MandelPoint, Viewport}};
+    /// let results = Precision::Fixed.escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128);
+    /// let floats = escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128);
+    ///
+    /// assert_eq!(results.len(), 30 * 20);
+    ///
+    /// let doubles = Precision::DoubleDouble.escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128);
+    ///
+    /// assert!(doubles.iter().zip(&results).all(|(double, result)| double.iterations == result.iterations));
+    /// assert_eq!(results.iter().filter(|result| result.escaped).count(), floats.iter().filter(|result| result.escaped).count());
+    ///
+    /// // two centers a single `f32` apart are two different views
+    /// let re = -0.743643887f32;
+    /// let next = f32::from_bits(re.to_bits() + 1);
+    ///
+    /// let view = Viewport::new(MandelPoint::new((re as f64, 0.131825904)), 1e9, 0.0);
+    /// let next_view = Viewport::new(MandelPoint::new((next as f64, 0.131825904)), 1e9, 0.0);
+    ///
+    /// let iterations = |results: Vec<EscapeResult>| results.iter().map(|result| result.iterations).collect::<Vec<_>>();
+    ///
+    /// for precision in [Precision::Double, Precision::DoubleDouble, Precision::Fixed] {
+    ///     let view_iterations = iterations(precision.escape_results(&Mandelbrot, &view, (30, 20), 2000));
+    ///
+    ///     assert_ne!(view_iterations, iterations(precision.escape_results(&Mandelbrot, &next_view, (30, 20), 2000)));
+    ///
+    ///     // and so are the pixels of each view
+    ///     assert!(view_iterations.iter().any(|&pixel| pixel != view_iterations[0]));
+    /// }
+    /// ```
+    pub fn escape_results(&self, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize) -> Vec<EscapeResult> {
+        let mut results = self.escape_rows(fractal, viewport, size, render::distinct_rows(fractal, viewport, size), max_iterations);
+
+        render::mirror_rows(&mut results, size);
+
+        results
+    }
+
+    /// Iterates `fractal` like `escape_results()`, but only
+    /// on the pixels of the given `rows` of the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{fractal::Mandelbrot, precision::Precision, utils::Viewport};
+    /// let results = Precision::Fixed.escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128);
+    /// let rows = Precision::Fixed.escape_rows(&Mandelbrot, &Viewport::default(), (30, 20), 5..8, 128);
+    ///
+    /// assert_eq!(rows, results[5 * 30..8 * 30]);
+    /// ```
+    pub fn escape_rows(&self, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), rows: Range<usize>, max_iterations: usize) -> Vec<EscapeResult> {
+        let w = size.0;
+
+        match self {
+            Precision::Double => render::par_pixels(fractal, viewport, size, max_iterations).skip(rows.start * w).take(rows.len() * w).map(|(_, _, result)| result).collect(),
+            _ if !self.supports(fractal) => Precision::Double.escape_rows(fractal, viewport, size, rows, max_iterations),
+            Precision::Float => escape_rows::<f32>(viewport, size, rows, max_iterations),
+            Precision::DoubleDouble => escape_rows::<DoubleDouble>(viewport, size, rows, max_iterations),
+            Precision::Fixed => escape_rows::<Fixed>(viewport, size, rows, max_iterations),
+            Precision::Perturbation => perturbation::escape_rows(viewport, size, rows, max_iterations),
+        }
+    }
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Precision::Float => write!(f, "float"),
+            Precision::Double => write!(f, "double"),
+            Precision::DoubleDouble => write!(f, "double-double"),
+            Precision::Fixed => write!(f, "fixed"),
+            Precision::Perturbation => write!(f, "perturbation"),
+        }
+    }
+}
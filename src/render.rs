@@ -0,0 +1,924 @@
+use crate::{coloring::{self, ColorMapper}, fractal::{Fractal, Quadratic}, kernel::{EscapeResult, EscapeState}, stats::{self, RenderStats}, utils::*};
+use rayon::prelude::*;
+use memmap2::MmapMut;
+use std::{convert::TryInto, fs::OpenOptions, io::{self, Read, Write}, ops::Range, path::Path, sync::mpsc, thread, time::Instant};
+
+/// The largest number of samples of each pixel of `jittered()`.
+pub const MAX_SAMPLES: usize = 256;
+
+/// The zoom of the views of `julia_preview()`, which
+/// show the whole Julia sets of the Mandelbrot set.
+pub const JULIA_ZOOM: f32 = 0.6;
+
+/// The keyword of the PNG text chunk where
+/// `write_png_with_view()` stores the view.
+pub const VIEWPORT_KEYWORD: &str = "MandelbRust viewport";
+
+/// The keyword of the PNG text chunk where
+/// `write_png_with_view()` stores the iteration cap.
+pub const ITERATIONS_KEYWORD: &str = "MandelbRust iterations";
+
+/// The number of rows iterated together by `render_pam()`,
+/// which bounds the memory it needs besides the file.
+pub const PAM_BAND_HEIGHT: usize = 16;
+
+/// Iterates `fractal` on every pixel of a screen of the given
+/// `size` showing the area of the plane seen through `viewport`,
+/// in parallel, yielding each pixel (counted from the top left
+/// corner) together with its point and its result, row by row
+/// from top to bottom, so that they can be consumed as they are
+/// computed, without a buffer of the whole screen.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::par_pixels, utils::{Plottable, Point, Viewport}};
+/// # use rayon::prelude::*;
+/// let inside = par_pixels(&Mandelbrot, &Viewport::default(), (300, 200), 128)
+///     .filter(|(_, _, result)| !result.escaped)
+///     .count();
+///
+/// assert!(inside > 0 && inside < 300 * 200);
+///
+/// let (pixel, mandelpoint, _) = par_pixels(&Mandelbrot, &Viewport::default(), (300, 200), 128).find_first(|_| true).unwrap();
+///
+/// assert_eq!(pixel, Point::new((0, 0)));
+/// assert_eq!(mandelpoint, Viewport::default().orient(pixel, (300, 200)).to_mandel(&Viewport::default(), (300, 200)));
+/// ```
+pub fn par_pixels<'a>(fractal: &'a dyn Fractal, viewport: &'a Viewport, size: (usize, usize), max_iterations: usize) -> impl IndexedParallelIterator<Item = (Point, MandelPoint, EscapeResult)> + 'a {
+    let (w, h) = size;
+
+    (0..w * h).into_par_iter().map(move |idx| {
+        let pixel = Point::new((idx % w, idx / w));
+
+        // orient the y coordinate, like the cursor,
+        // otherwise the rotated fractal would not match
+        // the red line
+        let mandelpoint = viewport.orient(pixel, size).to_mandel(viewport, size);
+
+        (pixel, mandelpoint, fractal.escape(mandelpoint, max_iterations))
+    })
+}
+
+/// Iterates `fractal` on every pixel of a screen of the given
+/// `size` showing the area of the plane seen through `viewport`,
+/// returning the results row by row, from top to bottom
+/// (see `par_pixels()`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::escape_results, utils::Viewport};
+/// let results = escape_results(&Mandelbrot, &Viewport::default(), (300, 200), 128);
+///
+/// assert_eq!(results.len(), 300 * 200);
+/// assert!(!results[100 * 300 + 150].escaped); // the center is inside the set
+/// assert!(results[0].escaped);
+/// ```
+pub fn escape_results(fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize) -> Vec<EscapeResult> {
+    let rows = distinct_rows(fractal, viewport, size);
+
+    let mut results = par_pixels(fractal, viewport, size, max_iterations).take(rows.end * size.0).map(|(_, _, result)| result).collect();
+
+    mirror_rows(&mut results, size);
+
+    results
+}
+
+/// Returns the rows of a screen of the given `size` showing
+/// `viewport` that have to be iterated for `fractal`: when both
+/// are symmetric about the real axis (see `Fractal::is_symmetric()`
+/// and `Viewport::is_symmetric()`), the rows below the axis mirror
+/// the ones above it, which are the only ones returned (the axis
+/// included), while all of them are returned otherwise.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::distinct_rows, utils::{Plottable, MandelPoint, Viewport}};
+/// assert_eq!(distinct_rows(&Mandelbrot, &Viewport::default(), (300, 200)), 0..101);
+/// assert_eq!(distinct_rows(&Mandelbrot, &Viewport::new(MandelPoint::new((-0.75, 0.1)), 20.0, 0.0), (300, 200)), 0..200);
+/// ```
+pub fn distinct_rows(fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize)) -> Range<usize> {
+    if fractal.is_symmetric() && viewport.is_symmetric() {
+        // the row `y` mirrors the row `size.1 - y`
+        0..(size.1 / 2 + 1).min(size.1)
+    } else {
+        0..size.1
+    }
+}
+
+/// Completes the `results` of the rows returned by `distinct_rows()`
+/// with the rows that mirror them, conjugating their values, so that
+/// they cover the whole screen of the given `size`.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::{distinct_rows, mirror_rows, par_pixels}, utils::Viewport};
+/// # use rayon::prelude::*;
+/// let (viewport, size) = (Viewport::default(), (30, 20));
+///
+/// let rows = distinct_rows(&Mandelbrot, &viewport, size);
+///
+/// let mut results: Vec<_> = par_pixels(&Mandelbrot, &viewport, size, 128).take(rows.end * 30).map(|(_, _, result)| result).collect();
+///
+/// mirror_rows(&mut results, size);
+///
+/// let all: Vec<_> = par_pixels(&Mandelbrot, &viewport, size, 128).map(|(_, _, result)| result).collect();
+///
+/// let values = |results: &[mandelbrust::kernel::EscapeResult]| results.iter().map(|result| (result.iterations, result.z)).collect::<Vec<_>>();
+///
+/// assert_eq!(values(&results), values(&all));
+/// ```
+pub fn mirror_rows(results: &mut Vec<EscapeResult>, size: (usize, usize)) {
+    let (w, h) = size;
+
+    // an empty screen has nothing to mirror
+    for y in results.len().checked_div(w).unwrap_or(h)..h {
+        let mirrored = (h - y) * w;
+
+        for x in 0..w {
+            let result = results[mirrored + x];
+
+            results.push(EscapeResult { z: result.z.conj(), derivative: result.derivative.conj(), c: result.c.conj(), ..result });
+        }
+    }
+}
+
+/// Iterates `fractal` on the exponential map (the log-polar
+/// projection) of the view seen through `viewport`, on a screen
+/// of the given `size`: the columns go around the center of the
+/// view (a full turn, counterclockwise, from left to right), while
+/// the rows go from the edge of the view (at the top) towards its
+/// center, zooming in by `exp(2π / width)` at each row, so that
+/// the pixels stay square and a zoom into the center becomes a
+/// vertical translation (which makes the map the standard
+/// intermediate of zoom videos).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::exponential_map, utils::{Plottable, Viewport}};
+/// let results = exponential_map(&Mandelbrot, &Viewport::default(), (64, 200), 128);
+///
+/// assert_eq!(results.len(), 64 * 200);
+///
+/// // the top left pixel is on the edge of the view, to the right of its center
+/// let (re, im) = results[0].c.coordinates();
+///
+/// assert!((re - 0.5).abs() < 1e-6 && im.abs() < 1e-6);
+/// // the bottom rows are deep inside the main cardioid
+/// assert!(!results[199 * 64].escaped);
+/// ```
+pub fn exponential_map(fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize) -> Vec<EscapeResult> {
+    let (w, h) = size;
+
+    // half the height of the view
+    let radius = viewport.pixel_size((1, 1)) / 2.0;
+    let step = std::f64::consts::TAU / w.max(1) as f64;
+
+    (0..w * h)
+        .into_par_iter()
+        .map(|idx| {
+            let (x, y) = (idx % w, idx / w);
+
+            let mandelpoint = viewport.center() + MandelPoint::from_polar(radius * (-step * y as f64).exp(), step * x as f64 + viewport.rotation() as f64);
+
+            fractal.escape(mandelpoint, max_iterations)
+        })
+        .collect()
+}
+
+/// A rectangle of pixels of a screen.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Region {
+    /// The top left pixel of the rectangle.
+    pub origin: (usize, usize),
+    /// The size (in pixels) of the rectangle.
+    pub size: (usize, usize),
+}
+
+/// Renders a view like `render_rgba()` (without the boundary-only
+/// mode), but in bands of `band_height` rows, calling `callback` on
+/// the thread of the caller with the region and the RGBA pixels of
+/// each band as soon as it's done, so that it can be consumed (e.g.
+/// sent or shown) while the others are still being iterated; the
+/// bands aren't done in order.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::WikipediaPalette, fractal::Mandelbrot, render::{render_rgba, render_streaming}, utils::Viewport};
+/// let mut rgba = vec![0; 300 * 200 * 4];
+/// let mut bands = 0;
+///
+/// render_streaming(&Mandelbrot, &Viewport::default(), (300, 200), 128, &WikipediaPalette::default(), 16, |region, data| {
+///     let start = region.origin.1 * 300 * 4;
+///
+///     rgba[start..start + data.len()].copy_from_slice(data);
+///     bands += 1;
+/// }).unwrap();
+///
+/// assert_eq!(bands, 13);
+/// assert_eq!(rgba, render_rgba(&Mandelbrot, &Viewport::default(), (300, 200), 128, &WikipediaPalette::default(), None).unwrap());
+/// ```
+pub fn render_streaming<F: FnMut(Region, &[u8])>(fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize, mapper: &dyn ColorMapper, band_height: usize, mut callback: F) -> Result<(), ViewportError> {
+    viewport.check(size)?;
+
+    let start = Instant::now();
+
+    let (w, h) = size;
+    let band_height = band_height.max(1);
+
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            (0..h.div_ceil(band_height)).into_par_iter().for_each_with(sender, |sender, band| {
+                let top = band * band_height;
+                let rows = band_height.min(h - top);
+
+                let results: Vec<EscapeResult> = par_pixels(fractal, viewport, size, max_iterations).skip(top * w).take(rows * w).map(|(_, _, result)| result).collect();
+
+                // the receiver only hangs up if the callback panics
+                let _ = sender.send((Region { origin: (0, top), size: (w, rows) }, colorize(&results, mapper, max_iterations)));
+            });
+        });
+
+        for (region, rgba) in receiver {
+            callback(region, &rgba);
+        }
+    });
+
+    log::debug!("streamed {}x{} pixels of `{}` at {} in {:?}", w, h, fractal.name(), viewport, start.elapsed());
+
+    stats::record(&RenderStats::new(size, viewport, max_iterations, fractal.name(), start.elapsed()));
+
+    Ok(())
+}
+
+/// The iteration of every pixel of a screen showing a view
+/// of the Mandelbrot set (or of another member of its family,
+/// see `Seed`), which can be resumed with a larger iteration
+/// cap, so that an animation that raises the cap a little at
+/// a time only computes the new iterations at each frame.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::{escape_results, Sweep}, utils::{Seed, Viewport}};
+/// let mut sweep = Sweep::new(&Viewport::default(), (30, 20), Seed::default());
+///
+/// sweep.advance(8);
+/// sweep.advance(64);
+///
+/// assert_eq!(sweep.results(), escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 64));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+    viewport: Viewport,
+    states: Vec<EscapeState>,
+}
+
+impl Sweep {
+    /// Returns the iteration of every pixel of a screen with
+    /// the given `size`, showing `viewport`, before the first
+    /// iteration; each pixel enters it according to `seed`.
+    pub fn new(viewport: &Viewport, size: (usize, usize), seed: Seed) -> Self {
+        let (w, h) = size;
+
+        let states = (0..w * h)
+            .into_par_iter()
+            .map(|idx| {
+                let pixel = viewport.orient(Point::new((idx % w, idx / w)), size);
+
+                let (z0, c) = seed.split(pixel.to_mandel(viewport, size));
+
+                EscapeState::new(z0, c)
+            })
+            .collect();
+
+        Self { viewport: *viewport, states }
+    }
+
+    /// Returns the view shown by the pixels.
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// Iterates every pixel until `max_iterations + 1`
+    /// iterations are done in total (see `EscapeState::advance()`).
+    pub fn advance(&mut self, max_iterations: usize) {
+        self.states.par_iter_mut().for_each(|state| state.advance(max_iterations));
+    }
+
+    /// Returns the results of the iterations done so far,
+    /// row by row, from top to bottom.
+    pub fn results(&self) -> Vec<EscapeResult> {
+        self.states.par_iter().map(EscapeState::result).collect()
+    }
+}
+
+/// Colors the given `results` with `mapper`,
+/// returning an RGBA buffer.
+pub fn colorize(results: &[EscapeResult], mapper: &dyn ColorMapper, max_iterations: usize) -> Vec<u8> {
+    let mut rgba = vec![0; results.len() * 4];
+
+    rgba.par_chunks_mut(4).zip(results.par_iter()).for_each(|(chunks_pixel, pixel_result)| {
+        chunks_pixel.copy_from_slice(&mapper.color(pixel_result, max_iterations));
+    });
+
+    rgba
+}
+
+/// The results of every pixel of a view, with their smooth
+/// iteration counts (see `EscapeResult::smooth_iterations()`),
+/// kept apart from any coloring, so that the view can be
+/// colored again without iterating it again.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::{Grayscale, WikipediaPalette}, fractal::Mandelbrot, render::{self, IterationBuffer}, utils::Viewport};
+/// let buffer = IterationBuffer::new(render::escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128), 128);
+///
+/// assert_eq!(buffer.smooth().len(), 30 * 20);
+/// assert_eq!(buffer.smooth()[10 * 30 + 15], 129.0); // the center never escapes
+/// assert_eq!(buffer.colorize(&Grayscale), render::colorize(buffer.results(), &Grayscale, 128));
+/// assert_ne!(buffer.colorize(&Grayscale), buffer.colorize(&WikipediaPalette::default()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IterationBuffer {
+    results: Vec<EscapeResult>,
+    smooth: Vec<f32>,
+    max_iterations: usize,
+}
+
+impl IterationBuffer {
+    /// Keeps the `results` of a view iterated up to `max_iterations`.
+    pub fn new(results: Vec<EscapeResult>, max_iterations: usize) -> Self {
+        let smooth = results.par_iter().map(EscapeResult::smooth_iterations).collect();
+
+        Self { results, smooth, max_iterations }
+    }
+
+    /// Returns the results of the pixels.
+    pub fn results(&self) -> &[EscapeResult] {
+        &self.results
+    }
+
+    /// Returns the smooth iteration counts of the pixels.
+    pub fn smooth(&self) -> &[f32] {
+        &self.smooth
+    }
+
+    /// Returns the iteration cap of the view.
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// Colors the pixels with `mapper` (see `colorize()`).
+    pub fn colorize(&self, mapper: &dyn ColorMapper) -> Vec<u8> {
+        colorize(&self.results, mapper, self.max_iterations)
+    }
+}
+
+/// Renders the Julia set of the parameter `c`, centered
+/// at the origin, as an RGBA buffer of the given (usually
+/// small) `size`, colored with `mapper`, without recording
+/// its statistics, so that it can follow the cursor.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::WikipediaPalette, render::julia_preview, utils::{Plottable, MandelPoint}};
+/// // the Julia set of `0` is the unit disk
+/// let rgba = julia_preview(MandelPoint::new((0.0, 0.0)), (30, 20), 100, &WikipediaPalette::default());
+///
+/// assert_eq!(rgba.len(), 30 * 20 * 4);
+/// assert_ne!(rgba[..4], rgba[(10 * 30 + 15) * 4..][..4]);
+/// ```
+pub fn julia_preview(c: MandelPoint, size: (usize, usize), max_iterations: usize, mapper: &dyn ColorMapper) -> Vec<u8> {
+    let julia = Quadratic { seed: Seed::Start { c } };
+
+    colorize(&escape_results(&julia, &Viewport::new(MANDELPOINT_ZERO, JULIA_ZOOM, 0.0), size, max_iterations), mapper, max_iterations)
+}
+
+/// Renders `fractal` in the area of the plane seen through
+/// `viewport` as an RGBA buffer of the given `size`,
+/// colored with `mapper`, keeping only the pixels within
+/// `boundary` pixels from the boundary, if given (see
+/// `keep_boundary()`), failing if the `viewport` can't
+/// be shown with that `size` (see `Viewport::check()`);
+/// its statistics are recorded (see `stats::record()`).
+pub fn render_rgba(fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize, mapper: &dyn ColorMapper, boundary: Option<f32>) -> Result<Vec<u8>, ViewportError> {
+    viewport.check(size)?;
+
+    let start = Instant::now();
+
+    let results = escape_results(fractal, viewport, size, max_iterations);
+
+    let mut rgba = colorize(&results, mapper, max_iterations);
+
+    if let Some(width) = boundary {
+        keep_boundary(&mut rgba, &results, viewport.pixel_size(size), width);
+    }
+
+    log::debug!("rendered {}x{} pixels of `{}` at {} in {:?}", size.0, size.1, fractal.name(), viewport, start.elapsed());
+
+    stats::record(&RenderStats::new(size, viewport, max_iterations, fractal.name(), start.elapsed()));
+
+    Ok(rgba)
+}
+
+/// Returns the offset (in pixels, between `-0.5` and `0.5` on
+/// both axes) of the given `sample` of the pixel at `(x, y)`:
+/// the samples of each pixel follow the R2 low-discrepancy
+/// sequence, which spreads them evenly like blue noise,
+/// shifted by a random amount for each pixel, so that
+/// neighbouring pixels don't share the same pattern (which
+/// would produce the grid artifacts of uniform supersampling).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::render::jitter;
+/// let (x, y) = jitter((10, 20), 3);
+///
+/// assert!((-0.5..0.5).contains(&x) && (-0.5..0.5).contains(&y));
+/// assert_eq!(jitter((10, 20), 3), (x, y));
+/// assert_ne!(jitter((11, 20), 3), (x, y));
+/// ```
+pub fn jitter(pixel: (usize, usize), sample: usize) -> (f32, f32) {
+    // the generalization of the golden ratio to two dimensions
+    const ALPHA: (f64, f64) = (0.754_877_666_246_692_7, 0.569_840_290_998_053_3);
+
+    // a well-mixing integer hash, scaled to `[0, 1)`
+    let hash = |mut h: u32| {
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x7feb_352d);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x846c_a68b);
+        h ^= h >> 16;
+
+        h as f64 / (u32::MAX as f64 + 1.0)
+    };
+
+    let key = (pixel.0 as u32).wrapping_mul(73_856_093) ^ (pixel.1 as u32).wrapping_mul(19_349_663);
+
+    let x = (hash(key) + sample as f64 * ALPHA.0).fract();
+    let y = (hash(key ^ 0x9e37_79b9) + sample as f64 * ALPHA.1).fract();
+
+    (x as f32 - 0.5, y as f32 - 0.5)
+}
+
+/// Renders `fractal` like `render_rgba()` (without checking the
+/// `viewport`), averaging the colors of `samples` points (up to
+/// `MAX_SAMPLES`) of each pixel, at the positions given by
+/// `jitter()`, which smooths the edges of the details.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::WikipediaPalette, fractal::Mandelbrot, render::jittered, utils::Viewport};
+/// let rgba = jittered(&Mandelbrot, &Viewport::default(), (30, 20), 128, &WikipediaPalette::default(), 4);
+///
+/// assert_eq!(rgba.len(), 30 * 20 * 4);
+/// ```
+pub fn jittered(fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize, mapper: &dyn ColorMapper, samples: usize) -> Vec<u8> {
+    let w = size.0;
+
+    // the step between two pixels, on the plane, in
+    // each direction of the screen (whose y axis points
+    // down, unlike the one of the plane, by default)
+    let flip = match viewport.orientation() {
+        Orientation::Math => -1.0,
+        Orientation::Screen => 1.0,
+    };
+
+    let turn = MandelPoint::from_polar(viewport.pixel_size(size), viewport.rotation() as f64);
+    let step = (turn, MandelPoint::new((0.0, flip)).complex_mul(turn));
+
+    let mut accumulator = Accumulator::new(size);
+
+    for sample in 0..samples.min(MAX_SAMPLES) {
+        let rgba: Vec<u8> = (0..size.0 * size.1)
+            .into_par_iter()
+            .flat_map_iter(|idx| {
+                let pixel = (idx % w, idx / w);
+
+                let center = viewport.orient(Point::new(pixel), size).to_mandel(viewport, size);
+                let (dx, dy) = jitter(pixel, sample);
+
+                mapper.color(&fractal.escape(center + step.0 * dx as f64 + step.1 * dy as f64, max_iterations), max_iterations)
+            })
+            .collect();
+
+        accumulator.add(&rgba);
+    }
+
+    accumulator.rgba()
+}
+
+/// The sum of the colors of the samples of every pixel of a
+/// screen (e.g. the passes of a `worker::Job`, see its
+/// `samples`), to which more samples can be added over time
+/// (e.g. while the view doesn't change), so that the average
+/// color of each pixel converges to the exact one.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::render::Accumulator;
+/// let mut accumulator = Accumulator::new((2, 1));
+///
+/// accumulator.add(&[0, 0, 0, 255, 100, 100, 100, 255]);
+/// accumulator.add(&[255, 0, 0, 255, 200, 100, 0, 255]);
+///
+/// assert_eq!(accumulator.samples(), 2);
+/// assert_eq!(accumulator.rgba(), [128, 0, 0, 255, 150, 100, 50, 255]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accumulator {
+    sums: Vec<[f32; 4]>,
+    samples: usize,
+}
+
+impl Accumulator {
+    /// Returns an accumulator with no samples,
+    /// for a screen of the given `size`.
+    pub fn new(size: (usize, usize)) -> Self {
+        Self { sums: vec![[0.0; 4]; size.0 * size.1], samples: 0 }
+    }
+
+    /// Returns the number of samples of each pixel.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Adds a sample to each pixel, given as an RGBA buffer
+    /// of the size of the screen, unless there are already
+    /// `MAX_SAMPLES` of them.
+    pub fn add(&mut self, rgba: &[u8]) {
+        if self.samples >= MAX_SAMPLES {
+            return;
+        }
+
+        self.sums.par_iter_mut().zip(rgba.par_chunks(4)).for_each(|(sum, color)| sum.iter_mut().zip(color).for_each(|(total, &ch)| *total += ch as f32));
+
+        self.samples += 1;
+    }
+
+    /// Returns the average color of each pixel, as
+    /// an RGBA buffer (black if there are no samples).
+    pub fn rgba(&self) -> Vec<u8> {
+        let samples = self.samples.max(1) as f32;
+
+        self.sums.par_iter().flat_map_iter(|sum| sum.map(|total| (total / samples).round() as u8)).collect()
+    }
+}
+
+/// Makes transparent (and black) the pixels of `rgba` that are
+/// farther than `width` pixels, each one with a side of
+/// `pixel_size`, from the boundary of the set, according to
+/// the distance estimate of their `results` (see
+/// `EscapeResult::distance_estimate()`); the points that
+/// didn't escape are hidden too, leaving only the boundary.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{render::keep_boundary, kernel::escape, utils::{Plottable, MandelPoint}};
+/// let results = [
+///     escape(MandelPoint::new((0.26, 0.0)), 1024), // close to the cusp
+///     escape(MandelPoint::new((1.0, 1.0)), 1024),
+///     escape(MandelPoint::new((0.0, 0.0)), 1024),
+/// ];
+///
+/// let mut rgba = vec![255; 3 * 4];
+///
+/// keep_boundary(&mut rgba, &results, 0.01, 2.0);
+///
+/// assert_eq!(rgba, [255, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0]);
+/// ```
+pub fn keep_boundary(rgba: &mut [u8], results: &[EscapeResult], pixel_size: f64, width: f32) {
+    let distance = pixel_size * width as f64;
+
+    rgba.par_chunks_mut(4).zip(results.par_iter()).for_each(|(chunks_pixel, pixel_result)| {
+        let near = pixel_result.distance_estimate().is_some_and(|estimate| estimate <= distance);
+
+        if !near {
+            chunks_pixel.copy_from_slice(&[0, 0, 0, 0]);
+        }
+    });
+}
+
+/// Returns a heatmap (see `coloring::heat()`) of the per-pixel
+/// difference between the smooth iteration counts of two
+/// renders of the same view, on a logarithmic scale relative
+/// to the largest difference, as an RGBA buffer; the pixels
+/// where the renders agree are black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::{difference, escape_results}, utils::Viewport};
+/// let low = escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 16);
+/// let high = escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 256);
+///
+/// assert!(difference(&low, &low).chunks(4).all(|pixel| pixel == [0, 0, 0, 255]));
+/// // the points that escape in more than 16 iterations differ
+/// assert!(difference(&low, &high).chunks(4).any(|pixel| pixel == [255, 255, 255, 255]));
+/// ```
+pub fn difference(results: &[EscapeResult], other: &[EscapeResult]) -> Vec<u8> {
+    let differences: Vec<f32> = results
+        .par_iter()
+        .zip(other.par_iter())
+        .map(|(result, other_result)| (result.smooth_iterations() - other_result.smooth_iterations()).abs().ln_1p())
+        .collect();
+
+    let largest = differences.iter().copied().fold(0.0, f32::max);
+
+    let mut rgba = vec![0; differences.len() * 4];
+
+    rgba.par_chunks_mut(4).zip(differences.par_iter()).for_each(|(chunks_pixel, &difference)| {
+        let t = if largest > 0.0 { difference / largest } else { 0.0 };
+
+        chunks_pixel.copy_from_slice(&coloring::heat(t));
+    });
+
+    rgba
+}
+
+/// Replaces the columns of `rgba`, a buffer of the given
+/// `size`, from `divider` onwards with the ones of `other`
+/// (which has the same size), to compare two renders
+/// side by side.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::render::splice;
+/// let mut rgba = vec![0; 3 * 2 * 4];
+///
+/// splice(&mut rgba, &[1; 3 * 2 * 4], (3, 2), 2);
+///
+/// assert_eq!(rgba, [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1]);
+/// ```
+pub fn splice(rgba: &mut [u8], other: &[u8], size: (usize, usize), divider: usize) {
+    let offset = divider.min(size.0) * 4;
+    let row_len = (size.0 * 4).max(1);
+
+    rgba.par_chunks_mut(row_len).zip(other.par_chunks(row_len)).for_each(|(row, other_row)| {
+        row[offset..].copy_from_slice(&other_row[offset..]);
+    });
+}
+
+/// Returns the `rgba` pixels of a buffer of the given `size`
+/// that lie in the rectangle with the top-left corner in
+/// `origin` and the given `extent` (clipped to the buffer),
+/// together with the size of the cropped buffer.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::render::crop;
+/// let rgba: Vec<u8> = (0..3 * 2 * 4).collect();
+///
+/// let (tile, size) = crop(&rgba, (3, 2), (2, 0), (2, 2));
+///
+/// assert_eq!(size, (1, 2));
+/// assert_eq!(tile, [8, 9, 10, 11, 20, 21, 22, 23]);
+/// ```
+pub fn crop(rgba: &[u8], size: (usize, usize), origin: (usize, usize), extent: (usize, usize)) -> (Vec<u8>, (usize, usize)) {
+    let width = extent.0.min(size.0.saturating_sub(origin.0));
+    let height = extent.1.min(size.1.saturating_sub(origin.1));
+
+    let mut cropped = Vec::with_capacity(width * height * 4);
+
+    for y in origin.1..origin.1 + height {
+        let start = (y * size.0 + origin.0) * 4;
+
+        cropped.extend_from_slice(&rgba[start..start + width * 4]);
+    }
+
+    (cropped, (width, height))
+}
+
+/// Stretches an `rgba` buffer of the given `size` to the
+/// `target` size, repeating the nearest pixel, which is how
+/// the coarse passes of a view are shown at full size.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::render::upscale;
+/// let rgba = [10, 0, 0, 255, 20, 0, 0, 255];
+///
+/// assert_eq!(upscale(&rgba, (2, 1), (4, 2)), [10, 0, 0, 255, 10, 0, 0, 255, 20, 0, 0, 255, 20, 0, 0, 255].repeat(2));
+/// ```
+pub fn upscale(rgba: &[u8], size: (usize, usize), target: (usize, usize)) -> Vec<u8> {
+    let mut upscaled = vec![0; target.0 * target.1 * 4];
+
+    upscaled.par_chunks_mut(4).enumerate().for_each(|(idx, chunks_pixel)| {
+        let (x, y) = (idx % target.0 * size.0 / target.0, idx / target.0 * size.1 / target.1);
+
+        let start = (y * size.0 + x) * 4;
+
+        chunks_pixel.copy_from_slice(&rgba[start..start + 4]);
+    });
+
+    upscaled
+}
+
+/// Shrinks an `rgba` buffer of the given `size` by `factor`
+/// in both directions, averaging each block of pixels,
+/// returning the new buffer together with its size.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::render::downscale;
+/// let rgba = [
+///     0, 0, 0, 255, 100, 0, 0, 255,
+///     0, 0, 0, 255, 100, 0, 0, 255,
+/// ];
+///
+/// assert_eq!(downscale(&rgba, (2, 2), 2), (vec![50, 0, 0, 255], (1, 1)));
+/// ```
+pub fn downscale(rgba: &[u8], size: (usize, usize), factor: usize) -> (Vec<u8>, (usize, usize)) {
+    let factor = factor.max(1);
+
+    let small = (size.0.div_ceil(factor), size.1.div_ceil(factor));
+
+    let mut downscaled = vec![0; small.0 * small.1 * 4];
+
+    downscaled.par_chunks_mut(4).enumerate().for_each(|(idx, chunks_pixel)| {
+        let (x, y) = (idx % small.0 * factor, idx / small.0 * factor);
+
+        let mut sum = [0; 4];
+        let mut count = 0;
+
+        for py in y..(y + factor).min(size.1) {
+            for px in x..(x + factor).min(size.0) {
+                let start = (py * size.0 + px) * 4;
+
+                for (channel, &value) in sum.iter_mut().zip(&rgba[start..start + 4]) {
+                    *channel += value as usize;
+                }
+
+                count += 1;
+            }
+        }
+
+        for (channel, total) in chunks_pixel.iter_mut().zip(sum) {
+            *channel = (total / count) as u8;
+        }
+    });
+
+    (downscaled, small)
+}
+
+/// Returns the header of a binary PAM (Netpbm) image
+/// of the given `size`, with RGBA pixels.
+fn pam_header(size: (usize, usize)) -> String {
+    format!("P7\nWIDTH {}\nHEIGHT {}\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n", size.0, size.1)
+}
+
+/// Renders a view like `render_rgba()` (without the boundary-only
+/// mode) into the binary PAM image at `path`, which can be far
+/// larger than the memory: the file is memory-mapped, and the
+/// pixels are colored straight into it, `PAM_BAND_HEIGHT` rows at
+/// a time, so that the operating system writes them back to the
+/// disk as needed, instead of holding the whole image.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::WikipediaPalette, fractal::Mandelbrot, render::{render_pam, render_rgba}, utils::Viewport};
+/// let path = std::env::temp_dir().join("mandelbrust-doctest.pam");
+///
+/// render_pam(&path, &Mandelbrot, &Viewport::default(), (300, 200), 128, &WikipediaPalette::default()).unwrap();
+///
+/// let pam = std::fs::read(&path).unwrap();
+/// let rgba = render_rgba(&Mandelbrot, &Viewport::default(), (300, 200), 128, &WikipediaPalette::default(), None).unwrap();
+///
+/// assert!(pam.starts_with(b"P7\nWIDTH 300\nHEIGHT 200\n"));
+/// assert!(pam.ends_with(&rgba));
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub fn render_pam(path: &Path, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize, mapper: &dyn ColorMapper) -> io::Result<()> {
+    viewport.check(size).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+    let start = Instant::now();
+
+    let (w, h) = size;
+    let header = pam_header(size);
+
+    let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+
+    file.set_len((header.len() + w * h * 4) as u64)?;
+
+    // the file is only changed through the map until it's flushed
+    let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+
+    mmap[..header.len()].copy_from_slice(header.as_bytes());
+
+    mmap[header.len()..].par_chunks_mut(PAM_BAND_HEIGHT * w * 4).enumerate().for_each(|(band, chunk)| {
+        let top = band * PAM_BAND_HEIGHT;
+
+        let results: Vec<EscapeResult> = par_pixels(fractal, viewport, size, max_iterations).skip(top * w).take(chunk.len() / 4).map(|(_, _, result)| result).collect();
+
+        chunk.par_chunks_mut(4).zip(results.par_iter()).for_each(|(pixel, result)| pixel.copy_from_slice(&mapper.color(result, max_iterations)));
+    });
+
+    mmap.flush()?;
+
+    log::debug!("rendered {}x{} pixels of `{}` at {} into `{}` in {:?}", w, h, fractal.name(), viewport, path.display(), start.elapsed());
+
+    stats::record(&RenderStats::new(size, viewport, max_iterations, fractal.name(), start.elapsed()));
+
+    Ok(())
+}
+
+/// Encodes an `rgba` buffer of the given `size` as a PNG image.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{coloring::WikipediaPalette, fractal::Mandelbrot, render::{render_rgba, write_png}, utils::Viewport};
+/// let rgba = render_rgba(&Mandelbrot, &Viewport::default(), (30, 20), 128, &WikipediaPalette::default(), None).unwrap();
+///
+/// let mut png = Vec::new();
+///
+/// write_png(&mut png, &rgba, (30, 20)).unwrap();
+///
+/// assert!(png.starts_with(b"\x89PNG"));
+/// ```
+pub fn write_png<T: Write>(writer: T, rgba: &[u8], size: (usize, usize)) -> io::Result<()> {
+    encode_png(writer, rgba, size, &[])
+}
+
+/// Encodes an `rgba` buffer of the given `size` as a PNG image
+/// (see `write_png()`), storing `viewport` and `max_iterations`
+/// in its text chunks, so that the view can be restored from the
+/// image (see `read_png_view()`).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{render::{read_png_view, write_png_with_view}, utils::{Plottable, MandelPoint, Viewport}};
+/// let viewport = Viewport::new(MandelPoint::new((-0.75, 0.1)), 20.0, 0.5);
+///
+/// let mut png = Vec::new();
+/// write_png_with_view(&mut png, &[0; 16 * 16 * 4], (16, 16), &viewport, 500).unwrap();
+///
+/// assert_eq!(read_png_view(png.as_slice()).unwrap(), Some((viewport, 500)));
+/// ```
+pub fn write_png_with_view<T: Write>(writer: T, rgba: &[u8], size: (usize, usize), viewport: &Viewport, max_iterations: usize) -> io::Result<()> {
+    encode_png(writer, rgba, size, &[(VIEWPORT_KEYWORD, viewport.to_string()), (ITERATIONS_KEYWORD, max_iterations.to_string())])
+}
+
+/// Encodes an `rgba` buffer of the given `size` as a
+/// PNG image, with the given text chunks; it uses the
+/// `png` encoder directly, since the one of `image` has
+/// no way to add text chunks.
+fn encode_png<T: Write>(writer: T, rgba: &[u8], size: (usize, usize), text: &[(&str, String)]) -> io::Result<()> {
+    let dimension = |value: usize| value.try_into().map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the image is too large"));
+
+    let mut encoder = png::Encoder::new(writer, dimension(size.0)?, dimension(size.1)?);
+
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    for (keyword, value) in text {
+        encoder.add_text_chunk(keyword.to_string(), value.clone())?;
+    }
+
+    let mut writer = encoder.write_header()?;
+
+    writer.write_image_data(rgba)?;
+
+    Ok(())
+}
+
+/// Reads the view and the iteration cap stored in a PNG image by
+/// `write_png_with_view()`, if it has them.
+pub fn read_png_view<R: Read>(reader: R) -> io::Result<Option<(Viewport, usize)>> {
+    let reader = png::Decoder::new(reader).read_info().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let text = |keyword: &str| reader.info().uncompressed_latin1_text.iter().find(|chunk| chunk.keyword == keyword).map(|chunk| chunk.text.clone());
+
+    let (viewport, max_iterations) = match (text(VIEWPORT_KEYWORD), text(ITERATIONS_KEYWORD)) {
+        (Some(viewport), Some(max_iterations)) => (viewport, max_iterations),
+        _ => return Ok(None),
+    };
+
+    let invalid = |err: String| io::Error::new(io::ErrorKind::InvalidData, err);
+
+    let viewport = viewport.parse().map_err(|err| invalid(format!("invalid viewport: {}", err)))?;
+    let max_iterations = max_iterations.parse().map_err(|_| invalid(format!("invalid iteration cap `{}`", max_iterations)))?;
+
+    Ok(Some((viewport, max_iterations)))
+}
@@ -0,0 +1,123 @@
+//! The entry point for the programs that embed the renderer,
+//! which only needs the parts of the crate that don't depend
+//! on the window (build it with `default-features = false`
+//! to leave `ggez` out).
+//!
+//! # Examples
+//!
+//! ```
+//! # pub use mandelbrust::{coloring::AtomDomain, renderer::{render_into, RenderSettings}, utils::Viewport};
+//! let settings = RenderSettings { size: (64, 48), coloring: Box::new(AtomDomain), ..RenderSettings::default() };
+//!
+//! let mut buffer = vec![0; 64 * 48 * 4];
+//!
+//! render_into(&mut buffer, &Viewport::default(), &settings).unwrap();
+//!
+//! assert!(buffer.chunks(4).all(|pixel| pixel[3] == 255));
+//! ```
+
+use crate::{coloring::{ColorMapper, WikipediaPalette}, fractal::{Fractal, Mandelbrot}, precision::Precision, render, utils::*};
+use rayon::prelude::*;
+use std::{error, fmt};
+
+/// How `render_into()` renders a view.
+#[derive(Debug)]
+pub struct RenderSettings {
+    /// The size (in pixels) of the image.
+    pub size: (usize, usize),
+    /// The maximum number of iterations of each pixel.
+    pub max_iterations: usize,
+    /// The fractal that is rendered.
+    pub fractal: Box<dyn Fractal>,
+    /// The coloring of the pixels.
+    pub coloring: Box<dyn ColorMapper>,
+    /// The arithmetic the pixels are iterated with, which
+    /// is always `Precision::Double` with more than a sample.
+    pub precision: Precision,
+    /// The number of jittered samples averaged
+    /// in each pixel (see `render::jittered()`).
+    pub samples: usize,
+}
+
+impl Default for RenderSettings {
+    /// Returns the settings of the window: its size, `ESCAPE_POINT`
+    /// iterations of the Mandelbrot set, colored with
+    /// `WikipediaPalette`, with a single sample of `f64`.
+    fn default() -> Self {
+        Self {
+            size: (W, H),
+            max_iterations: ESCAPE_POINT,
+            fractal: Box::new(Mandelbrot),
+            coloring: Box::new(WikipediaPalette::default()),
+            precision: Precision::Double,
+            samples: 1,
+        }
+    }
+}
+
+/// The reasons why `render_into()` can fail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderError {
+    /// The view can't be rendered with the size of the settings.
+    Viewport(ViewportError),
+    /// The buffer doesn't have 4 bytes for each pixel.
+    BufferSize {
+        /// The length the buffer should have.
+        expected: usize,
+        /// The length of the buffer.
+        found: usize,
+    },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Viewport(err) => write!(f, "invalid viewport: {}", err),
+            RenderError::BufferSize { expected, found } => write!(f, "the buffer must be {} bytes long, found {}", expected, found),
+        }
+    }
+}
+
+impl error::Error for RenderError {}
+
+impl From<ViewportError> for RenderError {
+    fn from(err: ViewportError) -> Self {
+        RenderError::Viewport(err)
+    }
+}
+
+/// Renders `viewport` with the given `settings` into `buffer`,
+/// as RGBA pixels, row by row, from top to bottom; the buffer
+/// must have exactly 4 bytes for each pixel.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{renderer::{render_into, RenderError, RenderSettings}, utils::Viewport};
+/// let settings = RenderSettings { size: (30, 20), ..RenderSettings::default() };
+///
+/// assert_eq!(render_into(&mut [0; 16], &Viewport::default(), &settings), Err(RenderError::BufferSize { expected: 30 * 20 * 4, found: 16 }));
+/// ```
+pub fn render_into(buffer: &mut [u8], viewport: &Viewport, settings: &RenderSettings) -> Result<(), RenderError> {
+    let (w, h) = settings.size;
+
+    if buffer.len() != w * h * 4 {
+        return Err(RenderError::BufferSize { expected: w * h * 4, found: buffer.len() });
+    }
+
+    viewport.check(settings.size)?;
+
+    let (fractal, mapper) = (settings.fractal.as_ref(), settings.coloring.as_ref());
+
+    if settings.samples > 1 {
+        buffer.copy_from_slice(&render::jittered(fractal, viewport, settings.size, settings.max_iterations, mapper, settings.samples.min(render::MAX_SAMPLES)));
+
+        return Ok(());
+    }
+
+    let results = settings.precision.escape_results(fractal, viewport, settings.size, settings.max_iterations);
+
+    buffer.par_chunks_mut(4).zip(results.par_iter()).for_each(|(pixel, result)| pixel.copy_from_slice(&mapper.color(result, settings.max_iterations)));
+
+    Ok(())
+}
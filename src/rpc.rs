@@ -0,0 +1,369 @@
+use crate::utils::*;
+use serde_json::{json, Value};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{mpsc::{self, Receiver, Sender}, Arc, Mutex},
+    thread,
+};
+
+/// The error code of a request that is not valid JSON.
+const PARSE_ERROR: i64 = -32700;
+
+/// The error code of a request that is not a JSON-RPC request.
+const INVALID_REQUEST: i64 = -32600;
+
+/// The error code of an unknown method.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The error code of invalid parameters.
+const INVALID_PARAMS: i64 = -32602;
+
+/// The error code of a command that failed.
+const COMMAND_FAILED: i64 = -32000;
+
+/// A command that drives the viewer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Moves the view to the given `Viewport`.
+    Goto(Viewport),
+    /// Selects the coloring with the given name.
+    SetColoring(String),
+    /// Renders the current view to a PNG
+    /// image of the given size.
+    Export {
+        path: String,
+        size: Option<(usize, usize)>,
+    },
+    /// Exports the pinned orbits to the CSV file at the given path.
+    ExportOrbits(String),
+    /// Returns the state of the viewer.
+    State,
+}
+
+/// A command received by the control interface, together
+/// with the channel used to send back its outcome.
+#[derive(Debug)]
+pub struct Call {
+    /// The command to run.
+    pub command: Command,
+    /// The channel where the result of the command
+    /// (or an error message) must be sent.
+    pub reply: Sender<Result<Value, String>>,
+}
+
+/// Returns a JSON-RPC error response.
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "error": { "code": code, "message": message }, "id": id })
+}
+
+/// Parses the parameters of `goto`, which are either a
+/// `Viewport` string (see `Viewport::from_str()`), given
+/// as `{"viewport": "..."}`, or its fields, given as
+/// `{"re": .., "im": .., "zoom": .., "rotation": ..}`
+/// (where `rotation` is in degrees).
+fn parse_goto(params: &Value) -> Result<Viewport, String> {
+    if let Some(viewport) = params.get("viewport") {
+        return viewport
+            .as_str()
+            .ok_or_else(|| "`viewport` must be a string".to_owned())?
+            .parse()
+            .map_err(|err| format!("invalid viewport: {}", err));
+    }
+
+    let field = |key: &str, default: f64| match params.get(key) {
+        Some(value) => value.as_f64().ok_or_else(|| format!("`{}` must be a number", key)),
+        None => Ok(default),
+    };
+
+    let (re, im) = VIEWPORT_DEFAULT.center().coordinates();
+
+    let zoom = field("zoom", 1.0)? as f32;
+
+    if !(zoom.is_finite() && zoom > 0.0) {
+        return Err("the zoom must be a positive number".to_owned());
+    }
+
+    Ok(Viewport::new(MandelPoint::new((field("re", re)?, field("im", im)?)), zoom, (field("rotation", 0.0)? as f32).to_radians()))
+}
+
+/// Parses the `method` and the `params` of a request into a `Command`.
+fn parse_command(method: &str, params: &Value) -> Result<Command, (i64, String)> {
+    let invalid = |message: String| (INVALID_PARAMS, message);
+
+    match method {
+        "goto" => parse_goto(params).map(Command::Goto).map_err(invalid),
+        "set_coloring" => params
+            .get("name")
+            .and_then(Value::as_str)
+            .map(|name| Command::SetColoring(name.to_owned()))
+            .ok_or_else(|| invalid("expected `{\"name\": <coloring>}`".to_owned())),
+        "export" => {
+            let path = params
+                .get("path")
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid("expected `{\"path\": <file>}`".to_owned()))?;
+
+            let dimension = |key: &str| params.get(key).map(|value| value.as_u64().filter(|&value| value > 0).map(|value| value as usize));
+
+            let size = match (dimension("width"), dimension("height")) {
+                (None, None) => None,
+                (Some(Some(width)), Some(Some(height))) => Some((width, height)),
+                _ => return Err(invalid("`width` and `height` must be given together, as positive integers".to_owned())),
+            };
+
+            Ok(Command::Export { path: path.to_owned(), size })
+        }
+        "export_orbits" => params
+            .get("path")
+            .and_then(Value::as_str)
+            .map(|path| Command::ExportOrbits(path.to_owned()))
+            .ok_or_else(|| invalid("expected `{\"path\": <file>}`".to_owned())),
+        "state" => Ok(Command::State),
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+    }
+}
+
+/// Parses a line holding a JSON-RPC 2.0 request, returning
+/// its id (which is `None` for notifications) and its
+/// command, or the error response to send back.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{rpc::{parse_request, Command}, utils::{Plottable, MandelPoint, Viewport}};
+/// # pub use serde_json::json;
+/// let (id, command) = parse_request(r#"{"jsonrpc": "2.0", "method": "goto", "params": {"re": -0.75, "im": 0.1, "zoom": 20}, "id": 1}"#).unwrap();
+///
+/// assert_eq!(id, Some(json!(1)));
+/// assert_eq!(command, Command::Goto(Viewport::new(MandelPoint::new((-0.75, 0.1)), 20.0, 0.0)));
+///
+/// assert_eq!(parse_request(r#"{"jsonrpc": "2.0", "method": "state"}"#), Ok((None, Command::State)));
+/// assert!(parse_request(r#"{"jsonrpc": "2.0", "method": "fly", "id": 2}"#).is_err());
+/// ```
+pub fn parse_request(line: &str) -> Result<(Option<Value>, Command), Value> {
+    let request: Value = serde_json::from_str(line).map_err(|err| error(&Value::Null, PARSE_ERROR, &err.to_string()))?;
+
+    let id = request.get("id").cloned();
+    let error_id = id.clone().unwrap_or(Value::Null);
+
+    let method = match (request.get("jsonrpc").and_then(Value::as_str), request.get("method").and_then(Value::as_str)) {
+        (Some("2.0"), Some(method)) => method,
+        _ => return Err(error(&error_id, INVALID_REQUEST, "expected a JSON-RPC 2.0 request")),
+    };
+
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    parse_command(method, &params)
+        .map(|command| (id, command))
+        .map_err(|(code, message)| error(&error_id, code, &message))
+}
+
+/// Reads the requests from `reader`, one per line, sends their
+/// commands through `calls`, and writes the responses on `writer`.
+fn serve_lines<R: BufRead, T: Write>(reader: R, mut writer: T, calls: &Sender<Call>) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match parse_request(&line) {
+            Ok((id, command)) => {
+                let (reply, outcome) = mpsc::channel();
+
+                if calls.send(Call { command, reply }).is_err() {
+                    // the viewer was closed
+                    return Ok(());
+                }
+
+                let outcome = match outcome.recv() {
+                    Ok(outcome) => outcome,
+                    Err(_) => return Ok(()),
+                };
+
+                // notifications get no response
+                let id = match id {
+                    Some(id) => id,
+                    None => continue,
+                };
+
+                match outcome {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+                    Err(message) => error(&id, COMMAND_FAILED, &message),
+                }
+            }
+            Err(response) => response,
+        };
+
+        writeln!(writer, "{}", response)?;
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Serves the control interface over the standard input and
+/// output, on a background thread, returning the channel
+/// where the commands are received.
+pub fn spawn_stdio() -> Receiver<Call> {
+    let (calls, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        if let Err(err) = serve_lines(io::stdin().lock(), io::stdout(), &calls) {
+            log::error!("control interface error: {}", err);
+        }
+    });
+
+    receiver
+}
+
+/// Joins the session hosted by a `Presenter` at `address`,
+/// running the views it sends like the requests of the control
+/// interface (on a background thread), and returning the
+/// channel where the commands are received.
+pub fn spawn_tcp<A: ToSocketAddrs>(address: A) -> io::Result<Receiver<Call>> {
+    let stream = TcpStream::connect(address)?;
+
+    let (calls, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        // the presenter only sends notifications
+        if let Err(err) = serve_lines(BufReader::new(stream), io::sink(), &calls) {
+            log::error!("session error: {}", err);
+        }
+
+        log::warn!("the session was closed by the presenter");
+    });
+
+    Ok(receiver)
+}
+
+/// The host of a session, which mirrors its view on
+/// the instances that join it (see `spawn_tcp()`),
+/// sending them a `goto` notification every time
+/// the view changes.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{rpc::{spawn_tcp, Command, Presenter}, utils::{Plottable, MandelPoint, Viewport}};
+/// let presenter = Presenter::host("127.0.0.1:47878").unwrap();
+///
+/// let viewport = Viewport::new(MandelPoint::new((-0.75, 0.1)), 20.0, 0.0);
+///
+/// presenter.broadcast(&viewport);
+///
+/// // the instances that join receive the current view
+/// let calls = spawn_tcp("127.0.0.1:47878").unwrap();
+///
+/// assert_eq!(calls.recv().unwrap().command, Command::Goto(viewport));
+/// ```
+#[derive(Debug)]
+pub struct Presenter {
+    clients: Arc<Mutex<Vec<TcpStream>>>,
+    last: Arc<Mutex<Option<String>>>,
+}
+
+impl Presenter {
+    /// Hosts a session on `address`, accepting the instances
+    /// that join it on a background thread; each one receives
+    /// the current view as soon as it joins.
+    pub fn host<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let last = Arc::new(Mutex::new(None::<String>));
+
+        let (accepted, current) = (Arc::clone(&clients), Arc::clone(&last));
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        log::warn!("session error: {}", err);
+
+                        continue;
+                    }
+                };
+
+                log::info!("{:?} joined the session", stream.peer_addr());
+
+                // keep the current view until the instance is
+                // added, so that it doesn't miss any change
+                let current = current.lock().unwrap_or_else(|err| err.into_inner());
+
+                if let Some(line) = current.as_ref() {
+                    if writeln!(stream, "{}", line).is_err() {
+                        continue;
+                    }
+                }
+
+                accepted.lock().unwrap_or_else(|err| err.into_inner()).push(stream);
+            }
+        });
+
+        Ok(Self { clients, last })
+    }
+
+    /// Sends `viewport` to every instance in the session, if it
+    /// changed since the last time, dropping the ones that left.
+    pub fn broadcast(&self, viewport: &Viewport) {
+        let line = json!({ "jsonrpc": "2.0", "method": "goto", "params": { "viewport": viewport.to_string() } }).to_string();
+
+        let mut last = self.last.lock().unwrap_or_else(|err| err.into_inner());
+
+        if last.as_deref() == Some(line.as_str()) {
+            return;
+        }
+
+        self.clients
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .retain_mut(|stream| writeln!(stream, "{}", line).is_ok());
+
+        *last = Some(line);
+    }
+}
+
+/// Serves the control interface on the Unix socket at `path`
+/// (on a background thread, with a thread for each connection),
+/// returning the channel where the commands are received.
+#[cfg(unix)]
+pub fn spawn_unix(path: &str) -> io::Result<Receiver<Call>> {
+    use std::os::unix::net::UnixListener;
+
+    // remove the socket left by a previous run
+    let _ = std::fs::remove_file(path);
+
+    let listener = UnixListener::bind(path)?;
+
+    let (calls, receiver) = mpsc::channel::<Call>();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("control interface error: {}", err);
+
+                    continue;
+                }
+            };
+
+            let calls = calls.clone();
+
+            thread::spawn(move || {
+                let result = stream.try_clone().and_then(|writer| serve_lines(BufReader::new(stream), writer, &calls));
+
+                if let Err(err) = result {
+                    log::error!("control interface error: {}", err);
+                }
+            });
+        }
+    });
+
+    Ok(receiver)
+}
@@ -0,0 +1,226 @@
+use crate::{fractal::Fractal, kernel::EscapeResult, precision::Precision, render, utils::*};
+use std::{fmt, ops::Range, sync::Mutex, time::Instant};
+
+/// How much the throughput measured in the last frame
+/// weighs on the estimate of a device (see `Scheduler`).
+const THROUGHPUT_WEIGHT: f64 = 0.5;
+
+/// A device that can iterate the rows of a screen, like the
+/// CPU (see `Cpu`) or a GPU, which usually supports fewer
+/// precision backends.
+pub trait Device: Send + Sync + fmt::Debug {
+    /// Returns the name of the device.
+    fn name(&self) -> &str;
+
+    /// Returns whether the device can iterate with `precision`.
+    fn supports(&self, precision: Precision) -> bool;
+
+    /// Iterates `fractal` on every pixel of the given `rows` of a
+    /// screen of the given `size` showing `viewport`, with the
+    /// arithmetic of `precision`, row by row, from top to bottom.
+    fn escape_rows(&self, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), rows: Range<usize>, max_iterations: usize, precision: Precision) -> Vec<EscapeResult>;
+}
+
+/// The processors of the machine, split among the
+/// threads of the pool of `rayon`, which support
+/// every precision backend.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Cpu;
+
+impl Device for Cpu {
+    fn name(&self) -> &str {
+        "cpu"
+    }
+
+    fn supports(&self, _precision: Precision) -> bool {
+        true
+    }
+
+    fn escape_rows(&self, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), rows: Range<usize>, max_iterations: usize, precision: Precision) -> Vec<EscapeResult> {
+        precision.escape_rows(fractal, viewport, size, rows, max_iterations)
+    }
+}
+
+/// Splits the rows of each frame among the devices that support
+/// its precision backend, in proportion to the throughput (in
+/// pixels per second) measured on the previous frames, so that
+/// they finish at about the same time; the CPU, which is always
+/// one of the devices, iterates the frames none of the others
+/// can.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, kernel::EscapeResult, precision::Precision, render::escape_results, scheduler::Scheduler, utils::Viewport};
+/// let mut scheduler = Scheduler::default();
+///
+/// let results = scheduler.escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128, Precision::Double);
+///
+/// let iterations = |results: &[EscapeResult]| results.iter().map(|result| result.iterations).collect::<Vec<_>>();
+///
+/// assert_eq!(iterations(&results), iterations(&escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128)));
+/// assert!(scheduler.throughput("cpu").unwrap() > 0.0);
+/// ```
+#[derive(Debug)]
+pub struct Scheduler {
+    devices: Vec<(Box<dyn Device>, f64)>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self { devices: vec![(Box::new(Cpu), 1.0)] }
+    }
+}
+
+impl Scheduler {
+    /// Adds `device`, which starts with the
+    /// same throughput as the CPU.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{fractal::{Fractal, Mandelbrot}, kernel::EscapeResult, precision::Precision, scheduler::{Device, Scheduler}, utils::{Plottable, MandelPoint, Viewport}};
+    /// # use std::{ops::Range, sync::{Arc, Mutex}};
+    /// // a device which only supports `f32`, like most GPUs,
+    /// // and records the rows it's given
+    /// #[derive(Debug)]
+    /// struct Gpu(Arc<Mutex<Vec<Range<usize>>>>);
+    ///
+    /// impl Device for Gpu {
+    ///     fn name(&self) -> &str {
+    ///         "gpu"
+    ///     }
+    ///
+    ///     fn supports(&self, precision: Precision) -> bool {
+    ///         precision == Precision::Float
+    ///     }
+    ///
+    ///     fn escape_rows(&self, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), rows: Range<usize>, max_iterations: usize, precision: Precision) -> Vec<EscapeResult> {
+    ///         self.0.lock().unwrap().push(rows.clone());
+    ///
+    ///         precision.escape_rows(fractal, viewport, size, rows, max_iterations)
+    ///     }
+    /// }
+    ///
+    /// let given = Arc::new(Mutex::new(Vec::new()));
+    ///
+    /// let mut scheduler = Scheduler::default();
+    ///
+    /// scheduler.add(Box::new(Gpu(Arc::clone(&given))));
+    ///
+    /// let viewport = Viewport::new(MandelPoint::new((-0.75, 0.1)), 2.0, 0.0);
+    /// let iterations = |results: &[EscapeResult]| results.iter().map(|result| result.iterations).collect::<Vec<_>>();
+    ///
+    /// // the devices start with the same throughput, so they split the rows in half
+    /// let floats = scheduler.escape_rows(&Mandelbrot, &viewport, (30, 20), 0..20, 128, Precision::Float);
+    ///
+    /// assert_eq!(*given.lock().unwrap(), [10..20]);
+    /// assert_eq!(iterations(&floats), iterations(&Precision::Float.escape_results(&Mandelbrot, &viewport, (30, 20), 128)));
+    ///
+    /// // the CPU iterates all the rows of the other precisions
+    /// let gpu = scheduler.throughput("gpu");
+    /// let doubles = scheduler.escape_rows(&Mandelbrot, &viewport, (30, 20), 0..20, 128, Precision::Double);
+    ///
+    /// assert_eq!(*given.lock().unwrap(), [10..20]);
+    /// assert_eq!(scheduler.throughput("gpu"), gpu);
+    /// assert_eq!(iterations(&doubles), iterations(&Precision::Double.escape_results(&Mandelbrot, &viewport, (30, 20), 128)));
+    /// ```
+    pub fn add(&mut self, device: Box<dyn Device>) {
+        let throughput = self.devices[0].1;
+
+        self.devices.push((device, throughput));
+    }
+
+    /// Returns the estimated throughput (in pixels per second)
+    /// of the device with the given `name`, if there is one.
+    pub fn throughput(&self, name: &str) -> Option<f64> {
+        self.devices.iter().find(|(device, _)| device.name() == name).map(|&(_, throughput)| throughput)
+    }
+
+    /// Iterates `fractal` on every pixel of a screen of the given
+    /// `size` showing `viewport`, like `Precision::escape_results()`,
+    /// splitting the rows among the devices that support `precision`,
+    /// and updating their throughput.
+    pub fn escape_results(&mut self, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize, precision: Precision) -> Vec<EscapeResult> {
+        let mut results = self.escape_rows(fractal, viewport, size, render::distinct_rows(fractal, viewport, size), max_iterations, precision);
+
+        render::mirror_rows(&mut results, size);
+
+        results
+    }
+
+    /// Iterates only the given `rows` of the screen (see
+    /// `escape_results()`), which are split among the devices
+    /// in the same way, so that a screen can be iterated in
+    /// bands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{fractal::Mandelbrot, precision::Precision, scheduler::Scheduler, utils::Viewport};
+    /// let mut scheduler = Scheduler::default();
+    ///
+    /// let results = scheduler.escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128, Precision::Double);
+    /// let rows = scheduler.escape_rows(&Mandelbrot, &Viewport::default(), (30, 20), 5..8, 128, Precision::Double);
+    ///
+    /// assert_eq!(rows.iter().map(|result| result.iterations).collect::<Vec<_>>(), results[5 * 30..8 * 30].iter().map(|result| result.iterations).collect::<Vec<_>>());
+    /// ```
+    pub fn escape_rows(&mut self, fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), rows: Range<usize>, max_iterations: usize, precision: Precision) -> Vec<EscapeResult> {
+        let (w, h) = (size.0, rows.len());
+
+        let eligible: Vec<usize> = (0..self.devices.len()).filter(|&idx| self.devices[idx].0.supports(precision)).collect();
+
+        let total: f64 = eligible.iter().map(|&idx| self.devices[idx].1).sum();
+
+        // the contiguous rows of each device, the last taking the rest
+        let mut shares = Vec::with_capacity(eligible.len());
+        let mut top = rows.start;
+
+        for (n, &idx) in eligible.iter().enumerate() {
+            let count = if n + 1 == eligible.len() { rows.end - top } else { ((self.devices[idx].1 / total * h as f64).round() as usize).min(rows.end - top) };
+
+            shares.push((idx, top..top + count));
+
+            top += count;
+        }
+
+        let done = Mutex::new(Vec::with_capacity(shares.len()));
+
+        rayon::scope(|scope| {
+            for (idx, rows) in &shares {
+                let (device, done) = (&self.devices[*idx].0, &done);
+
+                scope.spawn(move |_| {
+                    let start = Instant::now();
+
+                    let results = device.escape_rows(fractal, viewport, size, rows.clone(), max_iterations, precision);
+
+                    let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+
+                    done.lock().unwrap_or_else(|err| err.into_inner()).push((*idx, rows.start, results, (rows.len() * w) as f64 / elapsed));
+                });
+            }
+        });
+
+        let mut done = done.into_inner().unwrap_or_else(|err| err.into_inner());
+
+        done.sort_by_key(|&(_, top, _, _)| top);
+
+        let mut results = Vec::with_capacity(w * h);
+
+        for (idx, _, device_results, throughput) in done {
+            // the devices without rows measured nothing
+            if !device_results.is_empty() {
+                let estimate = &mut self.devices[idx].1;
+
+                *estimate = (1.0 - THROUGHPUT_WEIGHT) * *estimate + THROUGHPUT_WEIGHT * throughput;
+
+                log::trace!("{} iterated {} pixels at {:.0} pixels/s", self.devices[idx].0.name(), device_results.len(), throughput);
+            }
+
+            results.extend(device_results);
+        }
+
+        results
+    }
+}
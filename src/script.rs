@@ -0,0 +1,411 @@
+//! Scripts, written in [Rhai](https://rhai.rs), can define new
+//! fractals and colorings, and automate the renders (batch
+//! exports, tours, ...), without writing any Rust.
+//!
+//! A script defines a fractal with a function `iterate(z, c)`,
+//! returning the next value of `z`, and a coloring with a
+//! function `color(iterations, max_iterations, escaped, z)`,
+//! returning an array `[r, g, b]`; both take the name of
+//! the script file. Complex numbers are built with
+//! `complex(re, im)`, support the usual operators, and have
+//! `re`, `im`, `norm`, `norm_sqr`, `arg`, `conj` and `square`.
+//! Since they are interpreted, scripted formulas are
+//! much slower than the built-in ones.
+//!
+//! When a script is run (see `run()`), its statements can
+//! also use:
+//! - `viewport(re, im, zoom)` and `viewport(re, im, zoom, rotation)`
+//!   (in degrees), or `parse_viewport(string)`, to build a view,
+//!   which has `re`, `im`, `zoom`, `rotation`, `pan(dx, dy)`,
+//!   `zoom_by(factor)` and `rotate(degrees)`
+//! - `interpolate(from, to, t)`, which moves between two views
+//!   (linearly for the center and the rotation, geometrically
+//!   for the zoom), for tours
+//! - `set_fractal(name)`, `set_coloring(name)` and `set_iterations(n)`
+//! - `export_png(view, path, width, height)`, which renders a PNG image
+//!
+//! ```text
+//! fn iterate(z, c) {
+//!     // the Burning Ship
+//!     let z = complex(abs(z.re), abs(z.im));
+//!
+//!     z * z + c
+//! }
+//!
+//! set_fractal("burning-ship");
+//!
+//! let from = viewport(-0.5, -0.5, 1.0);
+//! let to = viewport(-1.75, -0.03, 50.0);
+//!
+//! for frame in 0..60 {
+//!     export_png(interpolate(from, to, frame / 59.0), `frame-${frame}.png`, 640, 480);
+//! }
+//! ```
+
+use crate::{
+    coloring::{ColorMapper, Registry},
+    fractal::{self, Fractal},
+    kernel::{EscapeResult, OrbitStats},
+    plugin::Plugins,
+    render,
+    utils::*,
+};
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, FuncArgs, Position, Scope, AST, FLOAT, INT};
+use std::{
+    fmt,
+    fs::File,
+    io::BufWriter,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// The error returned by a script.
+pub type ScriptError = Box<EvalAltResult>;
+
+/// Returns a runtime error of a script.
+fn runtime_error(message: String) -> ScriptError {
+    Box::new(EvalAltResult::ErrorRuntime(message.into(), Position::NONE))
+}
+
+/// Returns an engine that knows the complex
+/// numbers, used to run the formulas.
+fn formula_engine() -> Engine {
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<MandelPoint>("Complex")
+        .register_fn("complex", |re: FLOAT, im: FLOAT| MandelPoint::new((re, im)))
+        .register_get("re", |z: &mut MandelPoint| z.coordinates().0)
+        .register_get("im", |z: &mut MandelPoint| z.coordinates().1)
+        .register_fn("norm", |z: MandelPoint| z.norm())
+        .register_fn("norm_sqr", |z: MandelPoint| z.norm_sqr())
+        .register_fn("arg", |z: MandelPoint| z.arg())
+        .register_fn("conj", |z: MandelPoint| z.conj())
+        .register_fn("square", |z: MandelPoint| z.square())
+        .register_fn("+", |a: MandelPoint, b: MandelPoint| a + b)
+        .register_fn("-", |a: MandelPoint, b: MandelPoint| a - b)
+        .register_fn("*", |a: MandelPoint, b: MandelPoint| a.complex_mul(b))
+        .register_fn("/", |a: MandelPoint, b: MandelPoint| a.complex_div(b))
+        .register_fn("-", |z: MandelPoint| z * -1.0)
+        .register_fn("*", |z: MandelPoint, k: FLOAT| z * k)
+        .register_fn("*", |k: FLOAT, z: MandelPoint| z * k)
+        .register_fn("/", |z: MandelPoint, k: FLOAT| z / k)
+        .register_fn("to_string", |z: &mut MandelPoint| z.to_string());
+
+    engine
+}
+
+/// Calls the function `name` of `ast`, without running the
+/// statements of the script (which are meant for `run()`),
+/// failing if it doesn't return a `T`.
+fn call<T: Clone + Send + Sync + 'static>(engine: &Engine, ast: &AST, name: &str, args: impl FuncArgs) -> Result<T, ScriptError> {
+    let value: Dynamic = engine.call_fn_with_options(CallFnOptions::new().eval_ast(false), &mut Scope::new(), ast, name, args)?;
+
+    let type_name = value.type_name();
+
+    value
+        .try_cast()
+        .ok_or_else(|| runtime_error(format!("`{}` returned a value of the wrong type ({})", name, type_name)))
+}
+
+/// Returns whether `ast` defines a function
+/// called `name` with `params` parameters.
+fn defines(ast: &AST, name: &str, params: usize) -> bool {
+    ast.iter_functions().any(|function| function.name == name && function.params.len() == params)
+}
+
+/// A fractal defined by the `iterate(z, c)` function of a script.
+struct ScriptFractal {
+    name: String,
+    engine: Arc<Engine>,
+    ast: Arc<AST>,
+}
+
+impl ScriptFractal {
+    /// Calls `iterate(z, c)`.
+    fn iterate(&self, z: MandelPoint, c: MandelPoint) -> Result<MandelPoint, ScriptError> {
+        call(&self.engine, &self.ast, "iterate", (z, c))
+    }
+}
+
+impl fmt::Debug for ScriptFractal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScriptFractal").field("name", &self.name).finish()
+    }
+}
+
+impl Fractal for ScriptFractal {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Iterates `c` like `kernel::escape()`, except for
+    /// the derivative, which is unknown (and left to `0`);
+    /// if the script fails, the point is considered
+    /// part of the set.
+    fn escape(&self, c: MandelPoint, max_iterations: usize) -> EscapeResult {
+        let mut z = MANDELPOINT_ZERO;
+        let mut atom_domain = 0;
+        let mut min_norm_sqr = f64::INFINITY;
+        let mut orbit = OrbitStats::default();
+
+        for iterations in 1..=max_iterations + 1 {
+            z = match self.iterate(z, c) {
+                Ok(next) => next,
+                Err(_) => break,
+            };
+
+            let norm_sqr = z.norm_sqr();
+
+            if norm_sqr > 4.0 {
+                return EscapeResult { iterations, z, derivative: MANDELPOINT_ZERO, escaped: true, atom_domain, orbit, c, power: 2 };
+            }
+
+            orbit.push(norm_sqr.sqrt() as f32);
+
+            if norm_sqr < min_norm_sqr {
+                min_norm_sqr = norm_sqr;
+                atom_domain = iterations;
+            }
+        }
+
+        EscapeResult { iterations: max_iterations + 1, z, derivative: MANDELPOINT_ZERO, escaped: false, atom_domain, orbit, c, power: 2 }
+    }
+
+    fn orbit(&self, c: MandelPoint, max_points: usize) -> Vec<MandelPoint> {
+        let mut orbit = Vec::new();
+        let mut z = MANDELPOINT_ZERO;
+
+        while orbit.len() < max_points && z.norm_sqr() <= 4.0 {
+            z = match self.iterate(z, c) {
+                Ok(next) => next,
+                Err(_) => break,
+            };
+
+            orbit.push(z);
+        }
+
+        orbit
+    }
+}
+
+/// A coloring defined by the `color(iterations,
+/// max_iterations, escaped, z)` function of a script.
+struct ScriptColoring {
+    name: String,
+    engine: Arc<Engine>,
+    ast: Arc<AST>,
+}
+
+impl ScriptColoring {
+    /// Calls `color(iterations, max_iterations, escaped, z)`,
+    /// checking that it returns 3 or 4 channels.
+    fn call(&self, result: &EscapeResult, max_iterations: usize) -> Result<[u8; 4], ScriptError> {
+        let args = (result.iterations as INT, max_iterations as INT, result.escaped, result.z);
+
+        let channels: Array = call(&self.engine, &self.ast, "color", args)?;
+
+        let mut color = [0, 0, 0, 255];
+
+        if !(3..=4).contains(&channels.len()) {
+            return Err(runtime_error(format!("`color` must return 3 or 4 channels, not {}", channels.len())));
+        }
+
+        for (channel, value) in color.iter_mut().zip(channels) {
+            *channel = value.as_int().map_err(|kind| runtime_error(format!("a channel must be an integer, not {}", kind)))?.clamp(0, 255) as u8;
+        }
+
+        Ok(color)
+    }
+}
+
+impl fmt::Debug for ScriptColoring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScriptColoring").field("name", &self.name).finish()
+    }
+}
+
+impl ColorMapper for ScriptColoring {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Calls the function of the script, coloring
+    /// the pixel magenta if it fails.
+    fn color(&self, result: &EscapeResult, max_iterations: usize) -> [u8; 4] {
+        self.call(result, max_iterations).unwrap_or([255, 0, 255, 255])
+    }
+
+    fn colors_interior(&self) -> bool {
+        true
+    }
+}
+
+/// Loads the fractal and the coloring defined by the
+/// script at `path` (see the documentation of the
+/// module), without running its statements; they
+/// are tried once, so that the obvious errors are
+/// reported now rather than while rendering.
+pub fn load(path: &Path) -> Result<Plugins, ScriptError> {
+    let engine = Arc::new(formula_engine());
+    let ast = Arc::new(engine.compile_file(path.to_path_buf())?);
+
+    let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+
+    let mut plugins = Plugins::default();
+
+    if defines(&ast, "iterate", 2) {
+        let fractal = ScriptFractal { name: name.clone(), engine: Arc::clone(&engine), ast: Arc::clone(&ast) };
+
+        fractal.iterate(MANDELPOINT_ZERO, MANDELPOINT_ZERO)?;
+
+        plugins.fractals.push(Box::new(fractal));
+    }
+
+    if defines(&ast, "color", 4) {
+        let coloring = ScriptColoring { name, engine, ast };
+
+        coloring.call(&EscapeResult::default(), ESCAPE_POINT)?;
+
+        plugins.colorings.push(Box::new(coloring));
+    }
+
+    Ok(plugins)
+}
+
+/// The state of the renders of a running script.
+struct Session {
+    fractals: Vec<Box<dyn Fractal>>,
+    colorings: Registry,
+    fractal: usize,
+    coloring: usize,
+    max_iterations: usize,
+}
+
+impl Session {
+    /// Selects the item of `items` called `name`.
+    fn select<T: ?Sized>(items: &[Box<T>], name: &str, item_name: impl Fn(&T) -> &str, kind: &str) -> Result<usize, ScriptError> {
+        items
+            .iter()
+            .position(|item| item_name(item) == name)
+            .ok_or_else(|| runtime_error(format!("unknown {} `{}`", kind, name)))
+    }
+}
+
+/// Runs the script at `path`, whose statements can render
+/// the fractals and the colorings it defines, together with
+/// the built-in ones (see the documentation of the module).
+pub fn run(path: &Path) -> Result<(), ScriptError> {
+    let plugins = load(path)?;
+
+    let mut fractals = fractal::builtin();
+    let mut colorings = Registry::builtin();
+
+    fractals.extend(plugins.fractals);
+    colorings.extend(plugins.colorings);
+
+    let session = Arc::new(Mutex::new(Session { fractals, colorings, fractal: 0, coloring: 0, max_iterations: ESCAPE_POINT }));
+
+    let mut engine = formula_engine();
+
+    engine
+        .register_type_with_name::<Viewport>("Viewport")
+        .register_fn("viewport", |re: FLOAT, im: FLOAT, zoom: FLOAT| Viewport::new(MandelPoint::new((re, im)), zoom as f32, 0.0))
+        .register_fn("viewport", |re: FLOAT, im: FLOAT, zoom: FLOAT, rotation: FLOAT| {
+            Viewport::new(MandelPoint::new((re, im)), zoom as f32, rotation.to_radians() as f32)
+        })
+        .register_fn("parse_viewport", |s: &str| s.parse::<Viewport>().map_err(|err| runtime_error(err.to_string())))
+        .register_get("re", |viewport: &mut Viewport| viewport.center().coordinates().0)
+        .register_get("im", |viewport: &mut Viewport| viewport.center().coordinates().1)
+        .register_get("zoom", |viewport: &mut Viewport| viewport.zoom() as FLOAT)
+        .register_get("rotation", |viewport: &mut Viewport| viewport.rotation().to_degrees() as FLOAT)
+        .register_fn("pan", |viewport: &mut Viewport, dx: FLOAT, dy: FLOAT| viewport.pan((dx as f32, dy as f32)))
+        .register_fn("zoom_by", |viewport: &mut Viewport, factor: FLOAT| viewport.zoom_by(factor as f32))
+        .register_fn("rotate", |viewport: &mut Viewport, degrees: FLOAT| viewport.rotate(degrees.to_radians() as f32))
+        .register_fn("interpolate", |from: Viewport, to: Viewport, t: FLOAT| {
+            let center = from.center() + (to.center() - from.center()) * t;
+            let t = t as f32;
+            let zoom = from.zoom() * (to.zoom() / from.zoom()).powf(t);
+            let rotation = from.rotation() + (to.rotation() - from.rotation()) * t;
+
+            Viewport::new(center, zoom, rotation)
+        })
+        .register_fn("to_string", |viewport: &mut Viewport| viewport.to_string());
+
+    let state = Arc::clone(&session);
+
+    engine.register_fn("set_fractal", move |name: &str| -> Result<(), ScriptError> {
+        let mut session = state.lock().unwrap();
+
+        session.fractal = Session::select(&session.fractals, name, |fractal| fractal.name(), "fractal")?;
+
+        Ok(())
+    });
+
+    let state = Arc::clone(&session);
+
+    engine.register_fn("set_coloring", move |name: &str| -> Result<(), ScriptError> {
+        let mut session = state.lock().unwrap();
+
+        session.coloring = session.colorings.position(name).ok_or_else(|| runtime_error(format!("unknown coloring `{}`", name)))?;
+
+        Ok(())
+    });
+
+    let state = Arc::clone(&session);
+
+    engine.register_fn("set_iterations", move |max_iterations: INT| -> Result<(), ScriptError> {
+        if max_iterations <= 0 {
+            return Err(runtime_error("the number of iterations must be positive".to_owned()));
+        }
+
+        state.lock().unwrap().max_iterations = max_iterations as usize;
+
+        Ok(())
+    });
+
+    let state = Arc::clone(&session);
+
+    engine.register_fn("export_png", move |viewport: Viewport, path: &str, width: INT, height: INT| -> Result<(), ScriptError> {
+        let session = state.lock().unwrap();
+
+        // negative sizes are as small as empty ones
+        let size = (width.max(0) as usize, height.max(0) as usize);
+
+        let rgba = render::render_rgba(
+            session.fractals[session.fractal].as_ref(),
+            &viewport,
+            size,
+            session.max_iterations,
+            &session.colorings[session.coloring],
+            None,
+        )
+        .map_err(|err| runtime_error(err.to_string()))?;
+
+        File::create(path)
+            .and_then(|file| render::write_png(BufWriter::new(file), &rgba, size))
+            .map_err(|err| runtime_error(format!("could not write `{}`: {}", path, err)))?;
+
+        log::info!("exported `{}`", path);
+
+        Ok(())
+    });
+
+    let ast = engine.compile_file(path.to_path_buf())?;
+
+    engine.run_ast(&ast)
+}
+
+/// Evaluates an expression with the formula
+/// engine, which knows the complex numbers.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{script::eval_complex, utils::{Plottable, MandelPoint}};
+/// assert_eq!(eval_complex("complex(1.0, 2.0) * complex(0.0, 1.0)").unwrap(), MandelPoint::new((-2.0, 1.0)));
+/// assert!(eval_complex("complex(1.0)").is_err());
+/// ```
+pub fn eval_complex(expression: &str) -> Result<MandelPoint, ScriptError> {
+    formula_engine().eval::<Dynamic>(expression)?.try_cast::<MandelPoint>().ok_or_else(|| runtime_error("the expression is not a complex number".to_owned()))
+}
@@ -0,0 +1,291 @@
+use crate::{coloring::{self, ColorMapper, Registry, WikipediaPalette}, fractal::Mandelbrot, render, utils::*};
+use std::{collections::HashMap, io, str::FromStr, time::Instant};
+
+/// The size (in pixels) of the side of a tile.
+pub const TILE_SIZE: usize = 256;
+
+/// The largest width or height of an image
+/// that can be requested to `/render`.
+const MAX_SIZE: usize = 4096;
+
+/// The largest number of iterations that can be requested.
+const MAX_ITERATIONS: usize = 100_000;
+
+/// The largest zoom level of the tiles.
+const MAX_TILE_ZOOM: u32 = 40;
+
+/// The side of the square area of the Mandelbrot
+/// plane covered by the only tile of zoom level `0`,
+/// which is centered on the center of `VIEWPORT_DEFAULT`.
+const TILE_AREA: f64 = 4.0;
+
+/// A response of the server, which is either
+/// a PNG image or a plain-text error message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The MIME type of `body`.
+    pub content_type: &'static str,
+    /// The content of the response.
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Returns a response holding a PNG image.
+    fn png(body: Vec<u8>) -> Self {
+        Self { status: 200, content_type: "image/png", body }
+    }
+
+    /// Returns an error response with the given `status`.
+    fn error(status: u16, message: &str) -> Self {
+        Self { status, content_type: "text/plain; charset=utf-8", body: message.as_bytes().to_vec() }
+    }
+}
+
+/// Decodes the `%XX` sequences (and the `+`
+/// for spaces) of a component of a URL.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+
+    let hex = |idx: usize| bytes.get(idx).and_then(|&byte| (byte as char).to_digit(16));
+
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match (bytes[idx], hex(idx + 1), hex(idx + 2)) {
+            (b'%', Some(high), Some(low)) => {
+                decoded.push((high * 16 + low) as u8);
+
+                idx += 2;
+            }
+            (b'+', _, _) => decoded.push(b' '),
+            (byte, _, _) => decoded.push(byte),
+        }
+
+        idx += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The parameters of the query string of a request.
+struct Query(HashMap<String, String>);
+
+impl Query {
+    /// Parses a query string, like `re=-0.5&im=0&zoom=2`.
+    fn parse(query: &str) -> Self {
+        Query(
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+                    (percent_decode(key), percent_decode(value))
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the value of the parameter `key`, or
+    /// `default` if it's missing, failing with a
+    /// message if it can't be parsed.
+    fn get<T: FromStr>(&self, key: &str, default: T) -> Result<T, String> {
+        match self.0.get(key) {
+            Some(value) => value.parse().map_err(|_| format!("invalid value `{}` for the parameter `{}`", value, key)),
+            None => Ok(default),
+        }
+    }
+
+    /// Returns the coloring named by the parameter `coloring`
+    /// (see `coloring::from_name()`), or `WikipediaPalette`.
+    fn coloring(&self) -> Result<Box<dyn ColorMapper>, String> {
+        match self.0.get("coloring") {
+            Some(name) => coloring::from_name(name).ok_or_else(|| format!("unknown coloring `{}` (expected one of {})", name, Registry::builtin().names().join(", "))),
+            None => Ok(Box::new(WikipediaPalette::default())),
+        }
+    }
+
+    /// Returns the width (in pixels) of the band around the
+    /// boundary given by the parameter `boundary`, if any.
+    fn boundary(&self) -> Result<Option<f32>, String> {
+        match self.0.get("boundary") {
+            Some(_) => match self.get::<f32>("boundary", 0.0)? {
+                width if width > 0.0 && width.is_finite() => Ok(Some(width)),
+                _ => Err("the boundary width must be a positive number".to_owned()),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the number of iterations given by
+    /// the parameter `iterations`, or `ESCAPE_POINT`.
+    fn iterations(&self) -> Result<usize, String> {
+        let iterations = self.get("iterations", ESCAPE_POINT)?;
+
+        if iterations > MAX_ITERATIONS {
+            return Err(format!("at most {} iterations can be requested", MAX_ITERATIONS));
+        }
+
+        Ok(iterations)
+    }
+}
+
+/// Returns the `Viewport` of the tile `(x, y)` at the zoom
+/// level `zoom`, where the area shown at zoom level `0`
+/// (see `TILE_AREA`) is split in `2^zoom`x`2^zoom` tiles,
+/// numbered from the top-left corner, or `None` if the
+/// tile doesn't exist.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{server::tile_viewport, utils::{Plottable, MandelPoint}};
+/// let viewport = tile_viewport(1, 0, 1).unwrap();
+///
+/// assert_eq!(viewport.center(), MandelPoint::new((-1.5, -1.0)));
+/// assert_eq!(viewport.pixel_size((256, 256)), 1.0 / 128.0);
+/// assert_eq!(tile_viewport(1, 2, 0), None);
+/// ```
+pub fn tile_viewport(zoom: u32, x: u64, y: u64) -> Option<Viewport> {
+    if zoom > MAX_TILE_ZOOM {
+        return None;
+    }
+
+    let tiles = 1u64 << zoom;
+
+    if x >= tiles || y >= tiles {
+        return None;
+    }
+
+    let side = TILE_AREA / tiles as f64;
+
+    let origin = VIEWPORT_DEFAULT.center() + MandelPoint::new((-TILE_AREA / 2.0, TILE_AREA / 2.0));
+
+    let center = origin + MandelPoint::new(((x as f64 + 0.5) * side, -(y as f64 + 0.5) * side));
+
+    let zoom = VIEWPORT_DEFAULT.pixel_size((TILE_SIZE, TILE_SIZE)) / (side / TILE_SIZE as f64);
+
+    Some(Viewport::new(center, zoom as f32, 0.0))
+}
+
+/// Renders the view to a PNG image, with the
+/// options given by the parameters of `query`.
+fn render_png(viewport: &Viewport, size: (usize, usize), query: &Query) -> Result<Response, String> {
+    let rgba = render::render_rgba(&Mandelbrot, viewport, size, query.iterations()?, query.coloring()?.as_ref(), query.boundary()?).map_err(|err| err.to_string())?;
+
+    let mut png = Vec::new();
+
+    Ok(match render::write_png(&mut png, &rgba, size) {
+        Ok(()) => Response::png(png),
+        Err(err) => Response::error(500, &err.to_string()),
+    })
+}
+
+/// Handles `/render?re=..&im=..&zoom=..&w=..&h=..`, where
+/// `rotation` (in degrees), `iterations`, `coloring`
+/// and `boundary` are also accepted.
+fn handle_render(query: &Query) -> Result<Response, String> {
+    let (re, im) = VIEWPORT_DEFAULT.center().coordinates();
+
+    let center = MandelPoint::new((query.get("re", re)?, query.get("im", im)?));
+
+    let zoom: f32 = query.get("zoom", 1.0)?;
+
+    let rotation: f32 = query.get("rotation", 0.0)?;
+
+    let size = (query.get("w", W)?, query.get("h", H)?);
+
+    if size.0 > MAX_SIZE || size.1 > MAX_SIZE {
+        return Err(format!("the size must be at most {}x{}", MAX_SIZE, MAX_SIZE));
+    }
+
+    let viewport = Viewport::new(center, zoom, rotation.to_radians());
+
+    viewport.check(size).map_err(|err| err.to_string())?;
+
+    render_png(&viewport, size, query)
+}
+
+/// Handles `/tiles/z/x/y.png` (see `tile_viewport()`),
+/// where `iterations`, `coloring` and `boundary` are also accepted.
+fn handle_tile(path: &str, query: &Query) -> Option<Result<Response, String>> {
+    let mut parts = path.strip_suffix(".png")?.split('/');
+
+    let zoom = parts.next()?.parse().ok()?;
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let viewport = tile_viewport(zoom, x, y)?;
+
+    Some(render_png(&viewport, (TILE_SIZE, TILE_SIZE), query))
+}
+
+/// Returns the response to a request for `url`
+/// (a path, with an optional query string).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::server::handle;
+/// let response = handle("/render?re=-0.75&im=0.1&zoom=20&w=64&h=48");
+///
+/// assert_eq!(response.status, 200);
+/// assert_eq!(response.content_type, "image/png");
+///
+/// assert_eq!(handle("/tiles/2/3/1.png").status, 200);
+/// assert_eq!(handle("/tiles/2/4/1.png").status, 404);
+/// assert_eq!(handle("/render?zoom=-1").status, 400);
+/// ```
+pub fn handle(url: &str) -> Response {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    let query = Query::parse(query);
+
+    let result = if path == "/render" {
+        Some(handle_render(&query))
+    } else {
+        path.strip_prefix("/tiles/").and_then(|tile| handle_tile(tile, &query))
+    };
+
+    match result {
+        Some(Ok(response)) => response,
+        Some(Err(message)) => Response::error(400, &message),
+        None => Response::error(404, "not found (expected `/render` or `/tiles/z/x/y.png`)"),
+    }
+}
+
+/// Serves the renders over HTTP on `address`
+/// (like `127.0.0.1:8080`), until the process
+/// is killed (see `handle()`).
+pub fn serve(address: &str) -> io::Result<()> {
+    let server = tiny_http::Server::http(address).map_err(io::Error::other)?;
+
+    for request in server.incoming_requests() {
+        let start = Instant::now();
+
+        let response = handle(request.url());
+
+        log::info!("{} {} ({} bytes in {:?})", response.status, request.url(), response.body.len(), start.elapsed());
+
+        let content_type = tiny_http::Header::from_bytes(&b"Content-Type"[..], response.content_type.as_bytes())
+            .expect("the content type is a valid header");
+
+        let reply = tiny_http::Response::from_data(response.body)
+            .with_status_code(response.status)
+            .with_header(content_type);
+
+        // a client that went away must not stop the server
+        if let Err(err) = request.respond(reply) {
+            log::warn!("could not send the response: {}", err);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::utils::*;
+use rayon::prelude::*;
+use wide::{f64x4, CmpLe};
+
+/// The number of points iterated together by `escape_times()`.
+pub const LANES: usize = 4;
+
+/// Returns the number of iterations needed by each of the points
+/// `mandel_c` to escape, like `MandelIter::escape_time()` (with the
+/// same `f64` operations, so the results are the same), iterating
+/// them together in the lanes of a SIMD vector, until all of them
+/// escaped or reached `max_iterations + 1` iterations; the counts
+/// are kept as `f64`, which is exact up to `2^53` iterations.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{simd, utils::{Plottable, MandelIter, MandelPoint}};
+/// let points = [(1.0, 1.0), (-0.75, 0.1), (0.3, 0.5), (-1.0, 0.3)].map(MandelPoint::new);
+///
+/// assert_eq!(simd::escape_times(points, 128), points.map(|point| MandelIter::escape_time(point, 128)));
+/// ```
+pub fn escape_times(mandel_c: [MandelPoint; LANES], max_iterations: usize) -> [usize; LANES] {
+    let (c_re, c_im) = (f64x4::from(mandel_c.map(|c| c.coordinates().0)), f64x4::from(mandel_c.map(|c| c.coordinates().1)));
+
+    let (mut re, mut im) = (f64x4::ZERO, f64x4::ZERO);
+    let mut counts = f64x4::ZERO;
+
+    let (bailout_sqr, cap) = (f64x4::splat(4.0), f64x4::splat(max_iterations as f64));
+
+    loop {
+        // the lanes still iterating, like `MandelIter::next()`
+        let active = (re * re + im * im).cmp_le(bailout_sqr) & counts.cmp_le(cap);
+
+        if active.none() {
+            break;
+        }
+
+        let (next_re, next_im) = (re * re - im * im + c_re, re * im + im * re + c_im);
+
+        re = active.blend(next_re, re);
+        im = active.blend(next_im, im);
+
+        counts += active & f64x4::ONE;
+    }
+
+    counts.to_array().map(|count| count as usize)
+}
+
+/// Returns the number of iterations needed by the points of every
+/// pixel of a screen of the given `size` showing `viewport` to escape,
+/// like `terminal::iterations()`, `LANES` pixels at a time.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{simd, utils::{Plottable, MandelIter, Point, Viewport}};
+/// let viewport = Viewport::default();
+///
+/// let iterations = simd::iterations(&viewport, (30, 21), 128);
+///
+/// let scalar: Vec<usize> = (0..30 * 21).map(|idx| MandelIter::escape_time(viewport.orient(Point::new((idx % 30, idx / 30)), (30, 21)).to_mandel(&viewport, (30, 21)), 128)).collect();
+///
+/// assert_eq!(iterations, scalar);
+/// ```
+pub fn iterations(viewport: &Viewport, size: (usize, usize), max_iterations: usize) -> Vec<usize> {
+    let mut iterations = vec![0; size.0 * size.1];
+
+    iterations.par_chunks_mut(LANES).enumerate().for_each(|(chunk, counts)| {
+        // the last chunk is padded with the origin
+        let mut points = [MANDELPOINT_ZERO; LANES];
+
+        for (lane, point) in points.iter_mut().enumerate().take(counts.len()) {
+            let idx = chunk * LANES + lane;
+
+            *point = viewport.orient(Point::new((idx % size.0, idx / size.0)), size).to_mandel(viewport, size);
+        }
+
+        counts.copy_from_slice(&escape_times(points, max_iterations)[..counts.len()]);
+    });
+
+    iterations
+}
@@ -0,0 +1,154 @@
+use crate::utils::*;
+use std::{fs::{File, OpenOptions}, io::{self, Write}, path::Path, sync::Mutex, time::Duration};
+
+/// The first line of a CSV statistics file,
+/// naming the columns of `RenderStats::to_csv()`.
+pub const CSV_HEADER: &str = "width,height,re,im,zoom,rotation,max_iterations,kernel,threads,elapsed_ms";
+
+/// The file the statistics are appended to, if enabled.
+static OUTPUT: Mutex<Option<(File, StatsFormat)>> = Mutex::new(None);
+
+/// The format of a statistics file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StatsFormat {
+    /// Comma-separated values, with a header (see `CSV_HEADER`).
+    Csv,
+    /// A JSON object per line (JSON Lines).
+    Json,
+}
+
+impl StatsFormat {
+    /// Returns the format of the file at `path`, which is
+    /// CSV if its extension is `csv`, and JSON otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::stats::StatsFormat;
+    /// assert_eq!(StatsFormat::from_path("renders.csv".as_ref()), StatsFormat::Csv);
+    /// assert_eq!(StatsFormat::from_path("renders.jsonl".as_ref()), StatsFormat::Json);
+    /// ```
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::Csv,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// The performance statistics of a render.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderStats {
+    /// The size (in pixels) of the render.
+    pub size: (usize, usize),
+    /// The view that was rendered.
+    pub viewport: Viewport,
+    /// The maximum number of iterations of each pixel.
+    pub max_iterations: usize,
+    /// The name of the fractal that was iterated.
+    pub kernel: String,
+    /// The number of threads the pixels were split among.
+    pub threads: usize,
+    /// The time the render took.
+    pub elapsed: Duration,
+}
+
+impl RenderStats {
+    /// Returns the statistics of a render that took
+    /// `elapsed`, using every thread of the pool of `rayon`.
+    pub fn new(size: (usize, usize), viewport: &Viewport, max_iterations: usize, kernel: &str, elapsed: Duration) -> Self {
+        Self {
+            size,
+            viewport: *viewport,
+            max_iterations,
+            kernel: kernel.to_owned(),
+            threads: rayon::current_num_threads(),
+            elapsed,
+        }
+    }
+
+    /// Returns the statistics as a CSV record (see `CSV_HEADER`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{stats::RenderStats, utils::Viewport};
+    /// # use std::time::Duration;
+    /// let mut stats = RenderStats::new((300, 200), &Viewport::default(), 128, "mandelbrot", Duration::from_millis(12));
+    /// stats.threads = 4;
+    ///
+    /// assert_eq!(stats.to_csv(), "300,200,-0.5,0,1,0,128,mandelbrot,4,12.000");
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let (re, im) = self.viewport.center().coordinates();
+
+        format!(
+            "{},{},{},{},{},{},{},{},{},{:.3}",
+            self.size.0,
+            self.size.1,
+            re,
+            im,
+            self.viewport.zoom(),
+            self.viewport.rotation(),
+            self.max_iterations,
+            // the names of the fractals can't contain commas
+            self.kernel.replace(',', ";"),
+            self.threads,
+            self.elapsed.as_secs_f64() * 1000.0,
+        )
+    }
+
+    /// Returns the statistics as a JSON object, with
+    /// the same fields as the columns of `CSV_HEADER`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let (re, im) = self.viewport.center().coordinates();
+
+        serde_json::json!({
+            "width": self.size.0,
+            "height": self.size.1,
+            "re": re,
+            "im": im,
+            "zoom": self.viewport.zoom(),
+            "rotation": self.viewport.rotation(),
+            "max_iterations": self.max_iterations,
+            "kernel": self.kernel,
+            "threads": self.threads,
+            "elapsed_ms": self.elapsed.as_secs_f64() * 1000.0,
+        })
+    }
+}
+
+/// Starts appending the statistics of every render to the file
+/// at `path` (creating it, if needed), in the format given by
+/// its extension (see `StatsFormat::from_path()`).
+pub fn enable(path: &Path) -> io::Result<()> {
+    let format = StatsFormat::from_path(path);
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    // a new CSV file starts with its header
+    if format == StatsFormat::Csv && file.metadata()?.len() == 0 {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+
+    *OUTPUT.lock().unwrap_or_else(|err| err.into_inner()) = Some((file, format));
+
+    Ok(())
+}
+
+/// Appends `stats` to the statistics file, if enabled
+/// (see `enable()`), logging the failures.
+pub fn record(stats: &RenderStats) {
+    let mut output = OUTPUT.lock().unwrap_or_else(|err| err.into_inner());
+
+    if let Some((file, format)) = output.as_mut() {
+        let line = match format {
+            StatsFormat::Csv => stats.to_csv(),
+            StatsFormat::Json => stats.to_json().to_string(),
+        };
+
+        if let Err(err) = writeln!(file, "{}", line) {
+            log::warn!("could not write the render statistics: {}", err);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! The Mariani–Silver algorithm, which iterates only the border
+//! of each rectangle of the screen, filling its inside without
+//! iterating it when the whole border has the same iteration
+//! count, and splitting it into four smaller rectangles otherwise;
+//! since the Mandelbrot set is connected, a border of points of
+//! the set can't surround any point outside of it, which makes
+//! the views with large areas of the set much faster.
+//!
+//! # Examples
+//!
+//! ```
+//! # pub use mandelbrust::{fractal::Mandelbrot, render, subdivision, utils::{Plottable, MandelPoint, Viewport}};
+//! // a view inside the main cardioid
+//! let viewport = Viewport::new(MandelPoint::new((-0.1, 0.1)), 8.0, 0.0);
+//!
+//! let (results, iterated) = subdivision::escape_results(&Mandelbrot, &viewport, (120, 80), 256);
+//!
+//! assert!(iterated < 120 * 80 / 4);
+//! assert!(results.iter().all(|result| !result.escaped));
+//!
+//! let iterations = |results: &[mandelbrust::kernel::EscapeResult]| results.iter().map(|result| result.iterations).collect::<Vec<_>>();
+//!
+//! assert_eq!(iterations(&results), iterations(&render::escape_results(&Mandelbrot, &viewport, (120, 80), 256)));
+//! ```
+
+use crate::{fractal::Fractal, kernel::EscapeResult, utils::*};
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// The side (in pixels) of the tiles of the screen, which are
+/// subdivided in parallel, each one by a single thread.
+pub const TILE_SIDE: usize = 64;
+
+/// The side (in pixels) of the smallest rectangles
+/// that are subdivided, under which all of their
+/// pixels are iterated.
+pub const MIN_SIDE: usize = 4;
+
+/// A tile of the screen, with the results
+/// of the pixels iterated so far.
+struct Tile<'a> {
+    fractal: &'a dyn Fractal,
+    viewport: &'a Viewport,
+    size: (usize, usize),
+    max_iterations: usize,
+    origin: (usize, usize),
+    width: usize,
+    results: Vec<Option<EscapeResult>>,
+    iterated: usize,
+}
+
+impl Tile<'_> {
+    /// Returns the point of the given pixel of the screen.
+    fn point(&self, pixel: (usize, usize)) -> MandelPoint {
+        self.viewport.orient(Point::new(pixel), self.size).to_mandel(self.viewport, self.size)
+    }
+
+    /// Returns the result of the given pixel of the
+    /// screen, iterating it the first time only.
+    fn result(&mut self, (x, y): (usize, usize)) -> EscapeResult {
+        let idx = (y - self.origin.1) * self.width + x - self.origin.0;
+
+        match self.results[idx] {
+            Some(result) => result,
+            None => {
+                let result = self.fractal.escape(self.point((x, y)), self.max_iterations);
+
+                self.results[idx] = Some(result);
+                self.iterated += 1;
+
+                result
+            }
+        }
+    }
+
+    /// Fills the rectangle of pixels of the screen
+    /// with the given columns `xs` and rows `ys`.
+    fn subdivide(&mut self, xs: Range<usize>, ys: Range<usize>) {
+        if xs.is_empty() || ys.is_empty() {
+            return;
+        }
+
+        let border: Vec<(usize, usize)> = xs
+            .clone()
+            .flat_map(|x| [(x, ys.start), (x, ys.end - 1)])
+            .chain(ys.clone().flat_map(|y| [(xs.start, y), (xs.end - 1, y)]))
+            .collect();
+
+        let results: Vec<EscapeResult> = border.into_iter().map(|pixel| self.result(pixel)).collect();
+
+        let (inner_xs, inner_ys) = (xs.start + 1..xs.end - 1, ys.start + 1..ys.end - 1);
+
+        if results.iter().all(|result| result.iterations == results[0].iterations) {
+            // the inside takes the values of the border,
+            // except for its own point
+            for y in inner_ys {
+                for x in inner_xs.clone() {
+                    let idx = (y - self.origin.1) * self.width + x - self.origin.0;
+
+                    self.results[idx] = Some(EscapeResult { c: self.point((x, y)), ..results[0] });
+                }
+            }
+        } else if xs.len() <= MIN_SIDE || ys.len() <= MIN_SIDE {
+            for y in inner_ys {
+                for x in inner_xs.clone() {
+                    self.result((x, y));
+                }
+            }
+        } else {
+            let (mid_x, mid_y) = (inner_xs.start + inner_xs.len() / 2, inner_ys.start + inner_ys.len() / 2);
+
+            self.subdivide(inner_xs.start..mid_x, inner_ys.start..mid_y);
+            self.subdivide(mid_x..inner_xs.end, inner_ys.start..mid_y);
+            self.subdivide(inner_xs.start..mid_x, mid_y..inner_ys.end);
+            self.subdivide(mid_x..inner_xs.end, mid_y..inner_ys.end);
+        }
+    }
+}
+
+/// Iterates `fractal` on a screen of the given `size` showing
+/// `viewport`, like `render::escape_results()`, with the
+/// Mariani–Silver algorithm (see the module), returning the
+/// results together with the number of pixels that were
+/// iterated; the pixels that are filled take the values
+/// of a pixel of the border (except for `c`), so the
+/// colorings that depend on more than the iteration
+/// count show them as flat areas.
+///
+/// The algorithm assumes that the fractal is connected,
+/// so thin filaments crossing a rectangle without touching
+/// its border may be missed.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, subdivision, utils::Viewport};
+/// let (results, iterated) = subdivision::escape_results(&Mandelbrot, &Viewport::default(), (300, 200), 128);
+///
+/// assert_eq!(results.len(), 300 * 200);
+/// assert!(iterated < 300 * 200);
+/// assert!(!results[100 * 300 + 150].escaped); // the center is inside the set
+/// ```
+pub fn escape_results(fractal: &dyn Fractal, viewport: &Viewport, size: (usize, usize), max_iterations: usize) -> (Vec<EscapeResult>, usize) {
+    let (w, h) = size;
+
+    let columns = w.div_ceil(TILE_SIDE);
+
+    let tiles: Vec<Tile> = (0..columns * h.div_ceil(TILE_SIDE))
+        .into_par_iter()
+        .map(|idx| {
+            let origin = (idx % columns * TILE_SIDE, idx / columns * TILE_SIDE);
+            let (xs, ys) = (origin.0..(origin.0 + TILE_SIDE).min(w), origin.1..(origin.1 + TILE_SIDE).min(h));
+
+            let mut tile = Tile { fractal, viewport, size, max_iterations, origin, width: xs.len(), results: vec![None; xs.len() * ys.len()], iterated: 0 };
+
+            tile.subdivide(xs, ys);
+
+            tile
+        })
+        .collect();
+
+    let mut results = vec![EscapeResult::default(); w * h];
+
+    for tile in &tiles {
+        for (idx, result) in tile.results.iter().enumerate() {
+            results[(tile.origin.1 + idx / tile.width) * w + tile.origin.0 + idx % tile.width] = result.unwrap_or_default();
+        }
+    }
+
+    (results, tiles.iter().map(|tile| tile.iterated).sum())
+}
@@ -0,0 +1,333 @@
+use crate::utils::*;
+use rayon::prelude::*;
+use ratatui::crossterm::terminal;
+use std::env;
+
+/// The characters used to draw the fractal in the
+/// terminal, from the emptiest to the densest one.
+pub const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// The first Unicode Braille pattern, which has no dots.
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// The bits of the dots of a Braille pattern,
+/// indexed by their column and row in the cell.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// The size of the terminal used when
+/// the actual one can't be determined.
+pub const DEFAULT_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+/// Returns the size of the terminal, in characters,
+/// as reported by the terminal itself or, if that fails
+/// (e.g. when the output is redirected), as exported by
+/// the shell through `COLUMNS` and `LINES`, falling back
+/// to `DEFAULT_TERMINAL_SIZE`.
+pub fn terminal_size() -> (usize, usize) {
+    if let Ok((columns, rows)) = terminal::size() {
+        if columns > 0 && rows > 0 {
+            return (columns as usize, rows as usize);
+        }
+    }
+
+    let read = |var: &str| env::var(var).ok().and_then(|value| value.parse().ok()).filter(|&value| value > 0);
+
+    (
+        read("COLUMNS").unwrap_or(DEFAULT_TERMINAL_SIZE.0),
+        read("LINES").unwrap_or(DEFAULT_TERMINAL_SIZE.1),
+    )
+}
+
+/// Returns the character of `ASCII_RAMP` corresponding
+/// to the given number of `iterations`.
+pub fn ascii_char(iterations: usize, max_iterations: usize) -> char {
+    let idx = iterations * (ASCII_RAMP.len() - 1) / (max_iterations + 1);
+
+    ASCII_RAMP[idx.min(ASCII_RAMP.len() - 1)] as char
+}
+
+/// The colors that can be used by the terminal renderers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ColorDepth {
+    /// No colors, only characters.
+    Monochrome,
+    /// The 256 colors of the xterm palette.
+    Ansi256,
+    /// 24-bit colors.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Returns the escape sequence that sets the
+    /// foreground (or, if `background` is `true`,
+    /// the background) color to `color`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::terminal::ColorDepth;
+    /// assert_eq!(ColorDepth::TrueColor.escape([255, 170, 0, 255], false), "\x1b[38;2;255;170;0m");
+    /// assert_eq!(ColorDepth::Ansi256.escape([255, 170, 0, 255], true), "\x1b[48;5;214m");
+    /// assert_eq!(ColorDepth::Monochrome.escape([255, 170, 0, 255], false), "");
+    /// ```
+    pub fn escape(&self, color: [u8; 4], background: bool) -> String {
+        let layer = if background { 48 } else { 38 };
+        let [r, g, b, _] = color;
+
+        match self {
+            ColorDepth::Monochrome => String::new(),
+            ColorDepth::Ansi256 => format!("\x1b[{};5;{}m", layer, Self::xterm_index(color)),
+            ColorDepth::TrueColor => format!("\x1b[{};2;{};{};{}m", layer, r, g, b),
+        }
+    }
+
+    /// Returns the `ColorDepth` with the given name, which
+    /// is one of `none`, `256` and `truecolor`, or `auto`
+    /// to detect the one supported by the terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::terminal::ColorDepth;
+    /// assert_eq!(ColorDepth::from_name("256"), Some(ColorDepth::Ansi256));
+    /// assert_eq!(ColorDepth::from_name("16"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(ColorDepth::Monochrome),
+            "256" => Some(ColorDepth::Ansi256),
+            "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+            "auto" => Some(Self::detect()),
+            _ => None,
+        }
+    }
+
+    /// Returns the `ColorDepth` supported by the terminal,
+    /// according to the `COLORTERM` and `TERM` variables.
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Monochrome
+        }
+    }
+
+    /// Returns the escape sequence that resets the colors.
+    pub fn reset(&self) -> &'static str {
+        match self {
+            ColorDepth::Monochrome => "",
+            _ => "\x1b[0m",
+        }
+    }
+
+    /// Returns the index of the closest color
+    /// of the 6x6x6 cube of the xterm palette.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::terminal::ColorDepth;
+    /// assert_eq!(ColorDepth::xterm_index([255, 170, 0, 255]), 214);
+    /// assert_eq!(ColorDepth::xterm_index([0, 0, 0, 255]), 16);
+    /// ```
+    pub fn xterm_index(color: [u8; 4]) -> u8 {
+        let level = |channel: u8| ((channel as u16 * 5 + 127) / 255) as u8;
+
+        16 + 36 * level(color[0]) + 6 * level(color[1]) + level(color[2])
+    }
+}
+
+/// Returns the iterations of each pixel of a screen with
+/// the given `size`, showing `viewport`, row by row; the
+/// y coordinate follows the orientation of the `Viewport`,
+/// like in the window; with the `simd` feature, the pixels
+/// are iterated together (see `simd::iterations()`).
+pub fn iterations(viewport: &Viewport, size: (usize, usize), max_iterations: usize) -> Vec<usize> {
+    #[cfg(feature = "simd")]
+    return crate::simd::iterations(viewport, size, max_iterations);
+
+    #[cfg(not(feature = "simd"))]
+    (0..size.0 * size.1)
+        .into_par_iter()
+        .map(|idx| {
+            let pixel = viewport.orient(Point::new((idx % size.0, idx / size.0)), size);
+
+            MandelIter::escape_time(pixel.to_mandel(viewport, size), max_iterations)
+        })
+        .collect()
+}
+
+/// Returns the palette color of the given number of
+/// `iterations`, or black for the points of the set.
+pub fn cell_color(iterations: usize, max_iterations: usize) -> [u8; 4] {
+    if iterations > max_iterations {
+        [0, 0, 0, 255]
+    } else {
+        COLOR_MAP[iterations % COLOR_MAP.len()]
+    }
+}
+
+/// Renders the area of the Mandelbrot plane shown by `viewport`
+/// as character art, with one line for each row of the given
+/// `size` (in characters). Since characters are about twice
+/// as tall as they are wide, each character covers two rows
+/// of pixels, in order to keep the proportions of the fractal;
+/// unless `depth` is `Monochrome`, characters are colored
+/// with the palette.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{terminal::{render_ascii, ColorDepth}, utils::Viewport};
+/// let art = render_ascii(&Viewport::default(), (60, 20), 64, ColorDepth::Monochrome);
+///
+/// assert_eq!(art.lines().count(), 20);
+/// assert!(art.lines().all(|line| line.chars().count() == 60));
+/// assert!(art.contains('@'));
+/// ```
+pub fn render_ascii(viewport: &Viewport, size: (usize, usize), max_iterations: usize, depth: ColorDepth) -> String {
+    let (columns, rows) = size;
+
+    let iterations = iterations(viewport, (columns, rows * 2), max_iterations);
+
+    let mut art = String::with_capacity((columns + 1) * rows);
+
+    for row in 0..rows {
+        let mut last_color = None;
+
+        for column in 0..columns {
+            let pixel_iterations = iterations[2 * row * columns + column];
+            let color = cell_color(pixel_iterations, max_iterations);
+
+            // avoid repeating the same escape sequence
+            if last_color != Some(color) {
+                art.push_str(&depth.escape(color, false));
+
+                last_color = Some(color);
+            }
+
+            art.push(ascii_char(pixel_iterations, max_iterations));
+        }
+
+        art.push_str(depth.reset());
+        art.push('\n');
+    }
+
+    art
+}
+
+/// A character of a Braille rendering, which
+/// shows the 2x4 pixels it covers as dots.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BrailleCell {
+    /// The Braille pattern, whose dots
+    /// are the pixels inside the set.
+    pub symbol: char,
+    /// The palette color of the average iterations
+    /// of the pixels outside the set (black if there
+    /// are none), meant to be used as background.
+    pub color: [u8; 4],
+}
+
+/// Returns the Braille cells of a rendering of the area of
+/// the Mandelbrot plane shown by `viewport`, with the given
+/// `size` in characters, row by row; since each character
+/// covers 2x4 pixels, the effective resolution is eight
+/// times the one of the terminal.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{terminal::braille_cells, utils::Viewport};
+/// let cells = braille_cells(&Viewport::default(), (40, 10), 64);
+///
+/// assert_eq!(cells.len(), 400);
+/// assert!(cells.iter().any(|cell| cell.symbol == '\u{28FF}')); // all dots set
+/// ```
+pub fn braille_cells(viewport: &Viewport, size: (usize, usize), max_iterations: usize) -> Vec<BrailleCell> {
+    let (columns, rows) = size;
+    let pixels = (columns * 2, rows * 4);
+
+    let iterations = iterations(viewport, pixels, max_iterations);
+
+    (0..columns * rows)
+        .into_par_iter()
+        .map(|idx| {
+            let (column, row) = (idx % columns, idx / columns);
+
+            let mut pattern = BRAILLE_BLANK;
+            let mut outside = (0, 0);
+
+            for (dx, dots) in BRAILLE_DOTS.iter().enumerate() {
+                for (dy, dot) in dots.iter().enumerate() {
+                    let pixel_iterations = iterations[(row * 4 + dy) * pixels.0 + column * 2 + dx];
+
+                    if pixel_iterations > max_iterations {
+                        pattern |= dot;
+                    } else {
+                        outside = (outside.0 + pixel_iterations, outside.1 + 1);
+                    }
+                }
+            }
+
+            let color = match outside {
+                (_, 0) => cell_color(max_iterations + 1, max_iterations),
+                (sum, count) => cell_color(sum / count, max_iterations),
+            };
+
+            BrailleCell {
+                symbol: std::char::from_u32(pattern).unwrap_or(' '),
+                color,
+            }
+        })
+        .collect()
+}
+
+/// Renders the area of the Mandelbrot plane shown by `viewport`
+/// with Braille characters (see `braille_cells()`); unless `depth`
+/// is `Monochrome`, the background of each character is colored
+/// with the palette, and the dots are black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{terminal::{render_braille, ColorDepth}, utils::Viewport};
+/// let art = render_braille(&Viewport::default(), (40, 10), 64, ColorDepth::TrueColor);
+///
+/// assert_eq!(art.lines().count(), 10);
+/// assert!(art.contains("\x1b[48;2;"));
+/// ```
+pub fn render_braille(viewport: &Viewport, size: (usize, usize), max_iterations: usize, depth: ColorDepth) -> String {
+    let (columns, rows) = size;
+
+    let cells = braille_cells(viewport, size, max_iterations);
+
+    let mut art = String::with_capacity((columns * 4 + 1) * rows);
+
+    for row in cells.chunks(columns.max(1)) {
+        let mut last_color = None;
+
+        art.push_str(&depth.escape([0, 0, 0, 255], false));
+
+        for cell in row {
+            // avoid repeating the same escape sequence
+            if last_color != Some(cell.color) {
+                art.push_str(&depth.escape(cell.color, true));
+
+                last_color = Some(cell.color);
+            }
+
+            art.push(cell.symbol);
+        }
+
+        art.push_str(depth.reset());
+        art.push('\n');
+    }
+
+    art
+}
@@ -0,0 +1,131 @@
+use crate::{kernel::EscapeResult, utils::*};
+use rayon::prelude::*;
+use std::fmt;
+
+/// The brightness that the exposure gives to the
+/// (logarithmic) average of the escaping pixels,
+/// before the tone mapping.
+pub const KEY: f32 = 0.5;
+
+/// An operator that compresses the high dynamic range of
+/// the smooth iteration counts of a view (where a few pixels
+/// near the boundary take far more iterations than all the
+/// others) into the range of a palette, from `0.0` to `1.0`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ToneMap {
+    /// The operator of Reinhard et al., `x / (1 + x)`.
+    Reinhard,
+    /// The filmic curve fitted to ACES by Narkowicz, which
+    /// keeps more contrast in the shadows and the highlights.
+    Filmic,
+}
+
+impl ToneMap {
+    /// Returns the next operator, in the order
+    /// they are cycled through in the window.
+    pub fn next(self) -> Option<Self> {
+        match self {
+            ToneMap::Reinhard => Some(ToneMap::Filmic),
+            ToneMap::Filmic => None,
+        }
+    }
+
+    /// Maps the (non-negative) exposed value `x`
+    /// to the range from `0.0` to `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::tonemap::ToneMap;
+    /// assert_eq!(ToneMap::Reinhard.map(1.0), 0.5);
+    /// assert_eq!(ToneMap::Filmic.map(0.0), 0.0);
+    /// assert!(ToneMap::Filmic.map(1000.0) <= 1.0);
+    /// ```
+    pub fn map(&self, x: f32) -> f32 {
+        match self {
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::Filmic => ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl fmt::Display for ToneMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ToneMap::Reinhard => write!(f, "reinhard"),
+            ToneMap::Filmic => write!(f, "filmic"),
+        }
+    }
+}
+
+/// Returns the HDR field of the `results`, which is the smooth
+/// iteration count (see `EscapeResult::smooth_iterations()`)
+/// of the pixels that escaped, and `None` for the others.
+pub fn hdr_field(results: &[EscapeResult]) -> Vec<Option<f32>> {
+    results
+        .par_iter()
+        .map(|result| Some(result.smooth_iterations().max(0.0)).filter(|_| result.escaped))
+        .collect()
+}
+
+/// Returns the color of `t` (between `0.0` and `1.0`) on the
+/// gradient of `COLOR_MAP`, interpolating between its colors.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{tonemap::gradient, utils::COLOR_MAP};
+/// assert_eq!(gradient(0.0), COLOR_MAP[0]);
+/// assert_eq!(gradient(1.0), COLOR_MAP[COLOR_MAP.len() - 1]);
+/// ```
+pub fn gradient(t: f32) -> [u8; 4] {
+    let position = t.clamp(0.0, 1.0) * (COLOR_MAP.len() - 1) as f32;
+
+    let idx = (position as usize).min(COLOR_MAP.len() - 2);
+    let fraction = position - idx as f32;
+
+    let (from, to) = (COLOR_MAP[idx], COLOR_MAP[idx + 1]);
+
+    let mut color = [0; 4];
+
+    color.iter_mut().zip(from.iter().zip(to)).for_each(|(ch, (&a, b))| *ch = (a as f32 + (b as f32 - a as f32) * fraction).round() as u8);
+
+    color
+}
+
+/// Colors the `results` by tone mapping their HDR field (see
+/// `hdr_field()`) with `operator`, after exposing it so that
+/// its logarithmic average becomes `KEY`, and looking up the
+/// mapped values on `gradient()`; the pixels that didn't
+/// escape are black.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, render::escape_results, tonemap::{tone_mapped, ToneMap}, utils::Viewport};
+/// let results = escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128);
+///
+/// let rgba = tone_mapped(&results, ToneMap::Reinhard);
+///
+/// assert_eq!(rgba.len(), 30 * 20 * 4);
+/// assert_eq!(rgba[(10 * 30 + 15) * 4..][..4], [0, 0, 0, 255]); // the center is inside the set
+/// ```
+pub fn tone_mapped(results: &[EscapeResult], operator: ToneMap) -> Vec<u8> {
+    let field = hdr_field(results);
+
+    // the logarithmic average, which is not
+    // dominated by the few brightest pixels
+    let (sum, count) = field.iter().flatten().fold((0.0, 0), |(sum, count), &value| (sum + (1.0 + value).ln(), count + 1));
+
+    let average = if count == 0 { 1.0 } else { (sum / count as f32).exp() };
+
+    let exposure = KEY / average;
+
+    field
+        .par_iter()
+        .flat_map_iter(|value| match value {
+            Some(value) => gradient(operator.map((1.0 + value) * exposure)),
+            None => [0, 0, 0, 255],
+        })
+        .collect()
+}
@@ -0,0 +1,171 @@
+use crate::{locale::Locale, terminal::{self, ColorDepth}, utils::*};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Paragraph, Widget},
+    DefaultTerminal,
+};
+use std::io;
+
+/// The fraction of the height of the
+/// view that is panned at each key press.
+const PAN_STEP: f32 = 0.1;
+
+/// The factor by which the view is
+/// zoomed at each key press.
+const ZOOM_STEP: f32 = 1.5;
+
+/// The character used to draw two pixels in a single
+/// cell: its foreground is the upper pixel, and its
+/// background is the lower one.
+const HALF_BLOCK: &str = "\u{2580}";
+
+/// Returns the terminal color closest to `color` among the ones
+/// of `depth`; without colors, it's either white or black,
+/// depending on the brightness of `color`.
+fn terminal_color(depth: ColorDepth, color: [u8; 4]) -> Color {
+    let [r, g, b, _] = color;
+
+    match depth {
+        ColorDepth::TrueColor => Color::Rgb(r, g, b),
+        ColorDepth::Ansi256 => Color::Indexed(ColorDepth::xterm_index(color)),
+        ColorDepth::Monochrome if 2 * r as u16 + 5 * g as u16 + b as u16 >= 128 * 8 => Color::White,
+        ColorDepth::Monochrome => Color::Black,
+    }
+}
+
+/// A widget that draws the area of the Mandelbrot
+/// plane shown by `viewport`, using either colored
+/// half-block characters, so that each cell of the
+/// terminal holds two (square-ish) pixels, or Braille
+/// characters, which hold 2x4 pixels each, with the
+/// colors of `depth`.
+struct FractalView<'a> {
+    viewport: &'a Viewport,
+    max_iterations: usize,
+    braille: bool,
+    depth: ColorDepth,
+}
+
+impl FractalView<'_> {
+    /// Draws the fractal using half-block characters.
+    fn render_half_blocks(&self, area: Rect, buf: &mut Buffer) {
+        let pixels = (area.width as usize, area.height as usize * 2);
+
+        let iterations = terminal::iterations(self.viewport, pixels, self.max_iterations);
+
+        let color = |idx: usize| terminal_color(self.depth, COLOR_MAP[iterations[idx] % COLOR_MAP.len()]);
+
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let upper = color(2 * y as usize * pixels.0 + x as usize);
+                let lower = color((2 * y as usize + 1) * pixels.0 + x as usize);
+
+                if let Some(cell) = buf.cell_mut((area.x + x, area.y + y)) {
+                    cell.set_symbol(HALF_BLOCK).set_fg(upper).set_bg(lower);
+                }
+            }
+        }
+    }
+
+    /// Draws the fractal using Braille characters,
+    /// with black dots on a colored background (or,
+    /// without colors, with the colors of the terminal).
+    fn render_braille(&self, area: Rect, buf: &mut Buffer) {
+        let cells = terminal::braille_cells(self.viewport, (area.width as usize, area.height as usize), self.max_iterations);
+
+        for (idx, braille_cell) in cells.iter().enumerate() {
+            let x = (idx % area.width as usize) as u16;
+            let y = (idx / area.width as usize) as u16;
+
+            let (fg, bg) = match self.depth {
+                ColorDepth::Monochrome => (Color::Reset, Color::Reset),
+                depth => (Color::Black, terminal_color(depth, braille_cell.color)),
+            };
+
+            if let Some(cell) = buf.cell_mut((area.x + x, area.y + y)) {
+                cell.set_char(braille_cell.symbol).set_fg(fg).set_bg(bg);
+            }
+        }
+    }
+}
+
+impl Widget for FractalView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        if self.braille {
+            self.render_braille(area, buf);
+        } else {
+            self.render_half_blocks(area, buf);
+        }
+    }
+}
+
+/// The state of the terminal explorer.
+struct Explorer<'a> {
+    viewport: Viewport,
+    max_iterations: usize,
+    braille: bool,
+    depth: ColorDepth,
+    locale: &'a Locale,
+}
+
+impl Explorer<'_> {
+    /// Draws the fractal and the status line, then
+    /// handles key presses until the user quits.
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| {
+                let [view_area, status_area] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+                frame.render_widget(FractalView { viewport: &self.viewport, max_iterations: self.max_iterations, braille: self.braille, depth: self.depth }, view_area);
+
+                // enough digits to tell apart the pixels of the view
+                let pixels = (view_area.width as usize, view_area.height as usize * 2);
+                let center = self.viewport.format_point(self.viewport.precise_center(), pixels);
+
+                let status = self.locale.format("tui.status", &[&center, &format!("{:.3}", self.viewport.zoom()), &self.max_iterations]);
+
+                frame.render_widget(Paragraph::new(status).style(Style::default().fg(Color::White).bg(Color::Black)), status_area);
+            })?;
+
+            let key = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key,
+                _ => continue,
+            };
+
+            match key.code {
+                KeyCode::Left | KeyCode::Char('h') => self.viewport.pan((-PAN_STEP, 0.0)),
+                KeyCode::Right | KeyCode::Char('l') => self.viewport.pan((PAN_STEP, 0.0)),
+                KeyCode::Up | KeyCode::Char('k') => self.viewport.pan((0.0, PAN_STEP)),
+                KeyCode::Down | KeyCode::Char('j') => self.viewport.pan((0.0, -PAN_STEP)),
+                KeyCode::Char('+') | KeyCode::Char('=') => self.viewport.zoom_by(ZOOM_STEP),
+                KeyCode::Char('-') => self.viewport.zoom_by(1.0 / ZOOM_STEP),
+                KeyCode::Char('r') => self.viewport = Viewport::default(),
+                KeyCode::Char('b') => self.braille = !self.braille,
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Runs the interactive terminal explorer, starting
+/// from `viewport` (drawn with Braille characters if
+/// `braille` is `true`, with the colors of `depth`),
+/// until the user quits; the terminal is restored
+/// even if drawing fails.
+pub fn run(viewport: Viewport, max_iterations: usize, braille: bool, depth: ColorDepth, locale: &Locale) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+
+    let result = Explorer { viewport, max_iterations, braille, depth, locale }.run(&mut terminal);
+
+    ratatui::restore();
+
+    result
+}
@@ -1,17 +1,18 @@
+use crate::{fixed::{self, Fixed}, kernel::CycleCheck, precision::Real};
 use num::Complex;
-use std::{fmt, ops};
+use std::{error, fmt, ops};
 
 /// The range of values of the x-axis of the Mandelbrot set.
-const X_RANGE: (f32, f32) = (-2.0, 1.0);
+const X_RANGE: (f64, f64) = (-2.0, 1.0);
 
 /// The range of values of the y-axis of the Mandelbrot set.
-const Y_RANGE: (f32, f32) = (-1.0, 1.0);
+const Y_RANGE: (f64, f64) = (-1.0, 1.0);
 
 /// The length of the x-axis of the Mandelbrot set.
-const X_DIFF: f32 = X_RANGE.1 - X_RANGE.0;
+const X_DIFF: f64 = X_RANGE.1 - X_RANGE.0;
 
 /// The length of the y-axis of the Mandelbrot set.
-const Y_DIFF: f32 = Y_RANGE.1 - Y_RANGE.0;
+const Y_DIFF: f64 = Y_RANGE.1 - Y_RANGE.0;
 
 /// The scaling factor, used to calculate `W` and `H`,
 /// to make sure that the right proportions are mantained.
@@ -23,6 +24,13 @@ pub const W: usize = X_DIFF as usize * SCALING_FACTOR;
 /// The height of the window.
 pub const H: usize = Y_DIFF as usize * SCALING_FACTOR;
 
+/// The minimum width and height of
+/// the window and of the rendered images.
+pub const MIN_SIZE: usize = 16;
+
+// the window must be renderable
+const _: () = assert!(W >= MIN_SIZE && H >= MIN_SIZE);
+
 /// The value after which the points are no longer
 /// iterated through the Mandelbrot set equation.
 pub const ESCAPE_POINT: usize = 128;
@@ -30,7 +38,7 @@ pub const ESCAPE_POINT: usize = 128;
 /// A constant used to check if the cursor
 /// is at the center of the Mandelbrot plane,
 /// to avoid crashes while rendering the red line.
-pub const CUSTOM_EPSILON: f32 = 0.065;
+pub const CUSTOM_EPSILON: f64 = 0.065;
 
 /// The color gradient used in the [Wikipedia page of
 /// the Mandelbrot set](https://en.wikipedia.org/wiki/Mandelbrot_set),
@@ -57,6 +65,10 @@ pub const COLOR_MAP: [[u8; 4]; 16] = [
     [106,  52,   3, 255], // brown 2
 ];
 
+/// The number of decimal digits used to show
+/// a `MandelPoint`, if not specified otherwise.
+pub const DISPLAY_PRECISION: usize = 6;
+
 /// The default position of the `Cursor`, which is `(0, 0)`.
 pub const CURSOR_ZERO: Cursor = Cursor { coordinates: (0, 0) };
 
@@ -67,6 +79,16 @@ pub const MANDELPOINT_ZERO: MandelPoint = MandelPoint { coordinates: (0.0, 0.0)
 /// The default position of a generic 2D point, which is `(0, 0)`.
 pub const POINT_ZERO: Point = Point { coordinates: (0, 0) };
 
+/// The default `Viewport`, which shows the whole
+/// Mandelbrot set (the area between `X_RANGE`
+/// and `Y_RANGE`), without any rotation.
+pub const VIEWPORT_DEFAULT: Viewport = Viewport {
+    center: (Fixed::from_f64((X_RANGE.0 + X_RANGE.1) / 2.0), Fixed::from_f64((Y_RANGE.0 + Y_RANGE.1) / 2.0)),
+    zoom: 1.0,
+    rotation: 0.0,
+    orientation: Orientation::Math,
+};
+
 /// A trait implemented by any entity that
 /// can be expressed using two coordinates.
 pub trait Plottable {
@@ -140,15 +162,18 @@ pub trait Plottable {
     /// let point = Point::default(); // (0, 0)
     /// 
     /// // note: using two 2D entities that have the same coordinates types
-    /// assert!(cursor.is_distance_less_than(point, 13.0)) // 9.0 * f32::SQRT_2 < 13.0
+    /// assert!(cursor.is_distance_less_than(point, 13.0)) // 9.0 * f64::SQRT_2 < 13.0
     /// ```
-    fn is_distance_less_than<P: Plottable<Coordinates = Self::Coordinates>>(&self, other: P, distance: f32) -> bool;
+    fn is_distance_less_than<P: Plottable<Coordinates = Self::Coordinates>>(&self, other: P, distance: f64) -> bool;
 }
 
 /// A macro used to implement:
 /// - `Add<usize>`, `Sub<usize>`, `Mul<usize>` and `Div<usize>` for `Cursor`
-/// - `Add<f32>`, `Sub<f32>`, `Mul<f32>` and `Div<f32>` for `MandelPoint`
+/// - `Add<f64>`, `Sub<f64>`, `Mul<f64>` and `Div<f64>` for `MandelPoint`
 /// - `Add<usize>`, `Sub<usize>`, `Mul<usize>` and `Div<usize>` for `Point`
+///
+/// and the component-wise `Add`, `Sub`, `Mul` and `Div`
+/// between two values of the same 2D entity.
 macro_rules! impl_ops {
     ($struct:ty, $trait:ident, $type:ty, $op:tt, $func:ident) => {
         impl ops::$trait<$type> for $struct {
@@ -160,11 +185,22 @@ macro_rules! impl_ops {
                 Self::new((coordinates.0 $op other, coordinates.1 $op other))
             }
         }
+
+        impl ops::$trait for $struct {
+            type Output = Self;
+
+            fn $func(self, other: Self) -> Self {
+                let coords_self = self.coordinates();
+                let coords_other = other.coordinates();
+
+                Self::new((coords_self.0 $op coords_other.0, coords_self.1 $op coords_other.1))
+            }
+        }
     };
 }
 
 /// A macro used to implement `Plottable`,
-/// some `std::ops` traits, `Default` and `Display`
+/// some `std::ops` traits and `Default`
 /// to `Cursor`, `MandelPoint` and `Point`.
 macro_rules! impl_2d_entity {
     ($struct:ty, $type:ty, $const:ident) => {
@@ -187,12 +223,12 @@ macro_rules! impl_2d_entity {
                 self.coordinates = coordinates
             }
 
-            fn is_distance_less_than<P: Plottable<Coordinates = Self::Coordinates>>(&self, other: P, distance: f32) -> bool {
+            fn is_distance_less_than<P: Plottable<Coordinates = Self::Coordinates>>(&self, other: P, distance: f64) -> bool {
                 let coords_self = self.coordinates();
                 let coords_other = other.coordinates();
 
-                let x_diff = (coords_self.0 - coords_other.0) as f32;
-                let y_diff = (coords_self.1 - coords_other.1) as f32;
+                let x_diff = (coords_self.0 - coords_other.0) as f64;
+                let y_diff = (coords_self.1 - coords_other.1) as f64;
 
                 x_diff * x_diff + y_diff * y_diff < distance * distance
             }
@@ -211,7 +247,13 @@ macro_rules! impl_2d_entity {
                 $const
             }
         }
+    };
+}
 
+/// A macro used to implement `Display` to `Cursor`
+/// and `Point`, which are shown as tuples.
+macro_rules! impl_tuple_display {
+    ($struct:ty) => {
         impl fmt::Display for $struct {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
                 write!(f, "{:?}", self.coordinates)
@@ -228,34 +270,294 @@ pub struct Cursor {
 }
 
 impl_2d_entity!(Cursor, usize, CURSOR_ZERO);
+impl_tuple_display!(Cursor);
+
+impl Cursor {
+    /// Returns the cursor at the given `position` (as reported
+    /// by the window) on a screen with the given `size`, or
+    /// `None` if the position lies outside of the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, Cursor};
+    /// assert_eq!(Cursor::from_position((10.7, 20.2), (300, 200)), Some(Cursor::new((10, 20))));
+    /// assert_eq!(Cursor::from_position((300.0, 20.0), (300, 200)), None);
+    /// assert_eq!(Cursor::from_position((10.0, -1.0), (300, 200)), None);
+    /// assert_eq!(Cursor::from_position((f32::NAN, 20.0), (300, 200)), None);
+    /// ```
+    pub fn from_position(position: (f32, f32), size: (usize, usize)) -> Option<Self> {
+        let (x, y) = position;
+
+        // `NaN` fails both comparisons
+        let inside = |coordinate: f32, length: usize| coordinate >= 0.0 && coordinate < length as f32;
+
+        if inside(x, size.0) && inside(y, size.1) {
+            Some(Self::new((x as usize, y as usize)))
+        } else {
+            None
+        }
+    }
+}
 
 /// A struct used to represent any point
 /// on the Mandelbrot plane.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+/// let a = MandelPoint::new((1.0, 2.0));
+/// let b = MandelPoint::new((0.5, 4.0));
+///
+/// // the operations between two points are component-wise
+/// assert_eq!(a + b, MandelPoint::new((1.5, 6.0)));
+/// assert_eq!(a - b, MandelPoint::new((0.5, -2.0)));
+/// assert_eq!(a * b, MandelPoint::new((0.5, 8.0)));
+/// assert_eq!(a / b, MandelPoint::new((2.0, 0.5)));
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct MandelPoint {
-    coordinates: (f32, f32),
+    coordinates: (f64, f64),
+}
+
+impl_2d_entity!(MandelPoint, f64, MANDELPOINT_ZERO);
+
+impl MandelPoint {
+    /// Returns the point with the given
+    /// modulus `r` and argument `theta`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+    /// let mandelpoint = MandelPoint::from_polar(2.0, 0.0);
+    ///
+    /// assert_eq!(mandelpoint, MandelPoint::new((2.0, 0.0)));
+    /// ```
+    pub fn from_polar(r: f64, theta: f64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+
+        MandelPoint::new((r * cos, r * sin))
+    }
+
+    /// Returns the squared modulus of the point,
+    /// seen as a complex number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+    /// let mandelpoint = MandelPoint::new((3.0, 4.0));
+    ///
+    /// assert_eq!(mandelpoint.norm_sqr(), 25.0);
+    /// assert_eq!(mandelpoint.norm(), 5.0);
+    /// ```
+    pub fn norm_sqr(&self) -> f64 {
+        let (re, im) = self.coordinates;
+
+        re * re + im * im
+    }
+
+    /// Returns the modulus of the point,
+    /// seen as a complex number.
+    pub fn norm(&self) -> f64 {
+        self.norm_sqr().sqrt()
+    }
+
+    /// Returns the argument of the point, seen
+    /// as a complex number, in the range `(-π, π]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+    /// # use std::f64::consts::PI;
+    /// let mandelpoint = MandelPoint::new((0.0, 1.0));
+    ///
+    /// assert_eq!(mandelpoint.arg(), PI / 2.0);
+    /// ```
+    pub fn arg(&self) -> f64 {
+        let (re, im) = self.coordinates;
+
+        im.atan2(re)
+    }
+
+    /// Returns the complex conjugate of the point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+    /// let mandelpoint = MandelPoint::new((1.0, 2.0));
+    ///
+    /// assert_eq!(mandelpoint.conj(), MandelPoint::new((1.0, -2.0)));
+    /// ```
+    pub fn conj(&self) -> Self {
+        let (re, im) = self.coordinates;
+
+        MandelPoint::new((re, -im))
+    }
+
+    /// Returns the complex product between `self` and `other`
+    /// (note that `*` is component-wise instead).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+    /// let a = MandelPoint::new((1.0, 2.0));
+    /// let b = MandelPoint::new((3.0, -1.0));
+    ///
+    /// assert_eq!(a.complex_mul(b), MandelPoint::new((5.0, 5.0)));
+    /// ```
+    pub fn complex_mul(&self, other: Self) -> Self {
+        let (a, b) = self.coordinates;
+        let (c, d) = other.coordinates;
+
+        MandelPoint::new((a * c - b * d, a * d + b * c))
+    }
+
+    /// Returns the complex quotient between `self` and `other`
+    /// (note that `/` is component-wise instead).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+    /// let a = MandelPoint::new((5.0, 5.0));
+    /// let b = MandelPoint::new((3.0, -1.0));
+    ///
+    /// assert_eq!(a.complex_div(b), MandelPoint::new((1.0, 2.0)));
+    /// ```
+    pub fn complex_div(&self, other: Self) -> Self {
+        self.complex_mul(other.conj()) / other.norm_sqr()
+    }
+
+    /// Returns the square of the point,
+    /// seen as a complex number.
+    pub fn square(&self) -> Self {
+        self.complex_mul(*self)
+    }
+
+    /// Maps the point to the corresponding pixel of a
+    /// screen with the given `size`, which shows `viewport`;
+    /// this is the inverse of `Point::to_mandel()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Point, Viewport};
+    /// let viewport = Viewport::default();
+    ///
+    /// assert_eq!(MandelPoint::new((-0.5, 0.0)).to_screen(&viewport, (300, 200)), Point::new((150, 100)));
+    /// ```
+    pub fn to_screen(&self, viewport: &Viewport, size: (usize, usize)) -> Point {
+        let (x, y) = viewport.pixel_of(self.coordinates, size);
+
+        Point::new((x as usize, y as usize))
+    }
+
+    /// Maps the point to its position on a screen with the
+    /// given `size`, which shows `viewport`, with the y axis
+    /// pointing down, like `MandelPoint::to_screen()` followed
+    /// by `Viewport::orient()`, but without rounding or clamping
+    /// the positions outside of the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Viewport};
+    /// let viewport = Viewport::default();
+    ///
+    /// assert_eq!(MandelPoint::new((-0.5, 0.5)).to_position(&viewport, (300, 200)), (150.0, 50.0));
+    /// assert_eq!(MandelPoint::new((-3.5, 0.0)).to_position(&viewport, (300, 200)), (-150.0, 100.0));
+    /// ```
+    pub fn to_position(&self, viewport: &Viewport, size: (usize, usize)) -> (f32, f32) {
+        let (x, y) = viewport.pixel_of(self.coordinates, size);
+        let (x, y) = (x as f32, y as f32);
+
+        match viewport.orientation() {
+            Orientation::Math => (x, size.1 as f32 - y),
+            Orientation::Screen => (x, y),
+        }
+    }
+}
+
+impl MandelPoint {
+    /// Returns the sign and the absolute value of the
+    /// imaginary part, used to format the point as
+    /// `a + bi` or `a - bi`.
+    fn imaginary_sign(&self) -> (char, f64) {
+        let im = self.coordinates.1;
+
+        if im < 0.0 {
+            ('-', -im)
+        } else {
+            ('+', im.abs())
+        }
+    }
+}
+
+/// The `Display` implementation of `MandelPoint` shows
+/// the point as a complex number, with `DISPLAY_PRECISION`
+/// decimal digits unless a precision is specified.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+/// let mandelpoint = MandelPoint::new((-0.743643, -0.131825));
+///
+/// assert_eq!(mandelpoint.to_string(), "-0.743643 - 0.131825i");
+/// assert_eq!(format!("{:.2}", mandelpoint), "-0.74 - 0.13i");
+/// ```
+impl fmt::Display for MandelPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(DISPLAY_PRECISION);
+        let (sign, im) = self.imaginary_sign();
+
+        write!(f, "{:.*} {} {:.*}i", precision, self.coordinates.0, sign, precision, im)
+    }
 }
 
-impl_2d_entity!(MandelPoint, f32, MANDELPOINT_ZERO);
+/// The `LowerExp` implementation of `MandelPoint` shows
+/// the point as a complex number in scientific notation,
+/// which is more readable at deep zoom scales.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::{Plottable, MandelPoint};
+/// let mandelpoint = MandelPoint::new((0.00012, 3.5));
+///
+/// assert_eq!(format!("{:.1e}", mandelpoint), "1.2e-4 + 3.5e0i");
+/// ```
+impl fmt::LowerExp for MandelPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(DISPLAY_PRECISION);
+        let (sign, im) = self.imaginary_sign();
+
+        write!(f, "{:.*e} {} {:.*e}i", precision, self.coordinates.0, sign, precision, im)
+    }
+}
 
-impl From<Complex<f32>> for MandelPoint {
-    fn from(complex: Complex<f32>) -> Self {
+impl From<Complex<f64>> for MandelPoint {
+    fn from(complex: Complex<f64>) -> Self {
         MandelPoint::new((complex.re, complex.im))
     }
 }
 
 impl From<Point> for MandelPoint {
+    /// Maps `point` from a `W`x`H` screen showing
+    /// `VIEWPORT_DEFAULT`; use `Point::to_mandel()`
+    /// for any other screen size or `Viewport`.
     fn from(point: Point) -> Self {
-        let coordinates = point.coordinates();
-
-        MandelPoint::new((
-            X_DIFF * coordinates.0 as f32 / W as f32 + X_RANGE.0,
-            Y_DIFF * coordinates.1 as f32 / H as f32 + Y_RANGE.0
-        ))
+        point.to_mandel(&VIEWPORT_DEFAULT, (W, H))
     }
 }
 
-impl From<MandelPoint> for Complex<f32> {
+impl From<MandelPoint> for Complex<f64> {
     fn from(mandelpoint: MandelPoint) -> Self {
         let coordinates = mandelpoint.coordinates();
 
@@ -263,31 +565,200 @@ impl From<MandelPoint> for Complex<f32> {
     }
 }
 
+/// A key built from a `MandelPoint`, which, unlike the
+/// point itself, implements `Eq`, `Ord` and `Hash`, so that
+/// plane coordinates can be used as keys of maps and sets.
+///
+/// Keys can either be exact (built with `exact()` or `From`),
+/// in which case two keys are equal if and only if the points
+/// are equal (treating `-0.0` as `0.0` and all `NaN`s as equal),
+/// or quantized (built with `quantized()`), in which case the
+/// points are snapped to a grid first; the two kinds of keys
+/// should not be mixed in the same collection.
+///
+/// Keys are ordered lexicographically, first by the real
+/// part and then by the imaginary part.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::{Plottable, MandelPoint, MandelPointKey};
+/// # use std::collections::HashSet;
+/// let mut visited = HashSet::new();
+///
+/// visited.insert(MandelPointKey::from(MandelPoint::new((-0.75, 0.1))));
+///
+/// assert!(visited.contains(&MandelPoint::new((-0.75, 0.1)).into()));
+/// assert!(MandelPointKey::exact(MandelPoint::new((-1.0, 0.0))) < MandelPointKey::exact(MandelPoint::new((0.5, 0.0))));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MandelPointKey {
+    coordinates: (i64, i64),
+}
+
+impl MandelPointKey {
+    /// Returns the exact key of `mandelpoint`.
+    pub fn exact(mandelpoint: MandelPoint) -> Self {
+        let (re, im) = mandelpoint.coordinates();
+
+        Self { coordinates: (Self::ordered_bits(re), Self::ordered_bits(im)) }
+    }
+
+    /// Returns the key of `mandelpoint` snapped to
+    /// the grid with the given `step`, so that points
+    /// closer than `step` usually share the same key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, MandelPointKey};
+    /// let a = MandelPointKey::quantized(MandelPoint::new((0.1001, 0.2)), 0.01);
+    /// let b = MandelPointKey::quantized(MandelPoint::new((0.0999, 0.2)), 0.01);
+    ///
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn quantized(mandelpoint: MandelPoint, step: f64) -> Self {
+        let (re, im) = (mandelpoint / step).coordinates();
+
+        Self { coordinates: (re.round() as i64, im.round() as i64) }
+    }
+
+    /// Maps `value` to an integer with the same ordering.
+    fn ordered_bits(value: f64) -> i64 {
+        let value = if value == 0.0 {
+            0.0
+        } else if value.is_nan() {
+            f64::NAN
+        } else {
+            value
+        };
+
+        let bits = value.to_bits() as i64;
+
+        // negative floats are ordered the opposite way of their bits
+        if bits < 0 {
+            bits ^ i64::MAX
+        } else {
+            bits
+        }
+    }
+}
+
+impl From<MandelPoint> for MandelPointKey {
+    /// Returns the exact key of `mandelpoint`.
+    fn from(mandelpoint: MandelPoint) -> Self {
+        Self::exact(mandelpoint)
+    }
+}
+
 /// A struct used to represent a generic 2D point.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::{Plottable, Point};
+/// let a = Point::new((6, 8));
+/// let b = Point::new((2, 4));
+///
+/// assert_eq!(a + b, Point::new((8, 12)));
+/// assert_eq!(a - b, Point::new((4, 4)));
+/// assert_eq!(a / b, Point::new((3, 2)));
+/// assert_eq!(a + 1, Point::new((7, 9)));
+/// ```
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Point {
     coordinates: (usize, usize),
 }
 
 impl_2d_entity!(Point, usize, POINT_ZERO);
+impl_tuple_display!(Point);
+
+impl Point {
+    /// Maps the pixel of a screen with the given `size`,
+    /// which shows `viewport`, to the corresponding point
+    /// on the Mandelbrot plane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Point, Viewport};
+    /// let viewport = Viewport::default();
+    ///
+    /// assert_eq!(Point::new((0, 0)).to_mandel(&viewport, (300, 200)), MandelPoint::new((-2.0, -1.0)));
+    /// assert_eq!(Point::new((150, 100)).to_mandel(&viewport, (300, 200)), MandelPoint::new((-0.5, 0.0)));
+    /// ```
+    pub fn to_mandel(&self, viewport: &Viewport, size: (usize, usize)) -> MandelPoint {
+        MandelPoint::new(viewport.plane_point(*self, size))
+    }
+}
 
 impl From<MandelPoint> for Point {
+    /// Maps `mandelpoint` to a `W`x`H` screen showing
+    /// `VIEWPORT_DEFAULT`; use `MandelPoint::to_screen()`
+    /// for any other screen size or `Viewport`.
     fn from(mandelpoint: MandelPoint) -> Self {
-        let coordinates = mandelpoint.coordinates();
+        mandelpoint.to_screen(&VIEWPORT_DEFAULT, (W, H))
+    }
+}
+
+/// How the point of each pixel enters the iteration
+/// `z = z^2 + c`, which is either the parameter `c`
+/// (as in the Mandelbrot set) or the starting `z`
+/// (as in the Julia sets).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seed {
+    /// The point is `c`, and the iteration starts from `z0`.
+    Parameter {
+        /// The starting value of `z`.
+        z0: MandelPoint,
+    },
+    /// The point is the starting value of `z`, while `c` is fixed.
+    Start {
+        /// The fixed parameter.
+        c: MandelPoint,
+    },
+}
+
+impl Seed {
+    /// Returns the starting `z` and the parameter `c`
+    /// of the iteration of the given `point`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Seed, MANDELPOINT_ZERO};
+    /// let point = MandelPoint::new((0.3, 0.5));
+    /// let c = MandelPoint::new((-0.8, 0.156));
+    ///
+    /// assert_eq!(Seed::default().split(point), (MANDELPOINT_ZERO, point));
+    /// assert_eq!(Seed::Start { c }.split(point), (point, c));
+    /// ```
+    pub fn split(&self, point: MandelPoint) -> (MandelPoint, MandelPoint) {
+        match *self {
+            Seed::Parameter { z0 } => (z0, point),
+            Seed::Start { c } => (point, c),
+        }
+    }
+}
 
-        Point::new((
-            (W as f32 * (coordinates.0 - X_RANGE.0) as f32 / X_DIFF as f32) as usize,
-            (H as f32 * (coordinates.1 - Y_RANGE.0) as f32 / Y_DIFF as f32) as usize,
-        ))
+impl Default for Seed {
+    /// Returns the seed of the Mandelbrot set,
+    /// where the point is `c` and `z0 = 0`.
+    fn default() -> Self {
+        Seed::Parameter { z0: MANDELPOINT_ZERO }
     }
 }
 
 /// An iterator that, at each step,
 /// calculates the next point of the
 /// equation of the Mandelbrot set
-/// (`z = z^2 + c`, starting with `z = 0`).
+/// (`z = z^2 + c`, starting with `z = 0`,
+/// unless specified otherwise).
 /// `next()` returns `None` if the next value
-/// is out of the area of radius 2.
+/// is out of the area of radius 2 (see
+/// `MandelIter::builder()` to change the
+/// exponent, the bailout radius and more,
+/// like stopping as soon as `z` enters a cycle).
 /// 
 /// # Examples
 /// 
@@ -302,8 +773,13 @@ impl From<MandelPoint> for Point {
 /// assert_eq!(iter.next(), None); // the point exits from the area of radius 2
 /// ```
 pub struct MandelIter {
-    curr: Complex<f32>,
-    c: Complex<f32>,
+    curr: MandelPoint,
+    c: MandelPoint,
+    power: u32,
+    bailout_sqr: f64,
+    remaining: Option<usize>,
+    derivative: Option<MandelPoint>,
+    cycle: Option<CycleCheck>,
 }
 
 impl MandelIter {
@@ -321,9 +797,185 @@ impl MandelIter {
     /// let mut iter = MandelIter::new(mandelpoint);
     /// ```
     pub fn new(mandel_c: MandelPoint) -> Self {
-        Self {
-            curr: Complex { re: 0.0, im: 0.0 },
-            c: mandel_c.into(),
+        Self::with_start(MANDELPOINT_ZERO, mandel_c)
+    }
+
+    /// Returns a new iterator of the equation
+    /// `z = z^2 + c`, starting with `z = z0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, MandelIter};
+    /// let mut iter = MandelIter::with_start(MandelPoint::new((0.0, 1.0)), MandelPoint::new((1.0, 0.0)));
+    ///
+    /// assert_eq!(iter.next(), Some(MandelPoint::new((0.0, 0.0)))); // `i^2 + 1 = 0`
+    /// ```
+    pub fn with_start(z0: MandelPoint, mandel_c: MandelPoint) -> Self {
+        Self::builder(mandel_c).start(z0).build()
+    }
+
+    /// Returns a builder of an iterator of the equation
+    /// `z = z^n + c`, for the given `mandel_c`, which by
+    /// default is the one of `MandelIter::new()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, MandelIter};
+    /// let mut iter = MandelIter::builder(MandelPoint::new((1.0, 0.0)))
+    ///     .power(3)
+    ///     .bailout(10.0)
+    ///     .max_iterations(2)
+    ///     .derivative(true)
+    ///     .build();
+    ///
+    /// assert_eq!(iter.next(), Some(MandelPoint::new((1.0, 0.0))));
+    /// assert_eq!(iter.derivative(), Some(MandelPoint::new((1.0, 0.0))));
+    /// assert_eq!(iter.next(), Some(MandelPoint::new((2.0, 0.0)))); // `1^3 + 1`
+    /// assert_eq!(iter.derivative(), Some(MandelPoint::new((4.0, 0.0)))); // `3 * 1^2 * 1 + 1`
+    /// assert_eq!(iter.next(), None); // the iterations are over
+    /// ```
+    pub fn builder(mandel_c: MandelPoint) -> MandelIterBuilder {
+        MandelIterBuilder {
+            c: mandel_c,
+            z0: MANDELPOINT_ZERO,
+            power: 2,
+            bailout: 2.0,
+            max_iterations: None,
+            derivative: false,
+            periodicity: false,
+        }
+    }
+
+    /// Returns a new iterator for the given
+    /// `point`, according to `seed`.
+    pub fn seeded(seed: Seed, point: MandelPoint) -> Self {
+        let (z0, c) = seed.split(point);
+
+        Self::with_start(z0, c)
+    }
+
+    /// Returns the number of iterations needed by `mandel_c`
+    /// to escape, up to `max_iterations + 1`, which is returned
+    /// for the points that never escape (e.g. the ones inside
+    /// the Mandelbrot set); the points whose orbit enters a cycle
+    /// are detected as soon as it repeats (see `period()`),
+    /// instead of being iterated up to the iteration cap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, MandelIter};
+    /// assert_eq!(MandelIter::escape_time(MandelPoint::new((1.0, 1.0)), 128), 2);
+    /// assert_eq!(MandelIter::escape_time(MandelPoint::new((0.0, 0.0)), 128), 129);
+    /// ```
+    pub fn escape_time(mandel_c: MandelPoint, max_iterations: usize) -> usize {
+        let mut iter = MandelIter::builder(mandel_c).periodicity(true).build();
+
+        let iterations = iter.by_ref().enumerate().take_while(|(idx, _)| *idx <= max_iterations).count();
+
+        // a cycle never escapes
+        match iter.period() {
+            Some(_) => max_iterations + 1,
+            None => iterations,
+        }
+    }
+
+    /// Returns the derivative `dz/dc` of the last
+    /// value of `z`, if the builder enabled it.
+    pub fn derivative(&self) -> Option<MandelPoint> {
+        self.derivative
+    }
+
+    /// Returns the period of the cycle that `z` entered,
+    /// if the builder enabled the periodicity checking and
+    /// the last value of `z` came back close to an earlier one
+    /// (see `CycleCheck`), after which the iterator stops, as `z` would never
+    /// escape; the period found may be a multiple of the
+    /// smallest one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, MandelIter};
+    /// let mut iter = MandelIter::builder(MandelPoint::new((-1.0, 0.0))).periodicity(true).build();
+    ///
+    /// // `0, -1, 0, -1, ...`
+    /// assert_eq!(iter.by_ref().count(), 3);
+    /// assert_eq!(iter.period(), Some(2));
+    /// ```
+    pub fn period(&self) -> Option<usize> {
+        self.cycle.and_then(|cycle| cycle.period())
+    }
+}
+
+/// A builder of `MandelIter` (see `MandelIter::builder()`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MandelIterBuilder {
+    c: MandelPoint,
+    z0: MandelPoint,
+    power: u32,
+    bailout: f64,
+    max_iterations: Option<usize>,
+    derivative: bool,
+    periodicity: bool,
+}
+
+impl MandelIterBuilder {
+    /// Sets the starting value of `z` (`0` by default).
+    pub fn start(mut self, z0: MandelPoint) -> Self {
+        self.z0 = z0;
+        self
+    }
+
+    /// Sets the exponent `n` of `z = z^n + c` (`2` by
+    /// default); exponents smaller than `2` become `2`.
+    pub fn power(mut self, power: u32) -> Self {
+        self.power = power.max(2);
+        self
+    }
+
+    /// Sets the radius of the area that `z` must leave
+    /// to escape (`2` by default).
+    pub fn bailout(mut self, radius: f64) -> Self {
+        self.bailout = radius;
+        self
+    }
+
+    /// Sets the maximum number of values
+    /// returned (unlimited by default).
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Sets whether the derivative `dz/dc` is tracked
+    /// (see `MandelIter::derivative()`), which is not
+    /// by default.
+    pub fn derivative(mut self, derivative: bool) -> Self {
+        self.derivative = derivative;
+        self
+    }
+
+    /// Sets whether the iterator stops as soon as `z`
+    /// enters a cycle (see `MandelIter::period()`),
+    /// which it doesn't by default.
+    pub fn periodicity(mut self, periodicity: bool) -> Self {
+        self.periodicity = periodicity;
+        self
+    }
+
+    /// Returns the iterator.
+    pub fn build(self) -> MandelIter {
+        MandelIter {
+            curr: self.z0,
+            c: self.c,
+            power: self.power,
+            bailout_sqr: self.bailout * self.bailout,
+            remaining: self.max_iterations,
+            derivative: Some(MANDELPOINT_ZERO).filter(|_| self.derivative),
+            cycle: Some(CycleCheck::new(self.z0)).filter(|_| self.periodicity),
         }
     }
 }
@@ -333,13 +985,671 @@ impl Iterator for MandelIter {
 
     fn next(&mut self) -> Option<Self::Item> {
         // checks if the distance between the origin
-        // and the current point is more than 2
-        if self.curr.re * self.curr.re + self.curr.im * self.curr.im > 4.0 {
-            None
-        } else {
-            self.curr = self.curr * self.curr + self.c;
+        // and the current point is more than the
+        // bailout radius (2 by default)
+        if self.curr.norm_sqr() > self.bailout_sqr || self.remaining == Some(0) || self.period().is_some() {
+            return None;
+        }
 
-            Some(self.curr.into())
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= 1;
         }
+
+        // `z^(n - 1)`, which the derivative needs too
+        let power = (2..self.power).fold(self.curr, |power, _| power.complex_mul(self.curr));
+
+        if let Some(derivative) = &mut self.derivative {
+            *derivative = (power * self.power as f64).complex_mul(*derivative) + MandelPoint::new((1.0, 0.0));
+        }
+
+        self.curr = power.complex_mul(self.curr) + self.c;
+
+        if let Some(cycle) = &mut self.cycle {
+            cycle.check(self.curr);
+        }
+
+        Some(self.curr)
+    }
+}
+
+/// The direction of the imaginary axis on the screen.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Orientation {
+    /// The imaginary axis points up, as in math.
+    Math,
+    /// The imaginary axis points down, like the rows of the screen.
+    Screen,
+}
+
+impl Orientation {
+    /// Returns the other orientation.
+    pub fn flipped(self) -> Self {
+        match self {
+            Orientation::Math => Orientation::Screen,
+            Orientation::Screen => Orientation::Math,
+        }
+    }
+}
+
+impl Default for Orientation {
+    /// Returns `Orientation::Math`.
+    fn default() -> Self {
+        Orientation::Math
+    }
+}
+
+/// The `Display` implementation of `Orientation`
+/// shows it as `math` or `screen`.
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Orientation::Math => write!(f, "math"),
+            Orientation::Screen => write!(f, "screen"),
+        }
+    }
+}
+
+/// A struct used to represent the area of the
+/// Mandelbrot plane that is shown on the screen.
+/// The area is centered on `center`, which is kept
+/// as a `Fixed`, so that it can move by less than the
+/// precision of `f64` at the deepest zooms, it is
+/// `zoom` times smaller than the default one, it is
+/// rotated by `rotation` radians around its center,
+/// and its imaginary axis follows `orientation`.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Viewport {
+    center: (Fixed, Fixed),
+    zoom: f32,
+    rotation: f32,
+    orientation: Orientation,
+}
+
+impl Viewport {
+    /// Returns a new `Viewport`, with
+    /// the imaginary axis pointing up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Viewport};
+    /// let viewport = Viewport::new(MandelPoint::new((-0.75, 0.1)), 20.0, 0.0);
+    /// ```
+    pub fn new(center: MandelPoint, zoom: f32, rotation: f32) -> Self {
+        let (re, im) = center.coordinates();
+
+        Self::with_precise_center((Fixed::from_f64(re), Fixed::from_f64(im)), zoom, rotation)
+    }
+
+    /// Returns a new `Viewport` like `new()`, centered on
+    /// a point given with the precision of `Fixed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{fixed::Fixed, utils::{Plottable, MandelPoint, Viewport}};
+    /// let re: Fixed = "-0.75000000000000000000000000001".parse().unwrap();
+    ///
+    /// let viewport = Viewport::with_precise_center((re, Fixed::from_f64(0.1)), 20.0, 0.0);
+    ///
+    /// assert_eq!(viewport.precise_center(), (re, Fixed::from_f64(0.1)));
+    /// assert_eq!(viewport.center(), MandelPoint::new((-0.75, 0.1)));
+    /// ```
+    pub fn with_precise_center(center: (Fixed, Fixed), zoom: f32, rotation: f32) -> Self {
+        Self { center, zoom, rotation, orientation: Orientation::Math }
+    }
+
+    /// Returns the center of the `Viewport`, rounded to
+    /// the precision of `f64` (see `precise_center()`).
+    pub fn center(&self) -> MandelPoint {
+        MandelPoint::new((self.center.0.to_f64(), self.center.1.to_f64()))
+    }
+
+    /// Returns the center of the `Viewport`,
+    /// with the precision of `Fixed`.
+    pub fn precise_center(&self) -> (Fixed, Fixed) {
+        self.center
+    }
+
+    /// Returns the zoom of the `Viewport`.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Returns the rotation of the `Viewport`, in radians.
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Returns the orientation of the imaginary axis.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Sets the orientation of the imaginary axis.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+    }
+
+    /// Maps a pixel of a screen with the given `size`, whose
+    /// rows go from top to bottom, to the pixel that has to be
+    /// passed to `Point::to_mandel()` (whose y axis points up),
+    /// according to the orientation of the `Viewport`; since
+    /// the mapping is its own inverse, it also maps the results
+    /// of `MandelPoint::to_screen()` back to the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, Orientation, Point, Viewport};
+    /// let mut viewport = Viewport::default();
+    ///
+    /// assert_eq!(viewport.orient(Point::new((10, 20)), (300, 200)), Point::new((10, 180)));
+    ///
+    /// viewport.set_orientation(Orientation::Screen);
+    ///
+    /// assert_eq!(viewport.orient(Point::new((10, 20)), (300, 200)), Point::new((10, 20)));
+    /// ```
+    pub fn orient(&self, pixel: Point, size: (usize, usize)) -> Point {
+        let (x, y) = pixel.coordinates();
+
+        match self.orientation {
+            // the pixels outside of the screen are clamped
+            Orientation::Math => Point::new((x, size.1.saturating_sub(y))),
+            Orientation::Screen => pixel,
+        }
+    }
+
+    /// Rotates the `Viewport` by `angle` radians
+    /// (counterclockwise), keeping the rotation
+    /// in the range `[0, 2π)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::Viewport;
+    /// # use std::f32::consts::PI;
+    /// let mut viewport = Viewport::default();
+    ///
+    /// viewport.rotate(-PI / 2.0);
+    ///
+    /// assert_eq!(viewport.rotation(), 3.0 * PI / 2.0);
+    /// ```
+    pub fn rotate(&mut self, angle: f32) {
+        self.rotation = (self.rotation + angle).rem_euclid(2.0 * std::f32::consts::PI);
+    }
+
+    /// Returns whether the `Viewport` is symmetric about
+    /// the real axis, i.e. its center is on the axis and
+    /// it's not rotated, so that the rows of any screen
+    /// mirror each other around the middle one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Viewport};
+    /// assert!(Viewport::default().is_symmetric());
+    /// assert!(!Viewport::new(MandelPoint::new((-0.75, 0.1)), 20.0, 0.0).is_symmetric());
+    /// ```
+    pub fn is_symmetric(&self) -> bool {
+        self.center.1 == Fixed::default() && self.rotation == 0.0
+    }
+
+    /// Returns the length of the side of a
+    /// pixel on the Mandelbrot plane, for a
+    /// screen with the given `size`.
+    pub fn pixel_size(&self, size: (usize, usize)) -> f64 {
+        Y_DIFF / (self.zoom as f64 * size.1 as f64)
+    }
+
+    /// Returns the offset from the center of the `Viewport` of
+    /// the point of the plane at `pixel` (see `plane_point()`),
+    /// which is small enough for `f64` to be precise, however
+    /// deep the zoom is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Point, Viewport};
+    /// let viewport = Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e9, 0.0);
+    ///
+    /// assert_eq!(viewport.pixel_offset(Point::new((150, 100)), (300, 200)), (0.0, 0.0));
+    /// assert_eq!(viewport.pixel_offset(Point::new((0, 100)), (300, 200)), (-150.0 * 1e-11, 0.0));
+    /// ```
+    pub fn pixel_offset(&self, pixel: Point, size: (usize, usize)) -> (f64, f64) {
+        let pixel_size = self.pixel_size(size);
+        let (sin, cos) = (self.rotation as f64).sin_cos();
+
+        let (x, y) = pixel.coordinates();
+        let (x, y) = ((x as f64 - size.0 as f64 / 2.0) * pixel_size, (y as f64 - size.1 as f64 / 2.0) * pixel_size);
+
+        (x * cos - y * sin, x * sin + y * cos)
+    }
+
+    /// Maps a pixel of a screen with the given `size` (whose y axis
+    /// points up, as in `Point::to_mandel()`) to the corresponding
+    /// point on the Mandelbrot plane, in the arithmetic of `T`: the
+    /// offset of the pixel (see `pixel_offset()`) is added to the
+    /// center (see `precise_center()`) in `T`, so that the pixels
+    /// smaller than the precision of `f64` still differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{double_double::DoubleDouble, fixed::Fixed, utils::{Plottable, MandelPoint, Point, Viewport}};
+    /// let viewport = Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e20, 0.0);
+    /// let (size, pixel) = ((300, 200), Point::new((151, 100)));
+    ///
+    /// // a pixel is far smaller than the precision of the center
+    /// assert_eq!(viewport.plane_point::<f64>(pixel, size), (-0.75, 0.1));
+    ///
+    /// let (re, _) = viewport.plane_point::<DoubleDouble>(pixel, size);
+    ///
+    /// assert!(((re - DoubleDouble::from(-0.75)).to_f64() - 1e-22).abs() < 1e-28);
+    ///
+    /// // and so is the distance of a center from `-0.75`
+    /// let shifted = Viewport::with_precise_center(("-0.7499999999999999999999".parse().unwrap(), "0.1".parse().unwrap()), 1e20, 0.0);
+    /// let center = Point::new((150, 100));
+    ///
+    /// assert_eq!(shifted.center(), viewport.center());
+    /// assert_eq!(shifted.plane_point::<Fixed>(center, size), shifted.precise_center());
+    ///
+    /// let (re, _) = shifted.plane_point::<DoubleDouble>(center, size);
+    ///
+    /// assert!(((re - DoubleDouble::from(-0.75)).to_f64() - 1e-22).abs() < 1e-28);
+    /// ```
+    pub fn plane_point<T: Real>(&self, pixel: Point, size: (usize, usize)) -> (T, T) {
+        let (re, im) = self.center;
+        let (offset_re, offset_im) = self.pixel_offset(pixel, size);
+
+        (T::from_fixed(re) + T::from_f64(offset_re), T::from_fixed(im) + T::from_f64(offset_im))
+    }
+
+    /// Returns the (unrounded) coordinates of the pixel of a
+    /// screen with the given `size` that shows `point`, whose
+    /// y axis points up (see `MandelPoint::to_screen()`).
+    fn pixel_of(&self, point: (f64, f64), size: (usize, usize)) -> (f64, f64) {
+        let pixel_size = self.pixel_size(size);
+
+        // rotate in the opposite direction
+        let (sin, cos) = (-self.rotation as f64).sin_cos();
+
+        // the difference is small, but only `Fixed` has its digits
+        let (x, y) = (Fixed::from_f64(point.0).saturating_sub(self.center.0).to_f64(), Fixed::from_f64(point.1).saturating_sub(self.center.1).to_f64());
+
+        ((x * cos - y * sin) / pixel_size + size.0 as f64 / 2.0, (x * sin + y * cos) / pixel_size + size.1 as f64 / 2.0)
+    }
+
+    /// Returns the lines of a grid of the Mandelbrot plane covering a
+    /// screen with the given `size`, which shows the `Viewport`, as
+    /// segments between positions on the screen (see
+    /// `MandelPoint::to_position()`), so that they turn with the view;
+    /// the lines are a power of 10 apart, between 2 and 20 of them
+    /// spanning the height of the view.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::Viewport;
+    /// // the view spans `[-2, 1]x[-1, 1]`, which the
+    /// // lines at `-2`, `-1`, `0`, `1`, `-i`, `0i` and `i`
+    /// // cross (along with the corners of the screen)
+    /// let lines = Viewport::default().grid_lines((300, 200));
+    ///
+    /// assert_eq!(lines.len(), 7);
+    ///
+    /// // the imaginary axis
+    /// assert!(lines.iter().any(|&((x0, _), (x1, _))| x0 == 200.0 && x1 == 200.0));
+    /// ```
+    pub fn grid_lines(&self, size: (usize, usize)) -> Vec<((f32, f32), (f32, f32))> {
+        let pixel_size = self.pixel_size(size);
+
+        let step = 10f64.powf((size.1 as f64 * pixel_size / 2.0).log10().floor());
+
+        // the lines reach the corners of the screen, whatever the rotation
+        let radius = (size.0 as f64).hypot(size.1 as f64) / 2.0 * pixel_size;
+
+        let (re, im) = self.center().coordinates();
+
+        let multiples = |middle: f64| ((middle - radius) / step).ceil() as i64..=((middle + radius) / step).floor() as i64;
+
+        let vertical = multiples(re).map(|k| ((k as f64 * step, im - radius), (k as f64 * step, im + radius)));
+        let horizontal = multiples(im).map(|k| ((re - radius, k as f64 * step), (re + radius, k as f64 * step)));
+
+        let position = |point| {
+            let (x, y) = self.pixel_of(point, size);
+
+            match self.orientation {
+                Orientation::Math => (x as f32, size.1 as f32 - y as f32),
+                Orientation::Screen => (x as f32, y as f32),
+            }
+        };
+
+        vertical.chain(horizontal).map(|(from, to)| (position(from), position(to))).collect()
+    }
+
+    /// Returns the number of decimal digits (at least
+    /// `DISPLAY_PRECISION`, and at most the `fixed::DIGITS`
+    /// the center holds) needed to show the center of the
+    /// `Viewport` precisely enough to tell apart the pixels
+    /// of a screen with the given `size`, which only depends
+    /// on the zoom (see `format_point()`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Viewport};
+    /// assert_eq!(Viewport::default().center_precision((300, 200)), 6);
+    /// assert_eq!(Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e6, 0.0).center_precision((300, 200)), 9);
+    /// assert_eq!(Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e12, 0.0).center_precision((300, 200)), 15);
+    /// assert_eq!(Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e36, 0.0).center_precision((300, 200)), 37);
+    /// ```
+    pub fn center_precision(&self, size: (usize, usize)) -> usize {
+        let digits = (-self.pixel_size(size).log10()).ceil().max(0.0) as usize + 1;
+
+        digits.clamp(DISPLAY_PRECISION, fixed::DIGITS)
+    }
+
+    /// Formats `point` (given with the precision of the center,
+    /// like the result of `precise_center()`) as a complex number,
+    /// with as many decimal digits as the pixels of a screen with
+    /// the given `size` need (see `center_precision()`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::Viewport;
+    /// let deep = Viewport::with_precise_center(("-0.75".parse().unwrap(), "0.1".parse().unwrap()), 1e6, 0.0);
+    ///
+    /// assert_eq!(deep.format_point(deep.precise_center(), (300, 200)), "-0.750000000 + 0.100000000i");
+    ///
+    /// // digits beyond the precision of `f64`, which tell the pixels apart
+    /// let deeper = Viewport::with_precise_center(("-0.74364388703715870475219150611".parse().unwrap(), "0.13182590420531197049774".parse().unwrap()), 1e25, 0.0);
+    ///
+    /// assert_eq!(deeper.format_point(deeper.precise_center(), (300, 200)), "-0.7436438870371587047521915061 + 0.1318259042053119704977400000i");
+    /// ```
+    pub fn format_point(&self, point: (Fixed, Fixed), size: (usize, usize)) -> String {
+        let precision = self.center_precision(size);
+
+        let (re, im) = point;
+        let (sign, im) = if im < Fixed::default() { ('-', -im) } else { ('+', im) };
+
+        format!("{:.*} {} {:.*}i", precision, re, sign, precision, im)
+    }
+
+    /// Moves the center of the `Viewport` by `offset`,
+    /// which is expressed in heights of the view and
+    /// follows its rotation (e.g. `(0.0, 0.1)` always
+    /// moves the view up by a tenth of its height).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Viewport};
+    /// let mut viewport = Viewport::default();
+    ///
+    /// viewport.pan((0.5, 0.0));
+    ///
+    /// assert_eq!(viewport.center(), MandelPoint::new((0.5, 0.0)));
+    ///
+    /// // the moves far smaller than the precision of `f64` add up
+    /// let mut deep = Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e25, 0.0);
+    ///
+    /// deep.pan((0.5, 0.0));
+    ///
+    /// assert_eq!(deep.center(), MandelPoint::new((-0.75, 0.1)));
+    /// assert_ne!(deep.precise_center(), Viewport::new(MandelPoint::new((-0.75, 0.1)), 1e25, 0.0).precise_center());
+    /// ```
+    pub fn pan(&mut self, offset: (f32, f32)) {
+        let height = Y_DIFF / self.zoom as f64;
+        let (sin, cos) = (self.rotation as f64).sin_cos();
+
+        let (x, y) = (offset.0 as f64 * height, offset.1 as f64 * height);
+        let (re, im) = self.center;
+
+        self.center = (re.saturating_add(Fixed::from_f64(x * cos - y * sin)), im.saturating_add(Fixed::from_f64(x * sin + y * cos)));
+    }
+
+    /// Moves the `Viewport` so that its content follows a
+    /// pointer moved by `delta` pixels (with the y axis pointing
+    /// down) on a screen of the given `size`, as in a drag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Viewport};
+    /// let mut viewport = Viewport::default();
+    ///
+    /// viewport.drag((150.0, -100.0), (300, 200));
+    ///
+    /// assert_eq!(viewport.center(), MandelPoint::new((-2.0, -1.0)));
+    /// ```
+    pub fn drag(&mut self, delta: (f32, f32), size: (usize, usize)) {
+        let dy = match self.orientation {
+            Orientation::Math => -delta.1,
+            Orientation::Screen => delta.1,
+        };
+
+        self.pan((-delta.0 / size.1 as f32, -dy / size.1 as f32));
+    }
+
+    /// Returns the `Viewport` whose pixels, on a screen of the
+    /// given `size`, are the ones of this view moved by `offset`
+    /// pixels (with the y axis pointing down), like the jittered
+    /// samples of a pixel (see `render::jitter()`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, Point, Viewport};
+    /// let viewport = Viewport::default();
+    /// let shifted = viewport.shifted((0.5, 1.0), (300, 200));
+    ///
+    /// let point = |viewport: &Viewport, pixel| viewport.plane_point::<f64>(viewport.orient(Point::new(pixel), (300, 200)), (300, 200));
+    /// let ((re, im), (below_re, below_im)) = (point(&shifted, (10, 10)), point(&viewport, (10, 11)));
+    ///
+    /// // half a pixel to the right of the pixel below
+    /// assert!((re - below_re - 0.5 * viewport.pixel_size((300, 200))).abs() < 1e-12);
+    /// assert!((im - below_im).abs() < 1e-12);
+    /// ```
+    pub fn shifted(&self, offset: (f32, f32), size: (usize, usize)) -> Self {
+        let pixel_size = self.pixel_size(size);
+        let (sin, cos) = (self.rotation as f64).sin_cos();
+
+        // the y axis of the plane points up, by default
+        let flip = match self.orientation {
+            Orientation::Math => -1.0,
+            Orientation::Screen => 1.0,
+        };
+
+        let (x, y) = (offset.0 as f64 * pixel_size, flip * offset.1 as f64 * pixel_size);
+        let (re, im) = self.center;
+
+        Self { center: (re.saturating_add(Fixed::from_f64(x * cos - y * sin)), im.saturating_add(Fixed::from_f64(x * sin + y * cos))), ..*self }
+    }
+
+    /// Multiplies the zoom of the `Viewport` by `factor`,
+    /// keeping the same center; the zoom is left unchanged
+    /// if it would not be a positive finite number anymore.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::Viewport;
+    /// let mut viewport = Viewport::default();
+    ///
+    /// viewport.zoom_by(4.0);
+    /// viewport.zoom_by(0.0);
+    /// viewport.zoom_by(f32::MAX);
+    ///
+    /// assert_eq!(viewport.zoom(), 4.0);
+    /// ```
+    pub fn zoom_by(&mut self, factor: f32) {
+        let zoom = self.zoom * factor;
+
+        if zoom.is_finite() && zoom > 0.0 {
+            self.zoom = zoom;
+        }
+    }
+
+    /// Multiplies the zoom of the `Viewport` by `factor`
+    /// (see `zoom_by()`), keeping `point` (given with the
+    /// precision of the center, see `plane_point()`) at
+    /// the same position on the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::{fixed::Fixed, utils::{Plottable, MandelPoint, Viewport}};
+    /// let mut viewport = Viewport::default();
+    ///
+    /// viewport.zoom_at((Fixed::from_f64(0.5), Fixed::from_f64(1.0)), 2.0);
+    ///
+    /// assert_eq!(viewport.zoom(), 2.0);
+    /// assert_eq!(viewport.center(), MandelPoint::new((0.0, 0.5)));
+    /// ```
+    pub fn zoom_at(&mut self, point: (Fixed, Fixed), factor: f32) {
+        let zoom = self.zoom;
+
+        self.zoom_by(factor);
+
+        // the offsets from `point` shrink like the view, and
+        // they are small enough for `f64` to be precise
+        let shrink = zoom as f64 / self.zoom as f64;
+        let offset = |center: Fixed, point: Fixed| Fixed::from_f64(center.saturating_sub(point).to_f64() * shrink);
+
+        self.center = (point.0.saturating_add(offset(self.center.0, point.0)), point.1.saturating_add(offset(self.center.1, point.1)));
+    }
+
+    /// Moves and zooms the `Viewport` so that the rectangle with the
+    /// opposite `corners` (in pixels) of a screen of the given `size`
+    /// fills it, as large as it fits; the rectangles with no area
+    /// leave the `Viewport` unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Point, Viewport};
+    /// let mut viewport = Viewport::default();
+    ///
+    /// let center = viewport.orient(Point::new((200, 130)), (300, 200)).to_mandel(&viewport, (300, 200));
+    ///
+    /// viewport.zoom_to(((250.0, 160.0), (150.0, 100.0)), (300, 200));
+    ///
+    /// assert_eq!(viewport.zoom(), 3.0);
+    /// assert!((viewport.center() - center).norm() < 1e-6);
+    /// ```
+    pub fn zoom_to(&mut self, corners: ((f32, f32), (f32, f32)), size: (usize, usize)) {
+        let ((x0, y0), (x1, y1)) = corners;
+        let (width, height) = ((x1 - x0).abs(), (y1 - y0).abs());
+
+        if width == 0.0 || height == 0.0 {
+            return;
+        }
+
+        // bring the center of the rectangle to the one of the screen
+        self.drag((size.0 as f32 / 2.0 - (x0 + x1) / 2.0, size.1 as f32 / 2.0 - (y0 + y1) / 2.0), size);
+        self.zoom_by((size.0 as f32 / width).min(size.1 as f32 / height));
+    }
+
+    /// Checks that the `Viewport` can be shown on a screen
+    /// with the given `size`, which must be at least
+    /// `MIN_SIZE`x`MIN_SIZE`, without degenerating the
+    /// mapping between pixels and points of the plane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # pub use mandelbrust::utils::{Plottable, MandelPoint, Viewport, ViewportError};
+    /// assert_eq!(Viewport::default().check((300, 200)), Ok(()));
+    /// assert_eq!(Viewport::default().check((300, 0)), Err(ViewportError::TooSmall((300, 0))));
+    /// assert_eq!(Viewport::new(MandelPoint::new((0.0, 0.0)), 0.0, 0.0).check((300, 200)), Err(ViewportError::InvalidZoom(0.0)));
+    /// assert_eq!(Viewport::new(MandelPoint::new((0.0, 0.0)), 1e38, 0.0).check((300, 200)), Err(ViewportError::Degenerate));
+    /// ```
+    pub fn check(&self, size: (usize, usize)) -> Result<(), ViewportError> {
+        if size.0 < MIN_SIZE || size.1 < MIN_SIZE {
+            return Err(ViewportError::TooSmall(size));
+        }
+
+        if !(self.zoom.is_finite() && self.zoom > 0.0) {
+            return Err(ViewportError::InvalidZoom(self.zoom));
+        }
+
+        let pixel_size = self.pixel_size(size);
+
+        // the pixels must be representable, even by the most
+        // precise arithmetic, and their inverse is used by
+        // `MandelPoint::to_screen()`
+        if !(pixel_size >= (-(fixed::FRACTION_BITS as f64)).exp2() && (1.0 / pixel_size).is_finite()) {
+            return Err(ViewportError::Degenerate);
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned when a `Viewport`
+/// can't be shown on a screen (see `Viewport::check()`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewportError {
+    /// The screen is smaller than `MIN_SIZE`x`MIN_SIZE`.
+    TooSmall((usize, usize)),
+    /// The zoom is not a positive finite number.
+    InvalidZoom(f32),
+    /// The pixels are too small, or too big, to be represented.
+    Degenerate,
+}
+
+impl fmt::Display for ViewportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ViewportError::TooSmall((w, h)) => write!(f, "the size must be at least {}x{}, found {}x{}", MIN_SIZE, MIN_SIZE, w, h),
+            ViewportError::InvalidZoom(zoom) => write!(f, "the zoom must be a positive number, found `{}`", zoom),
+            ViewportError::Degenerate => write!(f, "the zoom is too extreme to be rendered"),
+        }
+    }
+}
+
+impl error::Error for ViewportError {}
+
+/// The `Display` implementation of `Viewport` shows it as
+/// a list of `key=value` fields, which can be parsed back
+/// into the same `Viewport` (the rotation is in degrees, and
+/// the orientation is only shown when it's not the default one).
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::utils::{Orientation, Viewport};
+/// let mut viewport = Viewport::default();
+///
+/// assert_eq!(viewport.to_string(), "center=-0.5+0i, zoom=1, rotation=0");
+///
+/// viewport.set_orientation(Orientation::Screen);
+///
+/// assert_eq!(viewport.to_string(), "center=-0.5+0i, zoom=1, rotation=0, orientation=screen");
+/// ```
+impl fmt::Display for Viewport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the shortest representation that
+        // round-trips, instead of `Display` of
+        // `MandelPoint`, which truncates the digits
+        let (re, im) = self.center;
+        let (sign, im) = if im < Fixed::default() { ('-', -im) } else { ('+', im) };
+
+        write!(f, "center={}{}{}i, zoom={}, rotation={}", re, sign, im, self.zoom, self.rotation.to_degrees())?;
+
+        if self.orientation != Orientation::default() {
+            write!(f, ", orientation={}", self.orientation)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Viewport {
+    /// Returns `VIEWPORT_DEFAULT`.
+    fn default() -> Self {
+        VIEWPORT_DEFAULT
     }
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+use std::{env, io, path::{Path, PathBuf}, process::Command};
+
+/// Returns the path where the wallpaper is saved, in the data
+/// directory of the user (so that it survives a reboot), or
+/// in the temporary directory if it can't be found.
+pub fn wallpaper_path() -> PathBuf {
+    let data_dir = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| Path::new(&home).join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share")))
+    };
+
+    data_dir.unwrap_or_else(env::temp_dir).join("mandelbrust").join("wallpaper.png")
+}
+
+/// Runs `command`, failing if it can't be
+/// started or if it exits unsuccessfully.
+fn run(command: &mut Command) -> io::Result<()> {
+    let status = command.status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{:?} exited with {}", command, status)))
+    }
+}
+
+/// Sets the image at `path` (which must be absolute) as the
+/// desktop wallpaper, through AppleScript on macOS,
+/// `SystemParametersInfo` (called from PowerShell) on Windows,
+/// and `gsettings` (GNOME and derivatives) or `feh` elsewhere.
+pub fn set_wallpaper(path: &Path) -> io::Result<()> {
+    let path = path.to_string_lossy();
+
+    if cfg!(target_os = "macos") {
+        run(Command::new("osascript").args([
+            "-e",
+            &format!("tell application \"System Events\" to tell every desktop to set picture to \"{}\"", path.replace('"', "\\\"")),
+        ]))
+    } else if cfg!(target_os = "windows") {
+        let script = format!(
+            "Add-Type -TypeDefinition 'using System.Runtime.InteropServices; public class Wallpaper {{ \
+             [DllImport(\"user32.dll\", CharSet = CharSet.Unicode)] \
+             public static extern int SystemParametersInfo(int action, int param, string value, int flags); }}'; \
+             if ([Wallpaper]::SystemParametersInfo(20, 0, '{}', 3) -eq 0) {{ exit 1 }}",
+            path.replace('\'', "''"),
+        );
+
+        run(Command::new("powershell").args(["-NoProfile", "-Command", &script]))
+    } else {
+        let uri = format!("file://{}", path);
+
+        let gnome = run(Command::new("gsettings").args(["set", "org.gnome.desktop.background", "picture-uri", &uri]));
+
+        match gnome {
+            Ok(()) => {
+                // the dark theme has its own wallpaper, and
+                // older versions of GNOME don't have the key
+                let _ = run(Command::new("gsettings").args(["set", "org.gnome.desktop.background", "picture-uri-dark", &uri]));
+
+                Ok(())
+            }
+            Err(_) => run(Command::new("feh").args(["--bg-fill", &path])),
+        }
+    }
+}
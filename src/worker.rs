@@ -0,0 +1,184 @@
+use crate::{fractal::Fractal, kernel::EscapeResult, precision::Precision, render, scheduler::Scheduler, utils::*};
+use std::{
+    ops::Range,
+    sync::{atomic::{AtomicU64, Ordering}, mpsc::{self, Receiver, Sender}, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The number of rows iterated by `RenderWorker` between
+/// two checks for a newer job, which bounds how long a
+/// canceled job keeps running.
+pub const BAND_HEIGHT: usize = 16;
+
+/// A view for `RenderWorker` to iterate.
+#[derive(Debug, Clone)]
+pub struct Job {
+    /// The fractal that is iterated.
+    pub fractal: Arc<dyn Fractal>,
+    /// The view that is iterated.
+    pub viewport: Viewport,
+    /// The size (in pixels) of the screen.
+    pub size: (usize, usize),
+    /// The maximum number of iterations of each pixel.
+    pub max_iterations: usize,
+    /// The arithmetic the pixels are iterated with.
+    pub precision: Precision,
+    /// The factors by which the coarse passes, iterated
+    /// before the full one, are smaller than `size`.
+    pub coarse: Vec<usize>,
+    /// The samples of each pixel that are iterated (up to
+    /// `render::MAX_SAMPLES`), one pass each: the first is the
+    /// full pass (with the coarse ones before it), and each of
+    /// the others is a full pass of the view shifted by a
+    /// fraction of a pixel (see `Viewport::shifted()`), by the
+    /// offset `render::jitter()` gives to the pixel at `(0, 0)`.
+    pub samples: Range<usize>,
+}
+
+/// A pass of a `Job`, iterated by `RenderWorker`.
+#[derive(Debug, Clone)]
+pub struct Pass {
+    /// The number returned by `RenderWorker::submit()` for the job.
+    pub job: u64,
+    /// The factor by which the pass is smaller than the
+    /// job, which is `1` for the last (full) pass.
+    pub factor: usize,
+    /// The size (in pixels) of the pass.
+    pub size: (usize, usize),
+    /// The sample of each pixel the pass holds (see
+    /// `Job::samples`), `0` for the coarse passes.
+    pub sample: usize,
+    /// The results of the pixels, row by row, from top to bottom.
+    pub results: Vec<EscapeResult>,
+    /// The time since the job was started.
+    pub elapsed: Duration,
+}
+
+/// A thread iterating the jobs it's given, pass by pass (see
+/// `Job`), with a `Scheduler`, so that the event loop never
+/// waits for them: a new job cancels the one in progress,
+/// whose remaining passes are not iterated.
+///
+/// # Examples
+///
+/// ```
+/// # pub use mandelbrust::{fractal::Mandelbrot, precision::Precision, render, scheduler::Scheduler, utils::Viewport, worker::{Job, RenderWorker}};
+/// # use std::sync::Arc;
+/// let worker = RenderWorker::spawn(Scheduler::default());
+///
+/// let job = Job { fractal: Arc::new(Mandelbrot), viewport: Viewport::default(), size: (30, 20), max_iterations: 128, precision: Precision::Double, coarse: vec![4], samples: 0..2 };
+///
+/// let id = worker.submit(job);
+///
+/// let coarse = worker.recv().unwrap();
+/// let full = worker.recv().unwrap();
+/// let jittered = worker.recv().unwrap();
+///
+/// assert_eq!((coarse.job, coarse.factor, coarse.size), (id, 4, (8, 5)));
+/// assert_eq!((full.job, full.factor, full.sample), (id, 1, 0));
+/// assert_eq!((jittered.job, jittered.factor, jittered.sample), (id, 1, 1));
+///
+/// let iterations = |results: &[mandelbrust::kernel::EscapeResult]| results.iter().map(|result| result.iterations).collect::<Vec<_>>();
+///
+/// let shifted = Viewport::default().shifted(render::jitter((0, 0), 1), (30, 20));
+///
+/// assert_eq!(iterations(&full.results), iterations(&render::escape_results(&Mandelbrot, &Viewport::default(), (30, 20), 128)));
+/// assert_eq!(iterations(&jittered.results), iterations(&render::escape_results(&Mandelbrot, &shifted, (30, 20), 128)));
+/// ```
+#[derive(Debug)]
+pub struct RenderWorker {
+    jobs: Sender<(u64, Job)>,
+    passes: Receiver<Pass>,
+    latest: Arc<AtomicU64>,
+}
+
+impl RenderWorker {
+    /// Starts the thread, which iterates with `scheduler`,
+    /// and stops once the worker is dropped.
+    pub fn spawn(mut scheduler: Scheduler) -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<(u64, Job)>();
+        let (pass_sender, passes) = mpsc::channel();
+
+        let latest = Arc::new(AtomicU64::new(0));
+        let current = Arc::clone(&latest);
+
+        thread::spawn(move || {
+            while let Ok(mut next) = job_receiver.recv() {
+                // only the newest of the queued jobs is worth iterating
+                while let Ok(newer) = job_receiver.try_recv() {
+                    next = newer;
+                }
+
+                let (id, job) = next;
+                let start = Instant::now();
+
+                let canceled = || current.load(Ordering::Relaxed) != id;
+
+                // the coarse passes only come before the full one
+                let coarse = if job.samples.start == 0 { job.coarse.as_slice() } else { &[] };
+
+                let passes = coarse.iter().map(|&factor| (factor, 0)).chain(job.samples.clone().map(|sample| (1, sample)));
+
+                for (factor, sample) in passes {
+                    let size = (job.size.0.div_ceil(factor), job.size.1.div_ceil(factor));
+
+                    let viewport = match sample {
+                        0 => job.viewport,
+                        _ => job.viewport.shifted(render::jitter((0, 0), sample), size),
+                    };
+
+                    let mut results = Vec::with_capacity(size.0 * size.1);
+
+                    // the shifted views are hardly ever symmetric
+                    let rows = render::distinct_rows(job.fractal.as_ref(), &viewport, size);
+
+                    for top in rows.clone().step_by(BAND_HEIGHT) {
+                        if canceled() {
+                            break;
+                        }
+
+                        results.extend(scheduler.escape_rows(job.fractal.as_ref(), &viewport, size, top..(top + BAND_HEIGHT).min(rows.end), job.max_iterations, job.precision));
+                    }
+
+                    if canceled() {
+                        log::trace!("canceled the job {} at the pass 1/{} (sample {})", id, factor, sample);
+
+                        break;
+                    }
+
+                    render::mirror_rows(&mut results, size);
+
+                    // the worker was dropped
+                    if pass_sender.send(Pass { job: id, factor, size, sample, results, elapsed: start.elapsed() }).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self { jobs, passes, latest }
+    }
+
+    /// Starts iterating `job`, canceling the one in progress,
+    /// and returns the number its passes are marked with.
+    pub fn submit(&self, job: Job) -> u64 {
+        let id = self.latest.fetch_add(1, Ordering::Relaxed) + 1;
+
+        // the thread only stops when the worker is dropped
+        let _ = self.jobs.send((id, job));
+
+        id
+    }
+
+    /// Returns the next pass that was iterated, if there is one,
+    /// without waiting (the passes of the canceled jobs included).
+    pub fn try_recv(&self) -> Option<Pass> {
+        self.passes.try_recv().ok()
+    }
+
+    /// Waits for the next pass that is iterated.
+    pub fn recv(&self) -> Option<Pass> {
+        self.passes.recv().ok()
+    }
+}